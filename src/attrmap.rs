@@ -5,9 +5,13 @@
 //!
 
 use crate::sealed;
+use crate::OdsError;
 use color::Rgb;
+use std::cmp::Ordering;
 use std::collections::{hash_map, HashMap};
 use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
 use string_cache::DefaultAtom;
 
 /// Container type for attributes.
@@ -94,14 +98,105 @@ impl<'a> Iterator for AttrMapIter<'a> {
     }
 }
 
+/// Shadow as x-offset, y-offset, optional blur and color.
+pub type Shadow = (Length, Length, Option<Length>, Rgb<u8>);
+
+/// A style attribute with a value that can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttrParseError {
+    attr: String,
+    value: String,
+}
+
+impl AttrParseError {
+    /// Name of the attribute.
+    pub fn attr(&self) -> &str {
+        &self.attr
+    }
+
+    /// The value that was found.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Display for AttrParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f, "Invalid value for {}: {}", self.attr, self.value)
+    }
+}
+
+impl std::error::Error for AttrParseError {}
+
+impl From<AttrParseError> for OdsError {
+    fn from(err: AttrParseError) -> OdsError {
+        OdsError::Ods(err.to_string())
+    }
+}
+
 /// Value type for angles.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Angles in different units are compared after conversion to the unit
+/// of the left operand.
+/// The result of an arithmetic operation has the unit of the left operand.
+#[derive(Debug, Clone, Copy)]
 pub enum Angle {
     Deg(f64),
     Grad(f64),
     Rad(f64),
 }
 
+impl Angle {
+    /// The value without unit.
+    fn value(&self) -> f64 {
+        match self {
+            Angle::Deg(v) | Angle::Grad(v) | Angle::Rad(v) => *v,
+        }
+    }
+
+    /// Degrees per unit.
+    fn factor(&self) -> f64 {
+        match self {
+            Angle::Deg(_) => 1.0,
+            Angle::Grad(_) => 0.9,
+            Angle::Rad(_) => 180.0 / std::f64::consts::PI,
+        }
+    }
+
+    /// Same unit, new value.
+    fn with_value(&self, v: f64) -> Angle {
+        match self {
+            Angle::Deg(_) => Angle::Deg(v),
+            Angle::Grad(_) => Angle::Grad(v),
+            Angle::Rad(_) => Angle::Rad(v),
+        }
+    }
+
+    /// Value of other in the unit of self.
+    fn value_of(&self, other: Angle) -> f64 {
+        if std::mem::discriminant(self) == std::mem::discriminant(&other) {
+            other.value()
+        } else {
+            other.value() * (other.factor() / self.factor())
+        }
+    }
+
+    /// Angle in degrees.
+    pub fn to_deg(&self) -> f64 {
+        self.value() * self.factor()
+    }
+
+    /// Angle in grad.
+    pub fn to_grad(&self) -> f64 {
+        self.to_deg() / 0.9
+    }
+
+    /// Angle in radians.
+    pub fn to_rad(&self) -> f64 {
+        self.to_deg().to_radians()
+    }
+}
+
 impl Display for Angle {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
@@ -112,6 +207,76 @@ impl Display for Angle {
     }
 }
 
+/// Parses an ODF angle. Without unit it is in degrees.
+impl FromStr for Angle {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let idx = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+        let (value, unit) = s.split_at(idx);
+        let value = value.parse::<f64>()?;
+        match unit {
+            "" | "deg" => Ok(Angle::Deg(value)),
+            "grad" => Ok(Angle::Grad(value)),
+            "rad" => Ok(Angle::Rad(value)),
+            _ => Err(OdsError::Ods(format!("Unknown angle unit {}", s))),
+        }
+    }
+}
+
+impl PartialEq for Angle {
+    fn eq(&self, other: &Angle) -> bool {
+        self.value() == self.value_of(*other)
+    }
+}
+
+impl PartialOrd for Angle {
+    fn partial_cmp(&self, other: &Angle) -> Option<Ordering> {
+        self.value().partial_cmp(&self.value_of(*other))
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+
+    fn add(self, rhs: Angle) -> Angle {
+        self.with_value(self.value() + self.value_of(rhs))
+    }
+}
+
+impl Sub for Angle {
+    type Output = Angle;
+
+    fn sub(self, rhs: Angle) -> Angle {
+        self.with_value(self.value() - self.value_of(rhs))
+    }
+}
+
+impl Mul<f64> for Angle {
+    type Output = Angle;
+
+    fn mul(self, rhs: f64) -> Angle {
+        self.with_value(self.value() * rhs)
+    }
+}
+
+impl Div<f64> for Angle {
+    type Output = Angle;
+
+    fn div(self, rhs: f64) -> Angle {
+        self.with_value(self.value() / rhs)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Angle {
+        self.with_value(-self.value())
+    }
+}
+
 /// deg angles. 360°
 #[macro_export]
 macro_rules! deg {
@@ -137,7 +302,12 @@ macro_rules! rad {
 }
 
 /// Value type for lengths.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// For conversions Em is taken as 12pt, the default font size, and Px as
+/// 1/96". Lengths in different units are compared after conversion to
+/// the unit of the left operand. The result of an arithmetic operation has the unit of the left
+/// operand.
+#[derive(Debug, Clone, Copy)]
 pub enum Length {
     Cm(f64),
     Mm(f64),
@@ -145,6 +315,81 @@ pub enum Length {
     Pt(f64),
     Pc(f64),
     Em(f64),
+    Px(f64),
+}
+
+impl Length {
+    /// The value without unit.
+    fn value(&self) -> f64 {
+        match self {
+            Length::Cm(v)
+            | Length::Mm(v)
+            | Length::In(v)
+            | Length::Pt(v)
+            | Length::Pc(v)
+            | Length::Em(v)
+            | Length::Px(v) => *v,
+        }
+    }
+
+    /// Millimeters per unit.
+    fn factor(&self) -> f64 {
+        match self {
+            Length::Cm(_) => 10.0,
+            Length::Mm(_) => 1.0,
+            Length::In(_) => 25.4,
+            Length::Pt(_) => 25.4 / 72.0,
+            Length::Pc(_) | Length::Em(_) => 25.4 / 6.0,
+            Length::Px(_) => 25.4 / 96.0,
+        }
+    }
+
+    /// Same unit, new value.
+    fn with_value(&self, v: f64) -> Length {
+        match self {
+            Length::Cm(_) => Length::Cm(v),
+            Length::Mm(_) => Length::Mm(v),
+            Length::In(_) => Length::In(v),
+            Length::Pt(_) => Length::Pt(v),
+            Length::Pc(_) => Length::Pc(v),
+            Length::Em(_) => Length::Em(v),
+            Length::Px(_) => Length::Px(v),
+        }
+    }
+
+    /// Value of other in the unit of self.
+    fn value_of(&self, other: Length) -> f64 {
+        if std::mem::discriminant(self) == std::mem::discriminant(&other) {
+            other.value()
+        } else {
+            other.value() * (other.factor() / self.factor())
+        }
+    }
+
+    /// Length in centimeters.
+    pub fn to_cm(&self) -> f64 {
+        self.to_mm() / 10.0
+    }
+
+    /// Length in millimeters.
+    pub fn to_mm(&self) -> f64 {
+        self.value() * self.factor()
+    }
+
+    /// Length in inches.
+    pub fn to_in(&self) -> f64 {
+        self.to_mm() / 25.4
+    }
+
+    /// Length in points.
+    pub fn to_pt(&self) -> f64 {
+        self.to_in() * 72.0
+    }
+
+    /// Length in pixels at 96dpi.
+    pub fn to_px(&self) -> f64 {
+        self.to_in() * 96.0
+    }
 }
 
 impl Display for Length {
@@ -156,10 +401,85 @@ impl Display for Length {
             Length::Pt(v) => write!(f, "{}pt", v),
             Length::Pc(v) => write!(f, "{}pc", v),
             Length::Em(v) => write!(f, "{}em", v),
+            Length::Px(v) => write!(f, "{}px", v),
+        }
+    }
+}
+
+/// Parses an ODF length like "10.198cm".
+impl FromStr for Length {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let idx = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+        let (value, unit) = s.split_at(idx);
+        let value = value.parse::<f64>()?;
+        match unit {
+            "cm" => Ok(Length::Cm(value)),
+            "mm" => Ok(Length::Mm(value)),
+            "in" | "inch" => Ok(Length::In(value)),
+            "pt" => Ok(Length::Pt(value)),
+            "pc" => Ok(Length::Pc(value)),
+            "em" => Ok(Length::Em(value)),
+            "px" => Ok(Length::Px(value)),
+            _ => Err(OdsError::Ods(format!("Unknown length unit {}", s))),
         }
     }
 }
 
+impl PartialEq for Length {
+    fn eq(&self, other: &Length) -> bool {
+        self.value() == self.value_of(*other)
+    }
+}
+
+impl PartialOrd for Length {
+    fn partial_cmp(&self, other: &Length) -> Option<Ordering> {
+        self.value().partial_cmp(&self.value_of(*other))
+    }
+}
+
+impl Add for Length {
+    type Output = Length;
+
+    fn add(self, rhs: Length) -> Length {
+        self.with_value(self.value() + self.value_of(rhs))
+    }
+}
+
+impl Sub for Length {
+    type Output = Length;
+
+    fn sub(self, rhs: Length) -> Length {
+        self.with_value(self.value() - self.value_of(rhs))
+    }
+}
+
+impl Mul<f64> for Length {
+    type Output = Length;
+
+    fn mul(self, rhs: f64) -> Length {
+        self.with_value(self.value() * rhs)
+    }
+}
+
+impl Div<f64> for Length {
+    type Output = Length;
+
+    fn div(self, rhs: f64) -> Length {
+        self.with_value(self.value() / rhs)
+    }
+}
+
+impl Neg for Length {
+    type Output = Length;
+
+    fn neg(self) -> Length {
+        self.with_value(-self.value())
+    }
+}
+
 /// Centimeters.
 #[macro_export]
 macro_rules! cm {
@@ -208,6 +528,14 @@ macro_rules! em {
     };
 }
 
+/// Pixel. 1/96"
+#[macro_export]
+macro_rules! px {
+    ($l:expr) => {
+        Length::Px($l as f64)
+    };
+}
+
 /// Font pitch.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum FontPitch {
@@ -224,6 +552,18 @@ impl Display for FontPitch {
     }
 }
 
+impl FromStr for FontPitch {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "variable" => Ok(FontPitch::Variable),
+            "fixed" => Ok(FontPitch::Fixed),
+            _ => Err(OdsError::Ods(format!("Invalid FontPitch {}", s))),
+        }
+    }
+}
+
 /// Attributes for a FontFaceDecl
 pub trait AttrFontDecl
 where
@@ -248,6 +588,21 @@ where
     fn set_font_pitch(&mut self, pitch: FontPitch) {
         self.set_attr("style:font-pitch", pitch.to_string());
     }
+
+    /// External font family name.
+    fn font_family(&self) -> Option<&String> {
+        self.attr("svg:font-family")
+    }
+
+    /// System generic name.
+    fn font_family_generic(&self) -> Option<&String> {
+        self.attr("style:font-family-generic")
+    }
+
+    /// Font pitch.
+    fn font_pitch(&self) -> Result<Option<FontPitch>, AttrParseError> {
+        parse_attr(self, "style:font-pitch")
+    }
 }
 
 /// Margin attributes.
@@ -274,6 +629,31 @@ where
     fn set_margin_top(&mut self, margin: Length) {
         self.set_attr("fo:margin-top", margin.to_string());
     }
+
+    /// Margin.
+    fn margin(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:margin")
+    }
+
+    /// Margin.
+    fn margin_bottom(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:margin-bottom")
+    }
+
+    /// Margin.
+    fn margin_left(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:margin-left")
+    }
+
+    /// Margin.
+    fn margin_right(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:margin-right")
+    }
+
+    /// Margin.
+    fn margin_top(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:margin-top")
+    }
 }
 
 /// Padding attributes.
@@ -300,6 +680,31 @@ where
     fn set_padding_top(&mut self, padding: Length) {
         self.set_attr("fo:padding-top", padding.to_string());
     }
+
+    /// Padding.
+    fn padding(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:padding")
+    }
+
+    /// Padding.
+    fn padding_bottom(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:padding-bottom")
+    }
+
+    /// Padding.
+    fn padding_left(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:padding-left")
+    }
+
+    /// Padding.
+    fn padding_right(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:padding-right")
+    }
+
+    /// Padding.
+    fn padding_top(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:padding-top")
+    }
 }
 
 /// Background color.
@@ -311,6 +716,14 @@ where
     fn set_background_color(&mut self, color: Rgb<u8>) {
         self.set_attr("fo:background-color", color_string(color));
     }
+
+    /// Background color. None if it is transparent.
+    fn background_color(&self) -> Result<Option<Rgb<u8>>, AttrParseError> {
+        match self.attr("fo:background-color").map(|v| v.as_str()) {
+            Some("transparent") => Ok(None),
+            _ => parse_attr_with(self, "fo:background-color", parse_color),
+        }
+    }
 }
 
 /// Minimum height.
@@ -325,6 +738,24 @@ where
     fn set_min_height_percent(&mut self, height: f64) {
         self.set_attr("fo:min-height", percent_string(height));
     }
+
+    /// Minimum height. None if it is given in percent.
+    fn min_height(&self) -> Result<Option<Length>, AttrParseError> {
+        if is_percent(self.attr("fo:min-height")) {
+            Ok(None)
+        } else {
+            parse_attr(self, "fo:min-height")
+        }
+    }
+
+    /// Minimum height in percent. None if it is given as a length.
+    fn min_height_percent(&self) -> Result<Option<f64>, AttrParseError> {
+        if is_percent(self.attr("fo:min-height")) {
+            parse_attr_with(self, "fo:min-height", parse_percent)
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 /// Various border styles.
@@ -359,6 +790,26 @@ impl Display for Border {
     }
 }
 
+impl FromStr for Border {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Border::None),
+            "hidden" => Ok(Border::Hidden),
+            "dotted" => Ok(Border::Dotted),
+            "dashed" => Ok(Border::Dashed),
+            "solid" => Ok(Border::Solid),
+            "double" => Ok(Border::Double),
+            "groove" => Ok(Border::Groove),
+            "ridge" => Ok(Border::Ridge),
+            "inset" => Ok(Border::Inset),
+            "outset" => Ok(Border::Outset),
+            _ => Err(OdsError::Ods(format!("Invalid Border {}", s))),
+        }
+    }
+}
+
 /// Border attributes.
 pub trait AttrFoBorder
 where
@@ -428,6 +879,68 @@ where
             border_line_width_string(inner, spacing, outer),
         );
     }
+
+    /// Border style all four sides.
+    fn border(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, AttrParseError> {
+        parse_attr_with(self, "fo:border", parse_border)
+    }
+
+    /// Border style.
+    fn border_bottom(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, AttrParseError> {
+        parse_attr_with(self, "fo:border-bottom", parse_border)
+    }
+
+    /// Border style.
+    fn border_top(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, AttrParseError> {
+        parse_attr_with(self, "fo:border-top", parse_border)
+    }
+
+    /// Border style.
+    fn border_left(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, AttrParseError> {
+        parse_attr_with(self, "fo:border-left", parse_border)
+    }
+
+    /// Border style.
+    fn border_right(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, AttrParseError> {
+        parse_attr_with(self, "fo:border-right", parse_border)
+    }
+
+    /// Widths for double borders.
+    fn border_line_width(&self) -> Result<Option<(Length, Length, Length)>, AttrParseError> {
+        parse_attr_with(self, "style:border-line-width", parse_border_line_width)
+    }
+
+    /// Widths for double borders.
+    fn border_line_width_bottom(&self) -> Result<Option<(Length, Length, Length)>, AttrParseError> {
+        parse_attr_with(
+            self,
+            "style:border-line-width-bottom",
+            parse_border_line_width,
+        )
+    }
+
+    /// Widths for double borders.
+    fn border_line_width_left(&self) -> Result<Option<(Length, Length, Length)>, AttrParseError> {
+        parse_attr_with(
+            self,
+            "style:border-line-width-left",
+            parse_border_line_width,
+        )
+    }
+
+    /// Widths for double borders.
+    fn border_line_width_right(&self) -> Result<Option<(Length, Length, Length)>, AttrParseError> {
+        parse_attr_with(
+            self,
+            "style:border-line-width-right",
+            parse_border_line_width,
+        )
+    }
+
+    /// Widths for double borders.
+    fn border_line_width_top(&self) -> Result<Option<(Length, Length, Length)>, AttrParseError> {
+        parse_attr_with(self, "style:border-line-width-top", parse_border_line_width)
+    }
 }
 
 /// Page breaks.
@@ -449,6 +962,19 @@ impl Display for PageBreak {
     }
 }
 
+impl FromStr for PageBreak {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(PageBreak::Auto),
+            "column" => Ok(PageBreak::Column),
+            "page" => Ok(PageBreak::Page),
+            _ => Err(OdsError::Ods(format!("Invalid PageBreak {}", s))),
+        }
+    }
+}
+
 /// Page breaks.
 pub trait AttrFoBreak
 where
@@ -463,6 +989,16 @@ where
     fn set_break_after(&mut self, pagebreak: PageBreak) {
         self.set_attr("fo:break-after", pagebreak.to_string());
     }
+
+    /// page-break
+    fn break_before(&self) -> Result<Option<PageBreak>, AttrParseError> {
+        parse_attr(self, "fo:break-before")
+    }
+
+    /// page-break
+    fn break_after(&self) -> Result<Option<PageBreak>, AttrParseError> {
+        parse_attr(self, "fo:break-after")
+    }
 }
 
 /// Text keep together.
@@ -482,6 +1018,18 @@ impl Display for TextKeep {
     }
 }
 
+impl FromStr for TextKeep {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(TextKeep::Auto),
+            "always" => Ok(TextKeep::Always),
+            _ => Err(OdsError::Ods(format!("Invalid TextKeep {}", s))),
+        }
+    }
+}
+
 /// Keep with next.
 pub trait AttrFoKeepWithNext
 where
@@ -491,6 +1039,11 @@ where
     fn set_keep_with_next(&mut self, keep_with_next: TextKeep) {
         self.set_attr("fo:keep-with-next", keep_with_next.to_string());
     }
+
+    /// Keep with next.
+    fn keep_with_next(&self) -> Result<Option<TextKeep>, AttrParseError> {
+        parse_attr(self, "fo:keep-with-next")
+    }
 }
 
 /// Keep together.
@@ -502,6 +1055,11 @@ where
     fn set_keep_together(&mut self, keep_together: TextKeep) {
         self.set_attr("fo:keep-together", keep_together.to_string());
     }
+
+    /// Keep together.
+    fn keep_together(&self) -> Result<Option<TextKeep>, AttrParseError> {
+        parse_attr(self, "fo:keep-together")
+    }
 }
 
 /// Height attribute.
@@ -512,6 +1070,11 @@ where
     fn set_height(&mut self, height: Length) {
         self.set_attr("svg:height", height.to_string());
     }
+
+    /// Height.
+    fn height(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "svg:height")
+    }
 }
 
 /// Spacing for header/footer.
@@ -522,6 +1085,11 @@ where
     fn set_dynamic_spacing(&mut self, dynamic: bool) {
         self.set_attr("style:dynamic-spacing", dynamic.to_string());
     }
+
+    /// Dynamic spacing.
+    fn dynamic_spacing(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "style:dynamic-spacing")
+    }
 }
 
 /// Shadows. Only a single shadow supported here.
@@ -541,6 +1109,12 @@ where
             shadow_string(x_offset, y_offset, blur, color),
         );
     }
+
+    /// Shadow as x-offset, y-offset, blur and color. None if there is no
+    /// shadow.
+    fn shadow(&self) -> Result<Option<Shadow>, AttrParseError> {
+        parse_attr_with(self, "style:shadow", parse_shadow).map(Option::flatten)
+    }
 }
 
 /// Writing modes.
@@ -571,14 +1145,81 @@ impl Display for WritingMode {
     }
 }
 
+impl FromStr for WritingMode {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lr-tb" => Ok(WritingMode::LrTb),
+            "rl-tb" => Ok(WritingMode::RlTb),
+            "tb-rl" => Ok(WritingMode::TbRl),
+            "tb-lr" => Ok(WritingMode::TbLr),
+            "lr" => Ok(WritingMode::Lr),
+            "rl" => Ok(WritingMode::Rl),
+            "tb" => Ok(WritingMode::Tb),
+            "page" => Ok(WritingMode::Page),
+            _ => Err(OdsError::Ods(format!(
+                "Unknown value for style:writing-mode {}",
+                s
+            ))),
+        }
+    }
+}
+
 /// Writing mode.
 pub trait AttrStyleWritingMode
 where
     Self: AttrMap,
 {
+    /// Writing mode. For a table rl-tb mirrors the sheet, column A
+    /// is on the right side.
     fn set_writing_mode(&mut self, writing_mode: WritingMode) {
         self.set_attr("style:writing-mode", writing_mode.to_string());
     }
+
+    /// Writing mode.
+    fn writing_mode(&self) -> Option<WritingMode> {
+        self.attr("style:writing-mode").and_then(|v| v.parse().ok())
+    }
+}
+
+/// Table specific attributes.
+pub trait AttrTable
+where
+    Self: AttrMap,
+{
+    /// Color of the sheet tab.
+    fn set_tab_color(&mut self, color: Rgb<u8>) {
+        self.set_attr("tableooo:tab-color", color_string(color));
+    }
+
+    /// Color of the sheet tab.
+    fn tab_color(&self) -> Option<Rgb<u8>> {
+        self.attr("tableooo:tab-color")
+            .and_then(|v| parse_color(v).ok())
+    }
+
+    /// Shows or hides the table.
+    fn set_display(&mut self, display: bool) {
+        self.set_attr("table:display", display.to_string());
+    }
+
+    /// Shows or hides the table. Defaults to true.
+    fn display(&self) -> bool {
+        self.attr("table:display").map(|v| v.as_str()) != Some("false")
+    }
+
+    /// Allows page breaks within the table.
+    fn set_may_break_between_rows(&mut self, may_break: bool) {
+        self.set_attr("style:may-break-between-rows", may_break.to_string());
+    }
+
+    /// Allows page breaks within the table. Defaults to true.
+    fn may_break_between_rows(&self) -> bool {
+        self.attr("style:may-break-between-rows")
+            .map(|v| v.as_str())
+            != Some("false")
+    }
 }
 
 /// Table row specific attributes.
@@ -597,6 +1238,21 @@ where
     fn set_use_optimal_row_height(&mut self, opt: bool) {
         self.set_attr("style:use-optimal-row-height", opt.to_string());
     }
+
+    /// Minimum row height.
+    fn min_row_height(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "style:min-row-height")
+    }
+
+    /// Row height.
+    fn row_height(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "style:row-height")
+    }
+
+    /// Use the optimal row height.
+    fn use_optimal_row_height(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "style:use-optimal-row-height")
+    }
 }
 
 /// Table columns specific attributes.
@@ -618,6 +1274,21 @@ where
     fn set_use_optimal_col_width(&mut self, opt: bool) {
         self.set_attr("style:use-optimal-column-width", opt.to_string());
     }
+
+    /// Relative weights for the column width
+    fn rel_col_width(&self) -> Result<Option<f64>, AttrParseError> {
+        parse_attr_with(self, "style:rel-column-width", parse_rel_width)
+    }
+
+    /// Column width
+    fn col_width(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "style:column-width")
+    }
+
+    /// Override switch for the column width.
+    fn use_optimal_col_width(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "style:use-optimal-column-width")
+    }
 }
 
 /// Text wrapping.
@@ -636,6 +1307,18 @@ impl Display for WrapOption {
     }
 }
 
+impl FromStr for WrapOption {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "no-wrap" => Ok(WrapOption::NoWrap),
+            "wrap" => Ok(WrapOption::Wrap),
+            _ => Err(OdsError::Ods(format!("Invalid WrapOption {}", s))),
+        }
+    }
+}
+
 /// Rotation.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum RotationAlign {
@@ -656,6 +1339,20 @@ impl Display for RotationAlign {
     }
 }
 
+impl FromStr for RotationAlign {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(RotationAlign::None),
+            "bottom" => Ok(RotationAlign::Bottom),
+            "top" => Ok(RotationAlign::Top),
+            "center" => Ok(RotationAlign::Center),
+            _ => Err(OdsError::Ods(format!("Invalid RotationAlign {}", s))),
+        }
+    }
+}
+
 /// Vertical alignment.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum CellAlignVertical {
@@ -676,15 +1373,77 @@ impl Display for CellAlignVertical {
     }
 }
 
+impl FromStr for CellAlignVertical {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(CellAlignVertical::Top),
+            "middle" => Ok(CellAlignVertical::Middle),
+            "bottom" => Ok(CellAlignVertical::Bottom),
+            "automatic" => Ok(CellAlignVertical::Automatic),
+            _ => Err(OdsError::Ods(format!("Invalid CellAlignVertical {}", s))),
+        }
+    }
+}
+
+/// Cell protection. Only applies if the sheet is protected.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CellProtect {
+    /// Cell is not protected.
+    None,
+    /// Cell is protected and the content is not shown.
+    HiddenAndProtected,
+    /// Cell is protected.
+    Protected,
+    /// Formula is not shown.
+    FormulaHidden,
+    /// Cell is protected and the formula is not shown.
+    ProtectedFormulaHidden,
+}
+
+impl Display for CellProtect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            CellProtect::None => write!(f, "none"),
+            CellProtect::HiddenAndProtected => write!(f, "hidden-and-protected"),
+            CellProtect::Protected => write!(f, "protected"),
+            CellProtect::FormulaHidden => write!(f, "formula-hidden"),
+            CellProtect::ProtectedFormulaHidden => write!(f, "protected formula-hidden"),
+        }
+    }
+}
+
+impl FromStr for CellProtect {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(CellProtect::None),
+            "hidden-and-protected" => Ok(CellProtect::HiddenAndProtected),
+            "protected" => Ok(CellProtect::Protected),
+            "formula-hidden" => Ok(CellProtect::FormulaHidden),
+            "protected formula-hidden" => Ok(CellProtect::ProtectedFormulaHidden),
+            _ => Err(OdsError::Ods(format!("Invalid CellProtect {}", s))),
+        }
+    }
+}
+
 /// Table cell specific styles.
 pub trait AttrTableCell
 where
     Self: AttrMap,
 {
+    /// Cell protection.
+    fn set_cell_protect(&mut self, protect: CellProtect) {
+        self.set_attr("style:cell-protect", protect.to_string());
+    }
+
     fn set_wrap_option(&mut self, wrap: WrapOption) {
         self.set_attr("fo:wrap-option", wrap.to_string());
     }
 
+    /// Print the cell content.
     fn set_print_content(&mut self, print: bool) {
         self.set_attr("style:print-content", print.to_string());
     }
@@ -734,6 +1493,66 @@ where
             border_line_width_string(inner, spacing, outer),
         );
     }
+
+    /// Cell protection.
+    fn cell_protect(&self) -> Result<Option<CellProtect>, AttrParseError> {
+        parse_attr(self, "style:cell-protect")
+    }
+
+    /// Text wrapping.
+    fn wrap_option(&self) -> Result<Option<WrapOption>, AttrParseError> {
+        parse_attr(self, "fo:wrap-option")
+    }
+
+    /// Print the cell content.
+    fn print_content(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "style:print-content")
+    }
+
+    /// Repeat the content to fill the cell.
+    fn repeat_content(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "style:repeat-content")
+    }
+
+    /// Alignment of rotated text.
+    fn rotation_align(&self) -> Result<Option<RotationAlign>, AttrParseError> {
+        parse_attr(self, "style:rotation-align")
+    }
+
+    /// Rotation. A plain number is in degrees.
+    fn rotation_angle(&self) -> Result<Option<Angle>, AttrParseError> {
+        parse_attr(self, "style:rotation-angle")
+    }
+
+    /// Shrink the text to fit the cell.
+    fn shrink_to_fit(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "style:shrink-to-fit")
+    }
+
+    /// Vertical alignment.
+    fn vertical_align(&self) -> Result<Option<CellAlignVertical>, AttrParseError> {
+        parse_attr(self, "style:vertical-align")
+    }
+
+    /// Diagonal style.
+    fn diagonal_bl_tr(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, AttrParseError> {
+        parse_attr_with(self, "style:diagonal-bl-tr", parse_border)
+    }
+
+    /// Widths for double borders.
+    fn diagonal_bl_tr_widths(&self) -> Result<Option<(Length, Length, Length)>, AttrParseError> {
+        parse_attr_with(self, "style:diagonal-bl-tr-widths", parse_border_line_width)
+    }
+
+    /// Diagonal style.
+    fn diagonal_tl_br(&self) -> Result<Option<(Length, Border, Rgb<u8>)>, AttrParseError> {
+        parse_attr_with(self, "style:diagonal-tl-br", parse_border)
+    }
+
+    /// Widths for double borders.
+    fn diagonal_tl_br_widths(&self) -> Result<Option<(Length, Length, Length)>, AttrParseError> {
+        parse_attr_with(self, "style:diagonal-tl-br-widths", parse_border_line_width)
+    }
 }
 
 /// Fix uses the text-align attribute, value-type bases alignment on content.
@@ -752,6 +1571,18 @@ impl Display for TextAlignSource {
     }
 }
 
+impl FromStr for TextAlignSource {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fix" => Ok(TextAlignSource::Fix),
+            "value-type" => Ok(TextAlignSource::ValueType),
+            _ => Err(OdsError::Ods(format!("Invalid TextAlignSource {}", s))),
+        }
+    }
+}
+
 /// Horizontal alignment.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TextAlign {
@@ -780,6 +1611,24 @@ impl Display for TextAlign {
     }
 }
 
+impl FromStr for TextAlign {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(TextAlign::Start),
+            "center" => Ok(TextAlign::Center),
+            "end" => Ok(TextAlign::End),
+            "justify" => Ok(TextAlign::Justify),
+            "inside" => Ok(TextAlign::Inside),
+            "outside" => Ok(TextAlign::Outside),
+            "left" => Ok(TextAlign::Left),
+            "right" => Ok(TextAlign::Right),
+            _ => Err(OdsError::Ods(format!("Invalid TextAlign {}", s))),
+        }
+    }
+}
+
 /// Vertical alignment.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum ParaAlignVertical {
@@ -802,6 +1651,21 @@ impl Display for ParaAlignVertical {
     }
 }
 
+impl FromStr for ParaAlignVertical {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(ParaAlignVertical::Top),
+            "middle" => Ok(ParaAlignVertical::Middle),
+            "bottom" => Ok(ParaAlignVertical::Bottom),
+            "auto" => Ok(ParaAlignVertical::Auto),
+            "baseline" => Ok(ParaAlignVertical::Baseline),
+            _ => Err(OdsError::Ods(format!("Invalid ParaAlignVertical {}", s))),
+        }
+    }
+}
+
 /// Paragraph specific styles.
 pub trait AttrParagraph
 where
@@ -830,6 +1694,36 @@ where
     fn set_vertical_align(&mut self, align: ParaAlignVertical) {
         self.set_attr("style:vertical-align", align.to_string());
     }
+
+    /// Source of the horizontal alignment.
+    fn text_align_source(&self) -> Result<Option<TextAlignSource>, AttrParseError> {
+        parse_attr(self, "style:text-align-source")
+    }
+
+    /// Horizontal alignment.
+    fn text_align(&self) -> Result<Option<TextAlign>, AttrParseError> {
+        parse_attr(self, "fo:text-align")
+    }
+
+    /// First line indent.
+    fn text_indent(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:text-indent")
+    }
+
+    /// Line spacing.
+    fn line_spacing(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "style:line-spacing")
+    }
+
+    /// Line numbering.
+    fn number_lines(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "text:number-lines")
+    }
+
+    /// Vertical alignment.
+    fn vertical_align(&self) -> Result<Option<ParaAlignVertical>, AttrParseError> {
+        parse_attr(self, "style:vertical-align")
+    }
 }
 
 /// Text style values.
@@ -850,6 +1744,19 @@ impl Display for TextStyle {
     }
 }
 
+impl FromStr for TextStyle {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(TextStyle::Normal),
+            "italic" => Ok(TextStyle::Italic),
+            "oblique" => Ok(TextStyle::Oblique),
+            _ => Err(OdsError::Ods(format!("Invalid TextStyle {}", s))),
+        }
+    }
+}
+
 /// Text weight values.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TextWeight {
@@ -884,6 +1791,27 @@ impl Display for TextWeight {
     }
 }
 
+impl FromStr for TextWeight {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(TextWeight::Normal),
+            "bold" => Ok(TextWeight::Bold),
+            "100" => Ok(TextWeight::W100),
+            "200" => Ok(TextWeight::W200),
+            "300" => Ok(TextWeight::W300),
+            "400" => Ok(TextWeight::W400),
+            "500" => Ok(TextWeight::W500),
+            "600" => Ok(TextWeight::W600),
+            "700" => Ok(TextWeight::W700),
+            "800" => Ok(TextWeight::W800),
+            "900" => Ok(TextWeight::W900),
+            _ => Err(OdsError::Ods(format!("Invalid TextWeight {}", s))),
+        }
+    }
+}
+
 /// Text case transformations.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TextTransform {
@@ -904,6 +1832,20 @@ impl Display for TextTransform {
     }
 }
 
+impl FromStr for TextTransform {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(TextTransform::None),
+            "lowercase" => Ok(TextTransform::Lowercase),
+            "uppercase" => Ok(TextTransform::Uppercase),
+            "capitalize" => Ok(TextTransform::Capitalize),
+            _ => Err(OdsError::Ods(format!("Invalid TextTransform {}", s))),
+        }
+    }
+}
+
 /// Text style engraved and embossed.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TextRelief {
@@ -922,6 +1864,19 @@ impl Display for TextRelief {
     }
 }
 
+impl FromStr for TextRelief {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(TextRelief::None),
+            "embossed" => Ok(TextRelief::Embossed),
+            "engraved" => Ok(TextRelief::Engraved),
+            _ => Err(OdsError::Ods(format!("Invalid TextRelief {}", s))),
+        }
+    }
+}
+
 /// Text style subscript or superscript.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TextPosition {
@@ -938,6 +1893,18 @@ impl Display for TextPosition {
     }
 }
 
+impl FromStr for TextPosition {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sub" => Ok(TextPosition::Sub),
+            "super" => Ok(TextPosition::Super),
+            _ => Err(OdsError::Ods(format!("Invalid TextPosition {}", s))),
+        }
+    }
+}
+
 /// Line style for underline, overline, line-through.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum LineStyle {
@@ -966,6 +1933,24 @@ impl Display for LineStyle {
     }
 }
 
+impl FromStr for LineStyle {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dash" => Ok(LineStyle::Dash),
+            "dot-dash" => Ok(LineStyle::DotDash),
+            "dot-dot-dash" => Ok(LineStyle::DotDotDash),
+            "dotted" => Ok(LineStyle::Dotted),
+            "long-dash" => Ok(LineStyle::LongDash),
+            "none" => Ok(LineStyle::None),
+            "solid" => Ok(LineStyle::Solid),
+            "wave" => Ok(LineStyle::Wave),
+            _ => Err(OdsError::Ods(format!("Invalid LineStyle {}", s))),
+        }
+    }
+}
+
 /// Line types for underline, overline, line-through.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum LineType {
@@ -984,6 +1969,19 @@ impl Display for LineType {
     }
 }
 
+impl FromStr for LineType {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(LineType::None),
+            "single" => Ok(LineType::Single),
+            "double" => Ok(LineType::Double),
+            _ => Err(OdsError::Ods(format!("Invalid LineType {}", s))),
+        }
+    }
+}
+
 /// Line modes for underline, overline, line-through.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum LineMode {
@@ -1000,6 +1998,18 @@ impl Display for LineMode {
     }
 }
 
+impl FromStr for LineMode {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "continuous" => Ok(LineMode::Continuous),
+            "skip-white-space" => Ok(LineMode::SkipWhiteSpace),
+            _ => Err(OdsError::Ods(format!("Invalid LineMode {}", s))),
+        }
+    }
+}
+
 /// Line width for underline, overline, line-through.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum LineWidth {
@@ -1024,6 +2034,22 @@ impl Display for LineWidth {
     }
 }
 
+impl FromStr for LineWidth {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(LineWidth::Auto),
+            "normal" => Ok(LineWidth::Normal),
+            "bold" => Ok(LineWidth::Bold),
+            "thin" => Ok(LineWidth::Thin),
+            "medium" => Ok(LineWidth::Medium),
+            "thick" => Ok(LineWidth::Thick),
+            _ => Err(OdsError::Ods(format!("Invalid LineWidth {}", s))),
+        }
+    }
+}
+
 /// Text style attributes.
 pub trait AttrText
 where
@@ -1175,12 +2201,267 @@ where
     fn set_font_overline_width(&mut self, lwidth: LineWidth) {
         self.set_attr("style:text-overline-width", lwidth.to_string());
     }
+
+    /// Text color.
+    fn color(&self) -> Result<Option<Rgb<u8>>, AttrParseError> {
+        parse_attr_with(self, "fo:color", parse_color)
+    }
+
+    /// Font name.
+    fn font_name(&self) -> Option<&String> {
+        self.attr("style:font-name")
+    }
+
+    /// Font size. None if it is given in percent.
+    fn font_size(&self) -> Result<Option<Length>, AttrParseError> {
+        if is_percent(self.attr("fo:font-size")) {
+            Ok(None)
+        } else {
+            parse_attr(self, "fo:font-size")
+        }
+    }
+
+    /// Font size in percent. None if it is given as a length.
+    fn font_size_percent(&self) -> Result<Option<f64>, AttrParseError> {
+        if is_percent(self.attr("fo:font-size")) {
+            parse_attr_with(self, "fo:font-size", parse_percent)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Font style.
+    fn font_style(&self) -> Result<Option<TextStyle>, AttrParseError> {
+        parse_attr(self, "fo:font-style")
+    }
+
+    /// Font weight.
+    fn font_weight(&self) -> Result<Option<TextWeight>, AttrParseError> {
+        parse_attr(self, "fo:font-weight")
+    }
+
+    /// Letter spacing. None if it is normal.
+    fn letter_spacing(&self) -> Result<Option<Length>, AttrParseError> {
+        match self.attr("fo:letter-spacing").map(|v| v.as_str()) {
+            Some("normal") => Ok(None),
+            _ => parse_attr(self, "fo:letter-spacing"),
+        }
+    }
+
+    /// Shadow as x-offset, y-offset, blur and color. None if there is no
+    /// shadow.
+    fn text_shadow(&self) -> Result<Option<Shadow>, AttrParseError> {
+        parse_attr_with(self, "fo:text-shadow", parse_shadow).map(Option::flatten)
+    }
+
+    /// Subscript or superscript. The relative size that may follow is
+    /// ignored.
+    fn text_position(&self) -> Result<Option<TextPosition>, AttrParseError> {
+        parse_attr_with(self, "style:text-position", |v| {
+            v.split_whitespace().next().unwrap_or_default().parse()
+        })
+    }
+
+    /// Case transformation.
+    fn text_transform(&self) -> Result<Option<TextTransform>, AttrParseError> {
+        parse_attr(self, "fo:text-transform")
+    }
+
+    /// Engraved or embossed.
+    fn font_relief(&self) -> Result<Option<TextRelief>, AttrParseError> {
+        parse_attr(self, "style:font-relief")
+    }
+
+    /// Line color.
+    fn font_line_through_color(&self) -> Result<Option<Rgb<u8>>, AttrParseError> {
+        parse_attr_with(self, "style:text-line-through-color", parse_color)
+    }
+
+    /// Line style.
+    fn font_line_through_style(&self) -> Result<Option<LineStyle>, AttrParseError> {
+        parse_attr(self, "style:text-line-through-style")
+    }
+
+    /// Line mode.
+    fn font_line_through_mode(&self) -> Result<Option<LineMode>, AttrParseError> {
+        parse_attr(self, "style:text-line-through-mode")
+    }
+
+    /// Line type.
+    fn font_line_through_type(&self) -> Result<Option<LineType>, AttrParseError> {
+        parse_attr(self, "style:text-line-through-type")
+    }
+
+    /// Line width.
+    fn font_line_through_width(&self) -> Result<Option<LineWidth>, AttrParseError> {
+        parse_attr(self, "style:text-line-through-width")
+    }
+
+    /// Text used for the line.
+    fn font_line_through_text(&self) -> Option<&String> {
+        self.attr("style:text-line-through-text")
+    }
+
+    /// Style for the text of the line.
+    fn font_line_through_text_style(&self) -> Option<&String> {
+        self.attr("style:text-line-through-text-style")
+    }
+
+    /// Outlined text.
+    fn font_text_outline(&self) -> Result<Option<bool>, AttrParseError> {
+        parse_attr(self, "style:text-outline")
+    }
+
+    /// Line color.
+    fn font_underline_color(&self) -> Result<Option<Rgb<u8>>, AttrParseError> {
+        parse_attr_with(self, "style:text-underline-color", parse_color)
+    }
+
+    /// Line style.
+    fn font_underline_style(&self) -> Result<Option<LineStyle>, AttrParseError> {
+        parse_attr(self, "style:text-underline-style")
+    }
+
+    /// Line mode.
+    fn font_underline_mode(&self) -> Result<Option<LineMode>, AttrParseError> {
+        parse_attr(self, "style:text-underline-mode")
+    }
+
+    /// Line type.
+    fn font_underline_type(&self) -> Result<Option<LineType>, AttrParseError> {
+        parse_attr(self, "style:text-underline-type")
+    }
+
+    /// Line width.
+    fn font_underline_width(&self) -> Result<Option<LineWidth>, AttrParseError> {
+        parse_attr(self, "style:text-underline-width")
+    }
+
+    /// Line color.
+    fn font_overline_color(&self) -> Result<Option<Rgb<u8>>, AttrParseError> {
+        parse_attr_with(self, "style:text-overline-color", parse_color)
+    }
+
+    /// Line style.
+    fn font_overline_style(&self) -> Result<Option<LineStyle>, AttrParseError> {
+        parse_attr(self, "style:text-overline-style")
+    }
+
+    /// Line mode.
+    fn font_overline_mode(&self) -> Result<Option<LineMode>, AttrParseError> {
+        parse_attr(self, "style:text-overline-mode")
+    }
+
+    /// Line type.
+    fn font_overline_type(&self) -> Result<Option<LineType>, AttrParseError> {
+        parse_attr(self, "style:text-overline-type")
+    }
+
+    /// Line width.
+    fn font_overline_width(&self) -> Result<Option<LineWidth>, AttrParseError> {
+        parse_attr(self, "style:text-overline-width")
+    }
+}
+
+/// Fill and stroke for drawing shapes.
+pub trait AttrGraphic
+where
+    Self: AttrMap,
+{
+    /// Solid fill.
+    fn set_fill_color(&mut self, color: Rgb<u8>) {
+        self.set_attr("draw:fill", "solid".to_string());
+        self.set_attr("draw:fill-color", color_string(color));
+    }
+
+    /// No fill.
+    fn set_fill_none(&mut self) {
+        self.set_attr("draw:fill", "none".to_string());
+    }
+
+    /// Solid line.
+    fn set_stroke(&mut self, width: Length, color: Rgb<u8>) {
+        self.set_attr("draw:stroke", "solid".to_string());
+        self.set_attr("svg:stroke-width", width.to_string());
+        self.set_attr("svg:stroke-color", color_string(color));
+    }
+
+    /// No line.
+    fn set_stroke_none(&mut self) {
+        self.set_attr("draw:stroke", "none".to_string());
+    }
+
+    /// Opacity of the fill in percent.
+    fn set_opacity(&mut self, opacity: f64) {
+        self.set_attr("draw:opacity", percent_string(opacity));
+    }
+
+    /// Shadow.
+    fn set_shadow(&mut self, x_offset: Length, y_offset: Length, color: Rgb<u8>) {
+        self.set_attr("draw:shadow", "visible".to_string());
+        self.set_attr("draw:shadow-offset-x", x_offset.to_string());
+        self.set_attr("draw:shadow-offset-y", y_offset.to_string());
+        self.set_attr("draw:shadow-color", color_string(color));
+    }
+
+    /// Fill color. None if the fill is not solid.
+    fn fill_color(&self) -> Result<Option<Rgb<u8>>, AttrParseError> {
+        match self.attr("draw:fill").map(|v| v.as_str()) {
+            Some("solid") => parse_attr_with(self, "draw:fill-color", parse_color),
+            _ => Ok(None),
+        }
+    }
+
+    /// Line width and color. None if there is no solid line.
+    fn stroke(&self) -> Result<Option<(Length, Rgb<u8>)>, AttrParseError> {
+        match self.attr("draw:stroke").map(|v| v.as_str()) {
+            Some("solid") => {
+                let width = parse_attr(self, "svg:stroke-width")?;
+                let color = parse_attr_with(self, "svg:stroke-color", parse_color)?;
+                Ok(width.zip(color))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Opacity of the fill in percent.
+    fn opacity(&self) -> Result<Option<f64>, AttrParseError> {
+        parse_attr_with(self, "draw:opacity", parse_percent)
+    }
+
+    /// Shadow offsets and color. None if the shadow is not visible.
+    fn shadow(&self) -> Result<Option<(Length, Length, Rgb<u8>)>, AttrParseError> {
+        match self.attr("draw:shadow").map(|v| v.as_str()) {
+            Some("visible") => {
+                let x_offset = parse_attr(self, "draw:shadow-offset-x")?;
+                let y_offset = parse_attr(self, "draw:shadow-offset-y")?;
+                let color = parse_attr_with(self, "draw:shadow-color", parse_color)?;
+                Ok(x_offset
+                    .zip(y_offset)
+                    .zip(color)
+                    .map(|((x, y), c)| (x, y, c)))
+            }
+            _ => Ok(None),
+        }
+    }
 }
 
 pub(crate) fn color_string(color: Rgb<u8>) -> String {
     format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
 }
 
+/// Parses a color #rrggbb.
+pub(crate) fn parse_color(s: &str) -> Result<Rgb<u8>, OdsError> {
+    if s.len() == 7 && s.starts_with('#') {
+        let r = u8::from_str_radix(&s[1..3], 16)?;
+        let g = u8::from_str_radix(&s[3..5], 16)?;
+        let b = u8::from_str_radix(&s[5..7], 16)?;
+        Ok(Rgb::new(r, g, b))
+    } else {
+        Err(OdsError::Ods(format!("Invalid color {}", s)))
+    }
+}
+
 fn border_string(width: Length, border: Border, color: Rgb<u8>) -> String {
     format!(
         "{} {} #{:02x}{:02x}{:02x}",
@@ -1212,3 +2493,124 @@ fn shadow_string(
         format!("{} {} {}", color_string(color), x_offset, y_offset)
     }
 }
+
+/// Parses the attribute with FromStr.
+pub(crate) fn parse_attr<A: AttrMap + ?Sized, T: FromStr>(
+    map: &A,
+    name: &str,
+) -> Result<Option<T>, AttrParseError> {
+    parse_attr_with(map, name, |v| v.parse::<T>())
+}
+
+/// Parses the attribute.
+fn parse_attr_with<A: AttrMap + ?Sized, T, E, F: Fn(&str) -> Result<T, E>>(
+    map: &A,
+    name: &str,
+    parse: F,
+) -> Result<Option<T>, AttrParseError> {
+    match map.attr(name) {
+        Some(value) => match parse(value.trim()) {
+            Ok(v) => Ok(Some(v)),
+            Err(_) => Err(AttrParseError {
+                attr: name.to_string(),
+                value: value.clone(),
+            }),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Parses a length. A percentage is taken relative to base.
+pub(crate) fn parse_attr_percent_of<A: AttrMap + ?Sized>(
+    map: &A,
+    name: &str,
+    base: Length,
+) -> Result<Option<Length>, AttrParseError> {
+    if is_percent(map.attr(name)) {
+        parse_attr_with(map, name, parse_percent).map(|v| v.map(|v| base * v / 100.0))
+    } else {
+        parse_attr(map, name)
+    }
+}
+
+fn is_percent(value: Option<&String>) -> bool {
+    value.map(|v| v.trim_end().ends_with('%')) == Some(true)
+}
+
+/// Parses 50%.
+fn parse_percent(s: &str) -> Result<f64, OdsError> {
+    match s.strip_suffix('%') {
+        Some(v) => Ok(v.trim().parse()?),
+        None => Err(OdsError::Ods(format!("Invalid percentage {}", s))),
+    }
+}
+
+/// Parses 2.5*.
+fn parse_rel_width(s: &str) -> Result<f64, OdsError> {
+    match s.strip_suffix('*') {
+        Some(v) => Ok(v.trim().parse()?),
+        None => Err(OdsError::Ods(format!("Invalid relative width {}", s))),
+    }
+}
+
+/// Parses width, style and color in any order. The color defaults to
+/// black, the width can only be missing for none and hidden.
+fn parse_border(s: &str) -> Result<(Length, Border, Rgb<u8>), OdsError> {
+    let mut width = None;
+    let mut border = None;
+    let mut color = None;
+    for token in s.split_whitespace() {
+        if token.starts_with('#') {
+            color = Some(parse_color(token)?);
+        } else if let Ok(v) = token.parse::<Border>() {
+            border = Some(v);
+        } else {
+            width = Some(token.parse::<Length>()?);
+        }
+    }
+
+    let border = border.ok_or_else(|| OdsError::Ods(format!("Invalid border {}", s)))?;
+    let width = match (width, border) {
+        (Some(width), _) => width,
+        (None, Border::None) | (None, Border::Hidden) => Length::Pt(0.0),
+        (None, _) => return Err(OdsError::Ods(format!("Invalid border {}", s))),
+    };
+    Ok((width, border, color.unwrap_or_else(|| Rgb::new(0, 0, 0))))
+}
+
+/// Parses inner width, spacing and outer width.
+fn parse_border_line_width(s: &str) -> Result<(Length, Length, Length), OdsError> {
+    let widths = s
+        .split_whitespace()
+        .map(str::parse::<Length>)
+        .collect::<Result<Vec<_>, _>>()?;
+    match widths.as_slice() {
+        [inner, spacing, outer] => Ok((*inner, *spacing, *outer)),
+        _ => Err(OdsError::Ods(format!("Invalid border line width {}", s))),
+    }
+}
+
+/// Parses color, x-offset, y-offset and an optional blur. None for
+/// "none".
+fn parse_shadow(s: &str) -> Result<Option<Shadow>, OdsError> {
+    if s == "none" {
+        return Ok(None);
+    }
+
+    let mut color = None;
+    let mut lengths = Vec::new();
+    for token in s.split_whitespace() {
+        if token.starts_with('#') {
+            color = Some(parse_color(token)?);
+        } else {
+            lengths.push(token.parse::<Length>()?);
+        }
+    }
+
+    let color = color.ok_or_else(|| OdsError::Ods(format!("Invalid shadow {}", s)))?;
+    match lengths.as_slice() {
+        [x, y] => Ok(Some((*x, *y, None, color))),
+        [x, y, blur] => Ok(Some((*x, *y, Some(*blur), color))),
+        _ => Err(OdsError::Ods(format!("Invalid shadow {}", s))),
+    }
+}
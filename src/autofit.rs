@@ -0,0 +1,370 @@
+//!
+//! Estimates column widths and row heights from the cell contents.
+//!
+//! There is no font rendering here. The widths of the characters are
+//! approximated with the metrics of Liberation Sans, scaled for some
+//! common font families.
+//!
+
+use std::collections::BTreeMap;
+
+use crate::format::format_value;
+use crate::style::{
+    AttrFoPadding, AttrMap, AttrTableCell, AttrText, ResolvedStyle, TextWeight, WrapOption,
+};
+use crate::{fonts, ucell, Length, Sheet, WorkBook};
+
+/// LibreOffice default for the width of a column.
+const DEFAULT_COL_WIDTH: Length = Length::Cm(2.258);
+/// LibreOffice default for the height of a row with a 10pt font.
+const DEFAULT_ROW_HEIGHT: Length = Length::Cm(0.452);
+/// Default font size.
+const DEFAULT_FONT_SIZE: f64 = 10.0;
+/// Extra space that LibreOffice adds to the optimal column width.
+const EXTRA_WIDTH: Length = Length::Cm(0.1);
+/// Line height relative to the font size.
+const LINE_HEIGHT: f64 = 1.2;
+/// Bold glyphs are a bit wider.
+const BOLD_FACTOR: f64 = 1.07;
+
+/// Width of the glyphs relative to Liberation Sans, or the fixed width
+/// of a monospaced font in em.
+#[derive(Debug, Clone, Copy)]
+enum Metrics {
+    Scaled(f64),
+    Mono(f64),
+}
+
+/// Metrics for some common font families.
+const FONT_METRICS: [(&str, Metrics); 20] = [
+    ("liberation sans", Metrics::Scaled(1.0)),
+    ("arial", Metrics::Scaled(1.0)),
+    ("helvetica", Metrics::Scaled(1.0)),
+    ("liberation sans narrow", Metrics::Scaled(0.82)),
+    ("arial narrow", Metrics::Scaled(0.82)),
+    ("liberation serif", Metrics::Scaled(0.9)),
+    ("times new roman", Metrics::Scaled(0.9)),
+    ("times", Metrics::Scaled(0.9)),
+    ("carlito", Metrics::Scaled(0.9)),
+    ("calibri", Metrics::Scaled(0.9)),
+    ("caladea", Metrics::Scaled(0.95)),
+    ("cambria", Metrics::Scaled(0.95)),
+    ("dejavu sans", Metrics::Scaled(1.1)),
+    ("dejavu serif", Metrics::Scaled(1.1)),
+    ("noto sans", Metrics::Scaled(1.04)),
+    ("verdana", Metrics::Scaled(1.15)),
+    ("liberation mono", Metrics::Mono(0.6)),
+    ("courier new", Metrics::Mono(0.6)),
+    ("dejavu sans mono", Metrics::Mono(0.602)),
+    ("consolas", Metrics::Mono(0.55)),
+];
+
+/// Font properties that determine the size of the text.
+struct Font {
+    size: f64,
+    bold: bool,
+    metrics: Metrics,
+}
+
+impl Font {
+    fn new(book: &WorkBook, style: &ResolvedStyle<'_>) -> Self {
+        let text = style.text();
+        let size = match text.font_size() {
+            Ok(Some(size)) => size.to_pt(),
+            _ => match text.font_size_percent() {
+                Ok(Some(percent)) => DEFAULT_FONT_SIZE * percent / 100.0,
+                _ => DEFAULT_FONT_SIZE,
+            },
+        };
+        let bold = match text.font_weight() {
+            Ok(Some(weight)) => matches!(
+                weight,
+                TextWeight::Bold
+                    | TextWeight::W600
+                    | TextWeight::W700
+                    | TextWeight::W800
+                    | TextWeight::W900
+            ),
+            _ => false,
+        };
+        let family = text
+            .attr("style:font-name")
+            .map(
+                |name| match book.font(name).and_then(|f| f.attr("svg:font-family")) {
+                    Some(family) => fonts::unquote(family),
+                    None => name.clone(),
+                },
+            )
+            .map(|v| v.to_lowercase());
+        let metrics = family
+            .and_then(|family| {
+                FONT_METRICS
+                    .iter()
+                    .find(|(name, _)| *name == family)
+                    .map(|(_, metrics)| *metrics)
+            })
+            .unwrap_or(Metrics::Scaled(1.0));
+
+        Self {
+            size,
+            bold,
+            metrics,
+        }
+    }
+
+    /// Width of the text in pt.
+    fn width(&self, text: &str) -> f64 {
+        let em: f64 = match self.metrics {
+            Metrics::Scaled(scale) => text.chars().map(char_width).sum::<f64>() * scale,
+            Metrics::Mono(width) => text
+                .chars()
+                .map(|c| if is_wide(c) { 1.0 } else { width })
+                .sum(),
+        };
+        let width = em * self.size;
+        if self.bold {
+            width * BOLD_FACTOR
+        } else {
+            width
+        }
+    }
+
+    /// Height of one line in pt.
+    fn line_height(&self) -> f64 {
+        self.size * LINE_HEIGHT
+    }
+}
+
+/// Width of a character of Liberation Sans in em.
+fn char_width(c: char) -> f64 {
+    match c {
+        'i' | 'j' | 'l' => 0.222,
+        ' ' | '!' | '\'' | ',' | '.' | '/' | ':' | ';' | 'I' | '[' | '\\' | ']' | 'f' | 't'
+        | '|' => 0.278,
+        '(' | ')' | '-' | '`' | 'r' | '{' | '}' => 0.333,
+        '"' => 0.355,
+        '*' => 0.389,
+        '^' => 0.469,
+        'J' | 'c' | 'k' | 's' | 'v' | 'x' | 'y' | 'z' => 0.5,
+        '0'..='9'
+        | '#'
+        | '$'
+        | '?'
+        | '_'
+        | 'L'
+        | 'a'
+        | 'b'
+        | 'd'
+        | 'e'
+        | 'g'
+        | 'h'
+        | 'n'
+        | 'o'
+        | 'p'
+        | 'q'
+        | 'u' => 0.556,
+        '+' | '<' | '=' | '>' | '~' => 0.584,
+        'F' | 'T' | 'Z' => 0.611,
+        '&' | 'A' | 'B' | 'E' | 'K' | 'P' | 'S' | 'V' | 'X' | 'Y' => 0.667,
+        'C' | 'D' | 'H' | 'N' | 'R' | 'U' | 'w' => 0.722,
+        'G' | 'O' | 'Q' => 0.778,
+        'M' | 'm' => 0.833,
+        '%' => 0.889,
+        'W' => 0.944,
+        '@' => 1.015,
+        c if is_wide(c) => 1.0,
+        _ => 0.6,
+    }
+}
+
+/// East asian wide characters.
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD)
+}
+
+/// Size of the content of one cell.
+struct CellSize {
+    font: Font,
+    text: String,
+    wrap: bool,
+    /// Rotation in radians.
+    rotation: f64,
+    /// Top, right, bottom, left in pt.
+    padding: (f64, f64, f64, f64),
+}
+
+impl CellSize {
+    /// None for cells without text.
+    fn new(book: &WorkBook, sheet: &Sheet, row: ucell, col: ucell) -> Option<Self> {
+        let value = sheet.value(row, col);
+        let style = book.sheet_cell_style(sheet, row, col);
+        let text = format_value(style.value_format(), value);
+        if text.is_empty() {
+            return None;
+        }
+
+        let cell = style.cell();
+        let rotation = match cell.rotation_angle() {
+            Ok(Some(angle)) => angle.to_rad(),
+            _ => 0.0,
+        };
+        let wrap = rotation == 0.0 && cell.wrap_option() == Ok(Some(WrapOption::Wrap));
+        let padding = cell.padding().ok().flatten();
+        let side = |v: Result<Option<Length>, _>| match v.ok().flatten().or(padding) {
+            Some(v) => v.to_pt(),
+            None => 0.0,
+        };
+        let padding = (
+            side(cell.padding_top()),
+            side(cell.padding_right()),
+            side(cell.padding_bottom()),
+            side(cell.padding_left()),
+        );
+
+        Some(Self {
+            font: Font::new(book, &style),
+            text,
+            wrap,
+            rotation,
+            padding,
+        })
+    }
+
+    /// Width and height of the unwrapped text in pt.
+    fn text_size(&self) -> (f64, f64) {
+        let width = self
+            .text
+            .lines()
+            .map(|line| self.font.width(line))
+            .fold(0.0, f64::max);
+        let lines = self.text.lines().count().max(1);
+        (width, lines as f64 * self.font.line_height())
+    }
+
+    /// Width the cell needs in pt. Wrapped text needs the width of its
+    /// longest word.
+    fn width(&self) -> f64 {
+        let width = if self.wrap {
+            self.text
+                .split_whitespace()
+                .map(|word| self.font.width(word))
+                .fold(0.0, f64::max)
+        } else {
+            let (width, height) = self.text_size();
+            width * self.rotation.cos().abs() + height * self.rotation.sin().abs()
+        };
+        width + self.padding.1 + self.padding.3
+    }
+
+    /// Height the cell needs in pt for the given column width in pt.
+    fn height(&self, col_width: f64) -> f64 {
+        let height = if self.wrap {
+            let available = col_width - self.padding.1 - self.padding.3;
+            let lines: usize = self
+                .text
+                .lines()
+                .map(|line| self.wrapped_lines(line, available))
+                .sum();
+            lines.max(1) as f64 * self.font.line_height()
+        } else {
+            let (width, height) = self.text_size();
+            width * self.rotation.sin().abs() + height * self.rotation.cos().abs()
+        };
+        height + self.padding.0 + self.padding.2
+    }
+
+    /// Number of lines after wrapping at word boundaries.
+    fn wrapped_lines(&self, line: &str, available: f64) -> usize {
+        let space = self.font.width(" ");
+        let mut lines = 1;
+        let mut current = 0.0;
+        for word in line.split_whitespace() {
+            let word = self.font.width(word);
+            if current == 0.0 {
+                current = word;
+            } else if current + space + word <= available {
+                current += space + word;
+            } else {
+                lines += 1;
+                current = word;
+            }
+        }
+        lines
+    }
+}
+
+/// Rounds to 1/1000 cm.
+fn round_cm(pt: f64) -> Length {
+    Length::Cm((Length::Pt(pt).to_cm() * 1000.0).round() / 1000.0)
+}
+
+/// Estimated widths for the columns that have some content. Cells that
+/// span several columns are not included.
+pub(crate) fn column_widths<I: IntoIterator<Item = ucell>>(
+    book: &WorkBook,
+    sheet: &Sheet,
+    cols: I,
+) -> Vec<(ucell, Length)> {
+    let mut widths: BTreeMap<ucell, f64> = cols.into_iter().map(|col| (col, 0.0)).collect();
+    for &(row, col) in sheet.data.keys() {
+        if !widths.contains_key(&col) || sheet.col_span(row, col) > 1 {
+            continue;
+        }
+        if let Some(cell) = CellSize::new(book, sheet, row, col) {
+            let width = widths.get_mut(&col).expect("col");
+            *width = width.max(cell.width());
+        }
+    }
+
+    widths
+        .into_iter()
+        .filter(|(_, width)| *width > 0.0)
+        .map(|(col, width)| (col, round_cm(width + EXTRA_WIDTH.to_pt())))
+        .collect()
+}
+
+/// Estimated heights for the rows that have some content. Cells that
+/// span several rows are not included. The height is at least the
+/// default row height.
+pub(crate) fn row_heights<I: IntoIterator<Item = ucell>>(
+    book: &WorkBook,
+    sheet: &Sheet,
+    rows: I,
+) -> Vec<(ucell, Length)> {
+    let mut heights: BTreeMap<ucell, f64> = rows.into_iter().map(|row| (row, 0.0)).collect();
+    for &(row, col) in sheet.data.keys() {
+        if !heights.contains_key(&row) || sheet.row_span(row, col) > 1 {
+            continue;
+        }
+        if let Some(cell) = CellSize::new(book, sheet, row, col) {
+            let col_width: f64 = (col..col + sheet.col_span(row, col).max(1))
+                .map(|col| {
+                    sheet
+                        .col_width(book, col)
+                        .unwrap_or(DEFAULT_COL_WIDTH)
+                        .to_pt()
+                })
+                .sum();
+            let height = heights.get_mut(&row).expect("row");
+            *height = height.max(cell.height(col_width));
+        }
+    }
+
+    heights
+        .into_iter()
+        .filter(|(_, height)| *height > 0.0)
+        .map(|(row, height)| (row, round_cm(height.max(DEFAULT_ROW_HEIGHT.to_pt()))))
+        .collect()
+}
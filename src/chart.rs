@@ -0,0 +1,344 @@
+//!
+//! Charts as embedded chart documents.
+//!
+//! Each chart is written as its own sub-document "Object N" and is placed
+//! on the sheet via a draw:frame in table:shapes.
+//!
+//! ```
+//! use spreadsheet_ods::{cm, CellRange, CellRef, Length, Sheet};
+//! use spreadsheet_ods::chart::{Chart, ChartType, LegendPosition, Series};
+//!
+//! let mut sheet = Sheet::new_with_name("Data");
+//! sheet.set_value(0, 1, "Sales");
+//! sheet.set_value(1, 0, "Q1");
+//! sheet.set_value(1, 1, 100);
+//! sheet.set_value(2, 0, "Q2");
+//! sheet.set_value(2, 1, 120);
+//!
+//! let mut chart = Chart::new(ChartType::Bar);
+//! chart.set_title("Sales per quarter");
+//! chart.set_legend(LegendPosition::End);
+//! chart.set_x_axis_title("Quarter");
+//! chart.set_categories(CellRange::remote("Data", 1, 0, 2, 0));
+//! let mut series = Series::new(CellRange::remote("Data", 1, 1, 2, 1));
+//! series.set_label(CellRef::remote("Data", 0, 1));
+//! chart.add_series(series);
+//! chart.set_position(cm!(4), cm!(1));
+//! chart.set_size(cm!(12), cm!(8));
+//!
+//! sheet.add_chart(chart);
+//! ```
+//!
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::xmltree::XmlTag;
+use crate::{CellRange, CellRef, Length, OdsError};
+
+/// Chart types.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ChartType {
+    Bar,
+    Line,
+    Pie,
+    Scatter,
+    Area,
+}
+
+impl FromStr for ChartType {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chart:bar" => Ok(ChartType::Bar),
+            "chart:line" => Ok(ChartType::Line),
+            "chart:circle" => Ok(ChartType::Pie),
+            "chart:scatter" => Ok(ChartType::Scatter),
+            "chart:area" => Ok(ChartType::Area),
+            _ => Err(OdsError::Ods(format!(
+                "Unknown value for chart:class {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for ChartType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ChartType::Bar => write!(f, "chart:bar"),
+            ChartType::Line => write!(f, "chart:line"),
+            ChartType::Pie => write!(f, "chart:circle"),
+            ChartType::Scatter => write!(f, "chart:scatter"),
+            ChartType::Area => write!(f, "chart:area"),
+        }
+    }
+}
+
+/// Position of the legend.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LegendPosition {
+    Start,
+    End,
+    Top,
+    Bottom,
+}
+
+impl FromStr for LegendPosition {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(LegendPosition::Start),
+            "end" => Ok(LegendPosition::End),
+            "top" => Ok(LegendPosition::Top),
+            "bottom" => Ok(LegendPosition::Bottom),
+            _ => Err(OdsError::Ods(format!(
+                "Unknown value for chart:legend-position {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for LegendPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            LegendPosition::Start => write!(f, "start"),
+            LegendPosition::End => write!(f, "end"),
+            LegendPosition::Top => write!(f, "top"),
+            LegendPosition::Bottom => write!(f, "bottom"),
+        }
+    }
+}
+
+/// One data series of a chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    values: CellRange,
+    label: Option<CellRef>,
+    domain: Option<CellRange>,
+}
+
+impl Series {
+    /// Series with the given values.
+    pub fn new(values: CellRange) -> Self {
+        Self {
+            values,
+            label: None,
+            domain: None,
+        }
+    }
+
+    /// Values.
+    pub fn set_values(&mut self, values: CellRange) {
+        self.values = values;
+    }
+
+    /// Values.
+    pub fn values(&self) -> &CellRange {
+        &self.values
+    }
+
+    /// Cell with the name of the series.
+    pub fn set_label(&mut self, label: CellRef) {
+        self.label = Some(label);
+    }
+
+    /// Cell with the name of the series.
+    pub fn label(&self) -> Option<&CellRef> {
+        self.label.as_ref()
+    }
+
+    /// X-values for a scatter chart.
+    pub fn set_domain(&mut self, domain: CellRange) {
+        self.domain = Some(domain);
+    }
+
+    /// X-values for a scatter chart.
+    pub fn domain(&self) -> Option<&CellRange> {
+        self.domain.as_ref()
+    }
+}
+
+/// A chart.
+///
+/// Ranges without a table name refer to the sheet that contains the chart.
+///
+/// Charts read from a file are informational only. The original chart
+/// document is written back unchanged, modifications are ignored.
+#[derive(Debug, Clone)]
+pub struct Chart {
+    name: String,
+    chart_type: ChartType,
+    title: Option<String>,
+    legend: Option<LegendPosition>,
+    x_axis_title: Option<String>,
+    y_axis_title: Option<String>,
+    categories: Option<CellRange>,
+    series: Vec<Series>,
+    x: Length,
+    y: Length,
+    width: Length,
+    height: Length,
+    /// Path of the chart document, if it was read from a file.
+    pub(crate) href: Option<String>,
+    /// Original draw:frame, if it was read from a file.
+    pub(crate) frame: Option<XmlTag>,
+}
+
+impl Chart {
+    /// Empty chart. The default size is 16cm x 9cm at the top left.
+    pub fn new(chart_type: ChartType) -> Self {
+        Self {
+            name: String::new(),
+            chart_type,
+            title: None,
+            legend: None,
+            x_axis_title: None,
+            y_axis_title: None,
+            categories: None,
+            series: vec![],
+            x: Length::Cm(0.0),
+            y: Length::Cm(0.0),
+            width: Length::Cm(16.0),
+            height: Length::Cm(9.0),
+            href: None,
+            frame: None,
+        }
+    }
+
+    /// Name of the drawing frame.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        self.name = name.into();
+    }
+
+    /// Name of the drawing frame.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Chart type.
+    pub fn set_chart_type(&mut self, chart_type: ChartType) {
+        self.chart_type = chart_type;
+    }
+
+    /// Chart type.
+    pub fn chart_type(&self) -> ChartType {
+        self.chart_type
+    }
+
+    /// Title.
+    pub fn set_title<S: Into<String>>(&mut self, title: S) {
+        self.title = Some(title.into());
+    }
+
+    /// Title.
+    pub fn title(&self) -> Option<&String> {
+        self.title.as_ref()
+    }
+
+    /// Shows a legend.
+    pub fn set_legend(&mut self, legend: LegendPosition) {
+        self.legend = Some(legend);
+    }
+
+    /// Removes the legend.
+    pub fn clear_legend(&mut self) {
+        self.legend = None;
+    }
+
+    /// Legend position.
+    pub fn legend(&self) -> Option<LegendPosition> {
+        self.legend
+    }
+
+    /// Title of the x-axis.
+    pub fn set_x_axis_title<S: Into<String>>(&mut self, title: S) {
+        self.x_axis_title = Some(title.into());
+    }
+
+    /// Title of the x-axis.
+    pub fn x_axis_title(&self) -> Option<&String> {
+        self.x_axis_title.as_ref()
+    }
+
+    /// Title of the y-axis.
+    pub fn set_y_axis_title<S: Into<String>>(&mut self, title: S) {
+        self.y_axis_title = Some(title.into());
+    }
+
+    /// Title of the y-axis.
+    pub fn y_axis_title(&self) -> Option<&String> {
+        self.y_axis_title.as_ref()
+    }
+
+    /// Category labels for the x-axis.
+    pub fn set_categories(&mut self, categories: CellRange) {
+        self.categories = Some(categories);
+    }
+
+    /// Category labels for the x-axis.
+    pub fn categories(&self) -> Option<&CellRange> {
+        self.categories.as_ref()
+    }
+
+    /// Adds a data series.
+    pub fn add_series(&mut self, series: Series) {
+        self.series.push(series);
+    }
+
+    /// Data series.
+    pub fn series(&self) -> &Vec<Series> {
+        &self.series
+    }
+
+    /// Data series.
+    pub fn series_mut(&mut self) -> &mut Vec<Series> {
+        &mut self.series
+    }
+
+    /// Position relative to the top left corner of the sheet.
+    pub fn set_position(&mut self, x: Length, y: Length) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Position relative to the top left corner of the sheet.
+    pub fn position(&self) -> (Length, Length) {
+        (self.x, self.y)
+    }
+
+    /// Size of the chart.
+    pub fn set_size(&mut self, width: Length, height: Length) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Size of the chart.
+    pub fn size(&self) -> (Length, Length) {
+        (self.width, self.height)
+    }
+
+    /// Path of the chart document inside the ods, if the chart was read
+    /// from a file.
+    pub fn href(&self) -> Option<&String> {
+        self.href.as_ref()
+    }
+
+    /// All ranges used by the chart.
+    pub(crate) fn ranges(&self) -> Vec<&CellRange> {
+        let mut ranges = Vec::new();
+        if let Some(categories) = &self.categories {
+            ranges.push(categories);
+        }
+        for series in &self.series {
+            if let Some(domain) = &series.domain {
+                ranges.push(domain);
+            }
+            ranges.push(&series.values);
+        }
+        ranges
+    }
+}
@@ -0,0 +1,280 @@
+//!
+//! Conversion between sheets and CSV.
+//!
+//! Needs the feature "csv". Writing uses either the raw values or the
+//! text as formatted by the ValueFormat of the cell. Reading can infer
+//! the type of each field: numbers, percentages, ISO dates and booleans
+//! are converted to the matching Value, everything else is text.
+//!
+//! ```
+//! use spreadsheet_ods::csv::{read_csv, write_csv, CsvOptions};
+//! use spreadsheet_ods::{Value, WorkBook};
+//!
+//! let mut wb = WorkBook::new();
+//! let data = "name;amount;share;due\nrent;800;25%;2021-01-01\n";
+//!
+//! let mut options = CsvOptions::new();
+//! options.set_delimiter(b';');
+//! options.set_default_styles(true);
+//! let idx = read_csv(&mut wb, "Import", data.as_bytes(), &options).unwrap();
+//!
+//! let sheet = wb.sheet(idx);
+//! assert_eq!(sheet.value(1, 1).as_f64_or(0.0), 800.0);
+//! assert!(matches!(sheet.value(1, 2), Value::Percentage(_)));
+//! assert!(matches!(sheet.value(1, 3), Value::DateTime(_)));
+//!
+//! let mut buf = Vec::new();
+//! options.set_display_text(true);
+//! write_csv(&wb, idx, &mut buf, &options).unwrap();
+//! ```
+//!
+
+use std::io::{Read, Write};
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::defaultstyles::create_default_styles;
+use crate::format::{display_text, raw_text};
+use crate::{ucell, OdsError, Sheet, Value, ValueType, WorkBook};
+
+/// Quoting of fields when writing.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CsvQuote {
+    /// Only fields that contain the delimiter, quotes or line breaks.
+    Necessary,
+    /// All fields.
+    Always,
+    /// All fields that are not numbers.
+    NonNumeric,
+    /// No quoting at all.
+    Never,
+}
+
+/// Settings for reading and writing CSV.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    delimiter: u8,
+    quote: u8,
+    quote_style: CsvQuote,
+    display_text: bool,
+    infer_types: bool,
+    default_styles: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvOptions {
+    /// Comma separated, quotes only where necessary, raw values and
+    /// type inference when reading.
+    pub fn new() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            quote_style: CsvQuote::Necessary,
+            display_text: false,
+            infer_types: true,
+            default_styles: false,
+        }
+    }
+
+    /// Field delimiter.
+    pub fn set_delimiter(&mut self, delimiter: u8) {
+        self.delimiter = delimiter;
+    }
+
+    /// Field delimiter.
+    pub fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+
+    /// Quote character.
+    pub fn set_quote(&mut self, quote: u8) {
+        self.quote = quote;
+    }
+
+    /// Quote character.
+    pub fn quote(&self) -> u8 {
+        self.quote
+    }
+
+    /// Which fields are quoted when writing.
+    pub fn set_quote_style(&mut self, quote_style: CsvQuote) {
+        self.quote_style = quote_style;
+    }
+
+    /// Which fields are quoted when writing.
+    pub fn quote_style(&self) -> CsvQuote {
+        self.quote_style
+    }
+
+    /// Writes the text as formatted by the ValueFormat of the cell
+    /// style, or of the default style for the value type. Otherwise
+    /// the raw values are written.
+    pub fn set_display_text(&mut self, display_text: bool) {
+        self.display_text = display_text;
+    }
+
+    /// Writes the formatted text instead of the raw values.
+    pub fn display_text(&self) -> bool {
+        self.display_text
+    }
+
+    /// Converts numbers, percentages, ISO dates and booleans when
+    /// reading. Otherwise all fields are read as text.
+    pub fn set_infer_types(&mut self, infer_types: bool) {
+        self.infer_types = infer_types;
+    }
+
+    /// Converts numbers, percentages, ISO dates and booleans.
+    pub fn infer_types(&self) -> bool {
+        self.infer_types
+    }
+
+    /// Adds the styles of defaultstyles::create_default_styles() when
+    /// reading, unless the workbook already has default styles.
+    pub fn set_default_styles(&mut self, default_styles: bool) {
+        self.default_styles = default_styles;
+    }
+
+    /// Adds the default styles when reading.
+    pub fn default_styles(&self) -> bool {
+        self.default_styles
+    }
+}
+
+impl From<::csv::Error> for OdsError {
+    fn from(err: ::csv::Error) -> OdsError {
+        if err.is_io_error() {
+            if let ::csv::ErrorKind::Io(err) = err.into_kind() {
+                return OdsError::Io(err);
+            }
+            unreachable!()
+        } else {
+            OdsError::Ods(format!("Csv {}", err))
+        }
+    }
+}
+
+/// Reads the CSV into a new sheet and appends it to the workbook.
+/// Returns the index of the new sheet.
+pub fn read_csv<R: Read, S: Into<String>>(
+    book: &mut WorkBook,
+    name: S,
+    read: R,
+    options: &CsvOptions,
+) -> Result<usize, OdsError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(read);
+
+    let mut sheet = Sheet::new_with_name(name);
+    let mut record = ::csv::StringRecord::new();
+    let mut row: ucell = 0;
+    while reader.read_record(&mut record)? {
+        for (col, field) in record.iter().enumerate() {
+            if field.is_empty() {
+                continue;
+            }
+            let value = if options.infer_types {
+                infer_value(field)
+            } else {
+                Value::Text(field.to_string())
+            };
+            sheet.set_value(row, col as ucell, value);
+        }
+        row += 1;
+    }
+
+    if options.default_styles && book.def_style(ValueType::Number).is_none() {
+        create_default_styles(book);
+    }
+
+    book.push_sheet(sheet);
+    Ok(book.num_sheets() - 1)
+}
+
+/// Writes the sheet as CSV. All rows and columns up to the last used
+/// cell are written.
+pub fn write_csv<W: Write>(
+    book: &WorkBook,
+    idx: usize,
+    write: W,
+    options: &CsvOptions,
+) -> Result<(), OdsError> {
+    let quote_style = match options.quote_style {
+        CsvQuote::Necessary => ::csv::QuoteStyle::Necessary,
+        CsvQuote::Always => ::csv::QuoteStyle::Always,
+        CsvQuote::NonNumeric => ::csv::QuoteStyle::NonNumeric,
+        CsvQuote::Never => ::csv::QuoteStyle::Never,
+    };
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .quote_style(quote_style)
+        .from_writer(write);
+
+    let sheet = book.sheet(idx);
+    if sheet.iter().next().is_some() {
+        let (rows, cols) = sheet.used_grid_size();
+        let mut record = vec![String::new(); cols as usize];
+        for row in 0..rows {
+            for field in record.iter_mut() {
+                field.clear();
+            }
+            for (col, cell) in sheet.row_cells(row) {
+                record[col as usize] = if options.display_text {
+                    display_text(book, cell.style(), cell.value())
+                } else {
+                    raw_text(cell.value())
+                };
+            }
+            writer.write_record(&record)?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Converts the field into the most specific Value.
+fn infer_value(field: &str) -> Value {
+    let trimmed = field.trim();
+
+    if trimmed.eq_ignore_ascii_case("true") {
+        Value::Boolean(true)
+    } else if trimmed.eq_ignore_ascii_case("false") {
+        Value::Boolean(false)
+    } else if let Some(v) = parse_number(trimmed) {
+        Value::Number(v)
+    } else if let Some(v) = trimmed
+        .strip_suffix('%')
+        .and_then(|v| parse_number(v.trim_end()))
+    {
+        Value::Percentage(v / 100.0)
+    } else if let Ok(v) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        Value::DateTime(v.and_hms(0, 0, 0))
+    } else if let Ok(v) = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M:%S%.f") {
+        Value::DateTime(v)
+    } else if let Ok(v) = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S%.f") {
+        Value::DateTime(v)
+    } else {
+        Value::Text(field.to_string())
+    }
+}
+
+/// Plain decimal numbers only, no inf or NaN.
+fn parse_number(s: &str) -> Option<f64> {
+    let digits = s.strip_prefix(|c| c == '-' || c == '+').unwrap_or(s);
+    if digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
@@ -0,0 +1,404 @@
+//!
+//! Compares two workbooks cell by cell.
+//!
+//! Sheets are matched by name. A sheet that only exists in one of the
+//! workbooks is added or removed, unless the other workbook has an
+//! unmatched sheet with the same cells. Then it counts as renamed.
+//!
+//! For each sheet the values, formulas, cell styles, the direct
+//! formatting of the cells and the spans are compared. Styles and value formats are compared by name and
+//! definition.
+//!
+//! ```
+//! use spreadsheet_ods::diff::{diff_with_options, CellChange, DiffOptions};
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//!
+//! let mut old = WorkBook::new();
+//! let mut sheet = Sheet::new_with_name("Report");
+//! sheet.set_value(0, 0, 1.0);
+//! sheet.set_value(0, 1, 2.0);
+//! old.push_sheet(sheet);
+//!
+//! let mut new = old.clone();
+//! new.sheet_mut(0).set_value(0, 0, 1.0000001);
+//! new.sheet_mut(0).set_value(0, 1, 3.0);
+//!
+//! let mut options = DiffOptions::new();
+//! options.set_tolerance(1e-6);
+//! let diff = diff_with_options(&old, &new, &options);
+//!
+//! let cells = diff.sheet("Report").unwrap().cells();
+//! assert_eq!(cells.len(), 1);
+//! assert_eq!(cells[0].cell().to_string(), "Report.B1");
+//! assert!(matches!(cells[0].changes()[0], CellChange::Value { .. }));
+//! ```
+//!
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+
+use crate::style::CellFormat;
+use crate::{ucell, CellRef, SCell, Sheet, Value, WorkBook};
+
+/// Settings for the comparison.
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    tolerance: f64,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiffOptions {
+    /// Exact comparison.
+    pub fn new() -> Self {
+        Self { tolerance: 0.0 }
+    }
+
+    /// Numbers, percentages and currencies that differ by no more than
+    /// this are equal.
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    /// Tolerance for floating point values.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+}
+
+/// Differences between two workbooks.
+#[derive(Debug, Clone, Default)]
+pub struct WorkbookDiff {
+    added_sheets: Vec<String>,
+    removed_sheets: Vec<String>,
+    renamed_sheets: Vec<(String, String)>,
+    sheets: Vec<SheetDiff>,
+    styles: Vec<DefinitionChange>,
+    formats: Vec<DefinitionChange>,
+}
+
+impl WorkbookDiff {
+    /// No differences at all.
+    pub fn is_empty(&self) -> bool {
+        self.added_sheets.is_empty()
+            && self.removed_sheets.is_empty()
+            && self.renamed_sheets.is_empty()
+            && self.sheets.is_empty()
+            && self.styles.is_empty()
+            && self.formats.is_empty()
+    }
+
+    /// Sheets that only exist in the new workbook.
+    pub fn added_sheets(&self) -> &[String] {
+        &self.added_sheets
+    }
+
+    /// Sheets that only exist in the old workbook.
+    pub fn removed_sheets(&self) -> &[String] {
+        &self.removed_sheets
+    }
+
+    /// Old and new name of renamed sheets.
+    pub fn renamed_sheets(&self) -> &[(String, String)] {
+        &self.renamed_sheets
+    }
+
+    /// Changed cells of the sheets that exist in both workbooks. Only
+    /// sheets with changes are listed.
+    pub fn sheets(&self) -> &[SheetDiff] {
+        &self.sheets
+    }
+
+    /// Changed cells of the sheet, by the new name.
+    pub fn sheet(&self, name: &str) -> Option<&SheetDiff> {
+        self.sheets.iter().find(|v| v.name == name)
+    }
+
+    /// Added, removed and changed styles.
+    pub fn styles(&self) -> &[DefinitionChange] {
+        &self.styles
+    }
+
+    /// Added, removed and changed value formats.
+    pub fn formats(&self) -> &[DefinitionChange] {
+        &self.formats
+    }
+}
+
+impl Display for WorkbookDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for name in &self.added_sheets {
+            writeln!(f, "added sheet {}", name)?;
+        }
+        for name in &self.removed_sheets {
+            writeln!(f, "removed sheet {}", name)?;
+        }
+        for (old, new) in &self.renamed_sheets {
+            writeln!(f, "renamed sheet {} to {}", old, new)?;
+        }
+        for sheet in &self.sheets {
+            for cell in &sheet.cells {
+                for change in &cell.changes {
+                    writeln!(f, "{}: {}", cell.cell, change)?;
+                }
+            }
+        }
+        for change in &self.styles {
+            writeln!(f, "style {}", change)?;
+        }
+        for change in &self.formats {
+            writeln!(f, "format {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Changed cells of one sheet.
+#[derive(Debug, Clone)]
+pub struct SheetDiff {
+    name: String,
+    cells: Vec<CellDiff>,
+}
+
+impl SheetDiff {
+    /// Sheet name in the new workbook.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Changed cells in row-major order.
+    pub fn cells(&self) -> &[CellDiff] {
+        &self.cells
+    }
+
+    /// Changes of the cell.
+    pub fn cell(&self, row: ucell, col: ucell) -> Option<&CellDiff> {
+        self.cells
+            .iter()
+            .find(|v| v.cell.row() == row && v.cell.col() == col)
+    }
+}
+
+/// Changes of one cell.
+#[derive(Debug, Clone)]
+pub struct CellDiff {
+    cell: CellRef,
+    changes: Vec<CellChange>,
+}
+
+impl CellDiff {
+    /// Cell reference with the new sheet name.
+    pub fn cell(&self) -> &CellRef {
+        &self.cell
+    }
+
+    /// What changed.
+    pub fn changes(&self) -> &[CellChange] {
+        &self.changes
+    }
+}
+
+/// One change of a cell. Cells that only exist in one of the workbooks
+/// are compared with an empty cell.
+#[derive(Debug, Clone)]
+pub enum CellChange {
+    Value {
+        old: Value,
+        new: Value,
+    },
+    Formula {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    Style {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// Direct formatting of the cell.
+    Format {
+        old: Option<CellFormat>,
+        new: Option<CellFormat>,
+    },
+    /// Row and column span.
+    Span {
+        old: (ucell, ucell),
+        new: (ucell, ucell),
+    },
+}
+
+impl Display for CellChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellChange::Value { old, new } => write!(f, "value {:?} -> {:?}", old, new),
+            CellChange::Formula { old, new } => write!(f, "formula {:?} -> {:?}", old, new),
+            CellChange::Style { old, new } => write!(f, "style {:?} -> {:?}", old, new),
+            CellChange::Format { .. } => write!(f, "cell format changed"),
+            CellChange::Span { old, new } => write!(f, "span {:?} -> {:?}", old, new),
+        }
+    }
+}
+
+/// Change of a style or value format, by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefinitionChange {
+    Added(String),
+    Removed(String),
+    Changed(String),
+}
+
+impl Display for DefinitionChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefinitionChange::Added(v) => write!(f, "added {}", v),
+            DefinitionChange::Removed(v) => write!(f, "removed {}", v),
+            DefinitionChange::Changed(v) => write!(f, "changed {}", v),
+        }
+    }
+}
+
+/// Compares the workbooks exactly.
+pub fn diff(old: &WorkBook, new: &WorkBook) -> WorkbookDiff {
+    diff_with_options(old, new, &DiffOptions::new())
+}
+
+/// Compares the workbooks.
+pub fn diff_with_options(old: &WorkBook, new: &WorkBook, options: &DiffOptions) -> WorkbookDiff {
+    let mut result = WorkbookDiff::default();
+
+    let mut pairs = Vec::new();
+    let mut removed = Vec::new();
+    for (idx, sheet) in old.sheets.iter().enumerate() {
+        match new.sheets.iter().find(|v| v.name == sheet.name) {
+            Some(new_sheet) => pairs.push((sheet, new_sheet)),
+            None => removed.push(idx),
+        }
+    }
+    let mut added: Vec<usize> = (0..new.sheets.len())
+        .filter(|idx| !old.sheets.iter().any(|v| v.name == new.sheets[*idx].name))
+        .collect();
+
+    for idx in removed {
+        let sheet = &old.sheets[idx];
+        let renamed = added
+            .iter()
+            .position(|v| diff_sheet(sheet, &new.sheets[*v], options).is_empty());
+        if let Some(pos) = renamed {
+            let new_sheet = &new.sheets[added.remove(pos)];
+            result
+                .renamed_sheets
+                .push((sheet.name.clone(), new_sheet.name.clone()));
+        } else {
+            result.removed_sheets.push(sheet.name.clone());
+        }
+    }
+    for idx in added {
+        result.added_sheets.push(new.sheets[idx].name.clone());
+    }
+
+    for (old_sheet, new_sheet) in pairs {
+        let cells = diff_sheet(old_sheet, new_sheet, options);
+        if !cells.is_empty() {
+            result.sheets.push(SheetDiff {
+                name: new_sheet.name.clone(),
+                cells,
+            });
+        }
+    }
+
+    result.styles = diff_definitions(&old.styles, &new.styles);
+    result.formats = diff_definitions(&old.formats, &new.formats);
+
+    result
+}
+
+fn diff_sheet(old: &Sheet, new: &Sheet, options: &DiffOptions) -> Vec<CellDiff> {
+    let empty = SCell::new();
+    let keys: BTreeSet<&(ucell, ucell)> = old.data.keys().chain(new.data.keys()).collect();
+
+    let mut cells = Vec::new();
+    for key in keys {
+        let old_cell = old.data.get(key).unwrap_or(&empty);
+        let new_cell = new.data.get(key).unwrap_or(&empty);
+
+        let mut changes = Vec::new();
+        if !value_eq(&old_cell.value, &new_cell.value, options.tolerance) {
+            changes.push(CellChange::Value {
+                old: old_cell.value.clone(),
+                new: new_cell.value.clone(),
+            });
+        }
+        if old_cell.formula != new_cell.formula {
+            changes.push(CellChange::Formula {
+                old: old_cell.formula.clone(),
+                new: new_cell.formula.clone(),
+            });
+        }
+        if old_cell.style != new_cell.style {
+            changes.push(CellChange::Style {
+                old: old_cell.style.clone(),
+                new: new_cell.style.clone(),
+            });
+        }
+        if old_cell.format != new_cell.format {
+            changes.push(CellChange::Format {
+                old: old_cell.cell_format().cloned(),
+                new: new_cell.cell_format().cloned(),
+            });
+        }
+        if old_cell.span != new_cell.span {
+            changes.push(CellChange::Span {
+                old: old_cell.span,
+                new: new_cell.span,
+            });
+        }
+
+        if !changes.is_empty() {
+            cells.push(CellDiff {
+                cell: CellRef::remote(new.name.as_str(), key.0, key.1),
+                changes,
+            });
+        }
+    }
+
+    cells
+}
+
+/// Equal values, floats within the tolerance.
+fn value_eq(old: &Value, new: &Value, tolerance: f64) -> bool {
+    let float_eq =
+        |a: f64, b: f64| a == b || (a.is_nan() && b.is_nan()) || (a - b).abs() <= tolerance;
+
+    match (old, new) {
+        (Value::Empty, Value::Empty) => true,
+        (Value::Boolean(a), Value::Boolean(b)) => a == b,
+        (Value::Number(a), Value::Number(b)) => float_eq(*a, *b),
+        (Value::Percentage(a), Value::Percentage(b)) => float_eq(*a, *b),
+        (Value::Currency(c, a), Value::Currency(d, b)) => c == d && float_eq(*a, *b),
+        (Value::Text(a), Value::Text(b)) => a == b,
+        (Value::TextXml(a), Value::TextXml(b)) => a == b,
+        (Value::DateTime(a), Value::DateTime(b)) => a == b,
+        (Value::TimeDuration(a), Value::TimeDuration(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Added, removed and changed definitions, sorted by name.
+fn diff_definitions<T: PartialEq>(
+    old: &HashMap<String, T>,
+    new: &HashMap<String, T>,
+) -> Vec<DefinitionChange> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| match (old.get(name), new.get(name)) {
+            (Some(_), None) => Some(DefinitionChange::Removed(name.clone())),
+            (None, Some(_)) => Some(DefinitionChange::Added(name.clone())),
+            (Some(a), Some(b)) if a != b => Some(DefinitionChange::Changed(name.clone())),
+            _ => None,
+        })
+        .collect()
+}
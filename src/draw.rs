@@ -0,0 +1,245 @@
+//!
+//! Drawing shapes.
+//!
+//! Rectangles, ellipses, lines, connectors and text boxes. A shape is
+//! either placed on the sheet or anchored to a cell, and refers to a
+//! graphic style for fill and stroke.
+//!
+//! ```
+//! use color::Rgb;
+//! use spreadsheet_ods::{cm, Length, Sheet, Style, WorkBook};
+//! use spreadsheet_ods::draw::Shape;
+//! use spreadsheet_ods::style::AttrGraphic;
+//!
+//! let mut wb = WorkBook::new();
+//!
+//! let mut style = Style::new_graphic_style("callout");
+//! style.graphic_mut().set_fill_color(Rgb::new(255, 255, 204));
+//! style.graphic_mut().set_stroke(cm!(0.05), Rgb::new(255, 0, 0));
+//! wb.add_style(style);
+//!
+//! let mut sheet = Sheet::new();
+//! let mut shape = Shape::new_text_box(cm!(1), cm!(0.5), cm!(5), cm!(1.5), "Check this!");
+//! shape.set_style("callout");
+//! shape.set_anchor_cell(3, 2);
+//! sheet.add_shape(shape);
+//!
+//! let mut shape = Shape::new_line(cm!(0), cm!(0), cm!(4), cm!(2));
+//! shape.set_style("callout");
+//! sheet.add_shape(shape);
+//!
+//! wb.push_sheet(sheet);
+//! ```
+//!
+
+use crate::{ucell, Length};
+
+/// Kind of shape.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ShapeType {
+    /// draw:rect
+    Rect,
+    /// draw:ellipse
+    Ellipse,
+    /// draw:line
+    Line,
+    /// draw:connector
+    Connector,
+    /// draw:frame with a draw:text-box
+    TextBox,
+}
+
+/// A drawing shape.
+///
+/// The position is relative to the anchor cell, if there is one,
+/// otherwise relative to the top left corner of the sheet.
+///
+/// Lines and connectors use position and end point, the other shapes
+/// use position and size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shape {
+    shape_type: ShapeType,
+    name: String,
+    style: Option<String>,
+    text_style: Option<String>,
+    z_index: Option<u32>,
+    anchor: Option<(ucell, ucell)>,
+    x: Length,
+    y: Length,
+    width: Length,
+    height: Length,
+    x2: Length,
+    y2: Length,
+    text: Option<String>,
+}
+
+impl Shape {
+    /// Empty shape of the given type at the top left corner.
+    pub fn new(shape_type: ShapeType) -> Self {
+        Self {
+            shape_type,
+            name: String::new(),
+            style: None,
+            text_style: None,
+            z_index: None,
+            anchor: None,
+            x: Length::Cm(0.0),
+            y: Length::Cm(0.0),
+            width: Length::Cm(0.0),
+            height: Length::Cm(0.0),
+            x2: Length::Cm(0.0),
+            y2: Length::Cm(0.0),
+            text: None,
+        }
+    }
+
+    /// Rectangle.
+    pub fn new_rect(x: Length, y: Length, width: Length, height: Length) -> Self {
+        let mut shape = Shape::new(ShapeType::Rect);
+        shape.set_position(x, y);
+        shape.set_size(width, height);
+        shape
+    }
+
+    /// Ellipse within the given rectangle.
+    pub fn new_ellipse(x: Length, y: Length, width: Length, height: Length) -> Self {
+        let mut shape = Shape::new(ShapeType::Ellipse);
+        shape.set_position(x, y);
+        shape.set_size(width, height);
+        shape
+    }
+
+    /// Line from (x1, y1) to (x2, y2).
+    pub fn new_line(x1: Length, y1: Length, x2: Length, y2: Length) -> Self {
+        let mut shape = Shape::new(ShapeType::Line);
+        shape.set_position(x1, y1);
+        shape.set_end(x2, y2);
+        shape
+    }
+
+    /// Connector from (x1, y1) to (x2, y2).
+    pub fn new_connector(x1: Length, y1: Length, x2: Length, y2: Length) -> Self {
+        let mut shape = Shape::new(ShapeType::Connector);
+        shape.set_position(x1, y1);
+        shape.set_end(x2, y2);
+        shape
+    }
+
+    /// Text box.
+    pub fn new_text_box<S: Into<String>>(
+        x: Length,
+        y: Length,
+        width: Length,
+        height: Length,
+        text: S,
+    ) -> Self {
+        let mut shape = Shape::new(ShapeType::TextBox);
+        shape.set_position(x, y);
+        shape.set_size(width, height);
+        shape.set_text(text);
+        shape
+    }
+
+    /// Kind of shape.
+    pub fn shape_type(&self) -> ShapeType {
+        self.shape_type
+    }
+
+    /// Name of the shape.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        self.name = name.into();
+    }
+
+    /// Name of the shape.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Graphic style for fill and stroke.
+    pub fn set_style<S: Into<String>>(&mut self, style: S) {
+        self.style = Some(style.into());
+    }
+
+    /// Graphic style for fill and stroke.
+    pub fn style(&self) -> Option<&String> {
+        self.style.as_ref()
+    }
+
+    /// Paragraph style for the text.
+    pub fn set_text_style<S: Into<String>>(&mut self, style: S) {
+        self.text_style = Some(style.into());
+    }
+
+    /// Paragraph style for the text.
+    pub fn text_style(&self) -> Option<&String> {
+        self.text_style.as_ref()
+    }
+
+    /// Drawing order. Shapes with a higher z-index are drawn on top.
+    pub fn set_z_index(&mut self, z_index: u32) {
+        self.z_index = Some(z_index);
+    }
+
+    /// Drawing order.
+    pub fn z_index(&self) -> Option<u32> {
+        self.z_index
+    }
+
+    /// Anchors the shape to a cell. The shape moves with the cell.
+    pub fn set_anchor_cell(&mut self, row: ucell, col: ucell) {
+        self.anchor = Some((row, col));
+    }
+
+    /// Anchors the shape to the sheet.
+    pub fn clear_anchor_cell(&mut self) {
+        self.anchor = None;
+    }
+
+    /// Anchor cell.
+    pub fn anchor_cell(&self) -> Option<(ucell, ucell)> {
+        self.anchor
+    }
+
+    /// Position, or the start point for lines.
+    pub fn set_position(&mut self, x: Length, y: Length) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Position, or the start point for lines.
+    pub fn position(&self) -> (Length, Length) {
+        (self.x, self.y)
+    }
+
+    /// Size. Not used for lines.
+    pub fn set_size(&mut self, width: Length, height: Length) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Size. Not used for lines.
+    pub fn size(&self) -> (Length, Length) {
+        (self.width, self.height)
+    }
+
+    /// End point of lines and connectors.
+    pub fn set_end(&mut self, x2: Length, y2: Length) {
+        self.x2 = x2;
+        self.y2 = y2;
+    }
+
+    /// End point of lines and connectors.
+    pub fn end(&self) -> (Length, Length) {
+        (self.x2, self.y2)
+    }
+
+    /// Text content. Line breaks start a new paragraph.
+    pub fn set_text<S: Into<String>>(&mut self, text: S) {
+        self.text = Some(text.into());
+    }
+
+    /// Text content.
+    pub fn text(&self) -> Option<&String> {
+        self.text.as_ref()
+    }
+}
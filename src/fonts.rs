@@ -0,0 +1,148 @@
+//!
+//! Font-face declarations for the fonts used by the styles.
+//!
+
+use std::collections::BTreeSet;
+
+use crate::style::{AttrFontDecl, AttrMap, FontFaceDecl, FontPitch};
+use crate::WorkBook;
+
+/// Attributes that reference a font-face declaration.
+pub(crate) const FONT_ATTRS: [&str; 3] = [
+    "style:font-name",
+    "style:font-name-asian",
+    "style:font-name-complex",
+];
+
+/// Generic family and pitch of some common fonts. Everything else is
+/// declared as "system" with a variable pitch.
+const KNOWN_FONTS: [(&str, &str, FontPitch); 14] = [
+    ("Liberation Sans", "swiss", FontPitch::Variable),
+    ("Liberation Serif", "roman", FontPitch::Variable),
+    ("Liberation Mono", "modern", FontPitch::Fixed),
+    ("DejaVu Sans", "swiss", FontPitch::Variable),
+    ("DejaVu Serif", "roman", FontPitch::Variable),
+    ("DejaVu Sans Mono", "modern", FontPitch::Fixed),
+    ("Arial", "swiss", FontPitch::Variable),
+    ("Helvetica", "swiss", FontPitch::Variable),
+    ("Calibri", "swiss", FontPitch::Variable),
+    ("Times New Roman", "roman", FontPitch::Variable),
+    ("Cambria", "roman", FontPitch::Variable),
+    ("Courier New", "modern", FontPitch::Fixed),
+    ("Consolas", "modern", FontPitch::Fixed),
+    ("Noto Sans", "swiss", FontPitch::Variable),
+];
+
+/// Name of the font-face declaration for the font family. Creates a new
+/// declaration if there is none yet.
+pub(crate) fn font_for(book: &mut WorkBook, family: &str) -> String {
+    let found = book.fonts.values().find(|font| match font.font_family() {
+        Some(v) => unquote(v) == family,
+        None => font.name() == family,
+    });
+    if let Some(font) = found {
+        return font.name().clone();
+    }
+
+    let mut name = family.to_string();
+    let mut n = 1;
+    while book.fonts.contains_key(&name) {
+        name = format!("{}{}", family, n);
+        n += 1;
+    }
+    book.fonts
+        .insert(name.clone(), new_font_decl(&name, family));
+    name
+}
+
+/// Font names that are used by some style, value format or direct cell
+/// formatting but have no declaration.
+pub(crate) fn missing_fonts(book: &WorkBook) -> Vec<String> {
+    let text_attrs = book
+        .styles
+        .values()
+        .map(|v| v.text())
+        .chain(book.formats.values().map(|v| v.text()))
+        .chain(
+            book.sheets
+                .iter()
+                .flat_map(|v| v.data.values())
+                .filter_map(|v| v.cell_format())
+                .map(|v| v.text()),
+        );
+
+    let mut missing = BTreeSet::new();
+    for text in text_attrs {
+        for attr in FONT_ATTRS.iter() {
+            if let Some(name) = text.attr(attr) {
+                if !book.fonts.contains_key(name) {
+                    missing.insert(name.clone());
+                }
+            }
+        }
+    }
+    missing.into_iter().collect()
+}
+
+/// Declares the missing fonts. The font name is used as font family.
+pub(crate) fn create_missing_fonts(book: &mut WorkBook) {
+    for name in missing_fonts(book) {
+        let font = new_font_decl(&name, &name);
+        book.fonts.insert(name, font);
+    }
+}
+
+fn new_font_decl(name: &str, family: &str) -> FontFaceDecl {
+    let mut font = FontFaceDecl::new_with_name(name);
+    font.set_font_family(quote(family));
+    match KNOWN_FONTS.iter().find(|(v, _, _)| *v == family) {
+        Some((_, generic, pitch)) => {
+            font.set_font_family_generic(*generic);
+            font.set_font_pitch(*pitch);
+        }
+        None => {
+            font.set_font_family_generic("system");
+            font.set_font_pitch(FontPitch::Variable);
+        }
+    }
+    font
+}
+
+/// svg:font-family uses the css syntax. Family names are quoted unless
+/// they are a single identifier.
+fn quote(family: &str) -> String {
+    let identifier = !family.is_empty()
+        && !family.starts_with(|c: char| c.is_ascii_digit())
+        && family
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if identifier {
+        family.to_string()
+    } else {
+        format!("'{}'", family.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// Family name without css quotes.
+pub(crate) fn unquote(family: &str) -> String {
+    let family = family.trim();
+    let quoted = family.len() >= 2
+        && ((family.starts_with('\'') && family.ends_with('\''))
+            || (family.starts_with('"') && family.ends_with('"')));
+    if quoted {
+        let mut res = String::new();
+        let mut chars = family[1..family.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                if let Some(c) = chars.next() {
+                    res.push(c);
+                }
+            } else {
+                res.push(c);
+            }
+        }
+        res
+    } else {
+        family.to_string()
+    }
+}
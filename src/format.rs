@@ -29,18 +29,22 @@
 
 use std::fmt::{Display, Formatter};
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, NaiveTime};
 use time::Duration;
 
 use crate::attrmap::{AttrMap, AttrMapType};
+use crate::formatcode::parse_format_code;
 use crate::sealed::Sealed;
 use crate::style::{StyleMap, StyleOrigin, StyleUse, TextAttr};
-use crate::ValueType;
+use crate::{Value, ValueType, WorkBook};
 
 #[derive(Debug)]
 pub enum ValueFormatError {
     Format(String),
     NaN,
+    /// Invalid format code. Position of the character, counted in chars,
+    /// and a message.
+    Parse(usize, String),
 }
 
 impl Display for ValueFormatError {
@@ -48,6 +52,7 @@ impl Display for ValueFormatError {
         match self {
             ValueFormatError::Format(s) => write!(f, "{}", s)?,
             ValueFormatError::NaN => write!(f, "Digit expected")?,
+            ValueFormatError::Parse(pos, s) => write!(f, "{} at position {}", s, pos)?,
         }
         Ok(())
     }
@@ -56,7 +61,7 @@ impl Display for ValueFormatError {
 impl std::error::Error for ValueFormatError {}
 
 /// Actual textual formatting of values.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct ValueFormat {
     /// Name
     name: String,
@@ -126,6 +131,55 @@ impl ValueFormat {
         }
     }
 
+    /// Parses a format code like `#,##0.00 €;[RED]-#,##0.00 €`.
+    ///
+    /// The code can have up to four sections separated by ';'. With two
+    /// sections the first one is for values >= 0, with three sections
+    /// the first two are for values greater and less than zero. A fourth
+    /// section or a section with '@' formats text. Explicit conditions like `[>100]`
+    /// replace the defaults, the condition of the last section is
+    /// ignored. Colours like `[RED]` set the text color.
+    ///
+    /// Dates and times use codes like DD.MM.YYYY HH:MM:SS, MMM or NNN for
+    /// the names of months and days, and `[HH]` for elapsed time in a
+    /// TimeDuration format. Numbers can be fractions like # ?/? or
+    /// scientific like 0.00E+00.
+    ///
+    /// The locale is a tag like "de-AT". It sets the attributes
+    /// number:language and number:country of the formats and currency
+    /// symbols.
+    ///
+    /// Each section but the last becomes a format of its own, with the
+    /// name suffixed by P0, P1, .... The last format is the one to use,
+    /// it references the others with style maps. All of them must be
+    /// added to the workbook.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{ValueFormat, ValueType, WorkBook};
+    ///
+    /// let mut wb = WorkBook::new();
+    /// let formats = ValueFormat::from_format_code(
+    ///     "eur",
+    ///     "#,##0.00 €;[RED]-#,##0.00 €",
+    ///     ValueType::Currency,
+    ///     "de-AT",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(formats.len(), 2);
+    /// assert_eq!(formats[1].name(), "eur");
+    /// for format in formats {
+    ///     wb.add_format(format);
+    /// }
+    /// ```
+    pub fn from_format_code<S: Into<String>>(
+        name: S,
+        code: &str,
+        value_type: ValueType,
+        locale: &str,
+    ) -> Result<Vec<ValueFormat>, ValueFormatError> {
+        parse_format_code(name.into(), code, value_type, locale)
+    }
+
     /// Sets the name.
     pub fn set_name<S: Into<String>>(&mut self, name: S) {
         self.name = name.into();
@@ -446,7 +500,7 @@ pub enum FormatPartType {
 }
 
 /// One structural part of a value format.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FormatPart {
     /// What kind of format part is this?
     part_type: FormatPartType,
@@ -1018,3 +1072,64 @@ pub fn create_time_format<S: Into<String>>(name: S) -> ValueFormat {
     v.push_seconds(FormatNumberStyle::Long);
     v
 }
+
+/// Text of the raw value. Dates without a time are written as dates.
+pub(crate) fn raw_text(value: &Value) -> String {
+    match value {
+        Value::Empty => String::new(),
+        Value::Boolean(v) => v.to_string(),
+        Value::Number(v) | Value::Percentage(v) | Value::Currency(_, v) => v.to_string(),
+        Value::Text(v) => v.clone(),
+        Value::TextXml(v) => v.text_content(),
+        Value::DateTime(v) => {
+            if v.time() == NaiveTime::from_hms(0, 0, 0) {
+                v.format("%Y-%m-%d").to_string()
+            } else {
+                v.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+            }
+        }
+        Value::TimeDuration(v) => duration_string(v),
+    }
+}
+
+/// Duration in the ISO 8601 form of office:time-value, with a single
+/// sign in front.
+pub(crate) fn duration_string(v: &Duration) -> String {
+    let ms = v.num_milliseconds();
+    let sign = if ms < 0 { "-" } else { "" };
+    let ms = ms.unsigned_abs();
+    format!(
+        "{}PT{}H{}M{}.{:03}S",
+        sign,
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Text as formatted by the value format of the cell style, or of the
+/// default style for the value type. Falls back to the raw value if
+/// there is no format.
+pub(crate) fn display_text(book: &WorkBook, style: Option<&String>, value: &Value) -> String {
+    let style = style.or_else(|| book.def_style(value.value_type()));
+    format_value(style.and_then(|s| book.find_value_format(s)), value)
+}
+
+/// Text as formatted by the value format. Falls back to the raw value if
+/// there is no format.
+pub(crate) fn format_value(format: Option<&ValueFormat>, value: &Value) -> String {
+    let format = match format {
+        Some(format) => format,
+        None => return raw_text(value),
+    };
+
+    match value {
+        Value::Boolean(v) => format.format_boolean(*v),
+        Value::Number(v) | Value::Percentage(v) | Value::Currency(_, v) => format.format_float(*v),
+        Value::Text(v) => format.format_str(v.as_str()),
+        Value::DateTime(v) => format.format_datetime(v),
+        Value::TimeDuration(v) => format.format_time_duration(v),
+        Value::Empty | Value::TextXml(_) => raw_text(value),
+    }
+}
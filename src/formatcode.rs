@@ -0,0 +1,821 @@
+//!
+//! Parses format codes like "#,##0.00 €;[RED]-#,##0.00 €" into value
+//! formats.
+//!
+//! A code has up to four sections separated by ';'. With two sections
+//! the first one is used for values >= 0, with three sections the first
+//! two are for values greater and less than zero, and the last one is for
+//! the rest. The fourth section, or a section with '@', formats text.
+//! Conditions like [>100] replace the default conditions.
+//!
+//! Each section except the last one becomes a value format of its own,
+//! with the suffix P0, P1, ... The last one references them with style
+//! maps.
+//!
+
+use color::Rgb;
+
+use crate::attrmap::AttrMap;
+use crate::format::{
+    FormatCalendarStyle, FormatNumberStyle, FormatPart, FormatPartType, ValueFormat,
+    ValueFormatError,
+};
+use crate::refs::CellRef;
+use crate::style::{AttrText, StyleMap};
+use crate::ValueType;
+
+/// Colours that can be used in a format code.
+const COLORS: [(&str, (u8, u8, u8)); 8] = [
+    ("BLACK", (0, 0, 0)),
+    ("BLUE", (0, 0, 255)),
+    ("CYAN", (0, 255, 255)),
+    ("GREEN", (0, 255, 0)),
+    ("MAGENTA", (255, 0, 255)),
+    ("RED", (255, 0, 0)),
+    ("WHITE", (255, 255, 255)),
+    ("YELLOW", (255, 255, 0)),
+];
+
+/// Parses the format code. The last format is the one to use, the others
+/// are referenced by its style maps.
+pub(crate) fn parse_format_code(
+    name: String,
+    code: &str,
+    value_type: ValueType,
+    locale: &str,
+) -> Result<Vec<ValueFormat>, ValueFormatError> {
+    let chars: Vec<char> = code.chars().collect();
+    parse_chars(name, &chars, value_type, &Locale::parse(locale), false)
+}
+
+/// Parses a format code of an xlsx file. The value type follows from the
+/// first section: dates and times, durations for [h], [m] or [s], text
+/// for @, percentages for % and currencies for [$...]. Unknown brackets
+/// are ignored, letters that are no codes for the value type are literal
+/// text.
+///
+/// None for "General".
+pub(crate) fn parse_excel_format_code(
+    name: String,
+    code: &str,
+) -> Result<Option<(ValueType, Vec<ValueFormat>)>, ValueFormatError> {
+    let chars: Vec<char> = code.chars().collect();
+    let first: String = chars.iter().take_while(|c| **c != ';').collect();
+    if first.trim().eq_ignore_ascii_case("general") {
+        return Ok(None);
+    }
+    let value_type = excel_value_type(&chars);
+    let formats = parse_chars(name, &chars, value_type, &Locale::default(), true)?;
+    Ok(Some((value_type, formats)))
+}
+
+/// Value type of an xlsx format code, from its first section.
+fn excel_value_type(chars: &[char]) -> ValueType {
+    let mut value_type = ValueType::Number;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            ';' => break,
+            '"' => match chars[i + 1..].iter().position(|c| *c == '"') {
+                Some(v) => i += v + 1,
+                None => break,
+            },
+            '\\' | '_' | '*' => i += 1,
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map(|v| i + v)
+                    .unwrap_or(chars.len());
+                let inner: String = chars[i + 1..end].iter().collect();
+                let inner = inner.to_lowercase();
+                if !inner.is_empty() && inner.chars().all(|c| c == 'h' || c == 'm' || c == 's') {
+                    value_type = ValueType::TimeDuration;
+                } else if inner.starts_with('$')
+                    && !inner.starts_with("$-")
+                    && inner.len() > 1
+                    && value_type == ValueType::Number
+                {
+                    value_type = ValueType::Currency;
+                }
+                i = end;
+            }
+            _ if starts_with_ignore_case(&chars[i..], "GENERAL") => i += 6,
+            'E' | 'e' if matches!(chars.get(i + 1), Some('+') | Some('-')) => i += 1,
+            '%' if value_type == ValueType::Number => value_type = ValueType::Percentage,
+            '@' => value_type = ValueType::Text,
+            'y' | 'Y' | 'e' | 'E' | 'd' | 'D' | 'm' | 'M' | 'h' | 'H' | 's' | 'S'
+                if value_type != ValueType::TimeDuration =>
+            {
+                value_type = ValueType::DateTime
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    value_type
+}
+
+fn parse_chars(
+    name: String,
+    chars: &[char],
+    value_type: ValueType,
+    locale: &Locale,
+    excel: bool,
+) -> Result<Vec<ValueFormat>, ValueFormatError> {
+    if chars.iter().all(|c| c.is_whitespace()) {
+        return Err(error(0, "Empty format code"));
+    }
+
+    let mut sections = Vec::new();
+    for (idx, (start, end)) in split_sections(chars)?.into_iter().enumerate() {
+        // the fourth section is always for text
+        let value_type = if idx == 3 {
+            ValueType::Text
+        } else {
+            value_type
+        };
+        sections.push(parse_section(chars, start, end, value_type, locale, excel)?);
+    }
+
+    let text_section = if sections.last().map(|v| v.text).unwrap_or(false) {
+        sections.pop()
+    } else {
+        None
+    };
+    if let Some(section) = sections.iter().find(|v| v.text) {
+        return Err(error(
+            section.start,
+            "Only the last section can format text",
+        ));
+    }
+    if let Some(section) = &text_section {
+        if section.condition.is_some() {
+            return Err(error(
+                section.start,
+                "The text section can't have a condition",
+            ));
+        }
+    }
+
+    let count = sections.len();
+    let mapped = if text_section.is_some() {
+        count
+    } else {
+        count - 1
+    };
+    let mut sections = sections.into_iter();
+    let mut formats = Vec::new();
+    let mut stylemaps = Vec::new();
+    for idx in 0..mapped {
+        let section = sections.next().expect("section");
+        let section_name = format!("{}P{}", name, idx);
+        let condition = match &section.condition {
+            Some(condition) => condition.clone(),
+            None => default_condition(count, idx).to_string(),
+        };
+        stylemaps.push(StyleMap::new(
+            condition,
+            section_name.as_str(),
+            CellRef::new(),
+        ));
+        formats.push(section.into_format(section_name, value_type, locale));
+    }
+
+    let section = match text_section {
+        Some(section) => section,
+        None => sections.next().expect("section"),
+    };
+    let mut format = section.into_format(name, value_type, locale);
+    for stylemap in stylemaps {
+        format.push_stylemap(stylemap);
+    }
+    formats.push(format);
+
+    Ok(formats)
+}
+
+fn error<S: Into<String>>(pos: usize, msg: S) -> ValueFormatError {
+    ValueFormatError::Parse(pos, msg.into())
+}
+
+/// Condition for a section without an explicit one.
+fn default_condition(sections: usize, idx: usize) -> &'static str {
+    match (sections, idx) {
+        (3, 0) => "value()>0",
+        (3, 2) => "value()=0",
+        (_, 1) => "value()<0",
+        _ => "value()>=0",
+    }
+}
+
+/// Start and end of the sections.
+fn split_sections(chars: &[char]) -> Result<Vec<(usize, usize)>, ValueFormatError> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => match chars[i + 1..].iter().position(|c| *c == '"') {
+                Some(v) => i += v + 2,
+                None => return Err(error(i, "Missing closing quote")),
+            },
+            '\\' | '_' | '*' => i += 2,
+            ';' => {
+                if sections.len() == 3 {
+                    return Err(error(i, "More than four sections"));
+                }
+                sections.push((start, i));
+                start = i + 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    sections.push((start, chars.len()));
+    Ok(sections)
+}
+
+/// Language, script and country of a locale like "de-AT" or "sr-Latn-RS".
+#[derive(Debug, Default)]
+struct Locale {
+    language: Option<String>,
+    script: Option<String>,
+    country: Option<String>,
+}
+
+impl Locale {
+    fn parse(locale: &str) -> Self {
+        let mut res = Locale::default();
+        let tags = locale.split(&['-', '_'][..]).filter(|v| !v.is_empty());
+        for (idx, tag) in tags.enumerate() {
+            if idx == 0 {
+                res.language = Some(tag.to_lowercase());
+            } else if tag.len() == 4 && res.script.is_none() && res.country.is_none() {
+                res.script = Some(tag.to_string());
+            } else if res.country.is_none() {
+                res.country = Some(tag.to_uppercase());
+            }
+        }
+        res
+    }
+
+    fn apply_to<A: AttrMap>(&self, map: &mut A) {
+        if let Some(language) = &self.language {
+            map.set_attr("number:language", language.clone());
+        }
+        if let Some(script) = &self.script {
+            map.set_attr("number:script", script.clone());
+        }
+        if let Some(country) = &self.country {
+            map.set_attr("number:country", country.clone());
+        }
+    }
+}
+
+/// One parsed section of the code.
+struct Section {
+    /// Position of the first character.
+    start: usize,
+    parts: Vec<FormatPart>,
+    /// Condition as used by a style map.
+    condition: Option<String>,
+    color: Option<Rgb<u8>>,
+    /// Formats text.
+    text: bool,
+    /// Contains an elapsed time like [HH].
+    elapsed: bool,
+}
+
+impl Section {
+    fn into_format(self, name: String, value_type: ValueType, locale: &Locale) -> ValueFormat {
+        let value_type = if self.text {
+            ValueType::Text
+        } else {
+            value_type
+        };
+        let mut format = ValueFormat::new_with_name(name, value_type);
+        locale.apply_to(&mut format);
+        if let Some(color) = self.color {
+            format.text_mut().set_color(color);
+        }
+        if self.elapsed {
+            format.set_attr("number:truncate-on-overflow", "false".to_string());
+        }
+        for part in self.parts {
+            format.push_part(part);
+        }
+        format
+    }
+}
+
+fn parse_section(
+    chars: &[char],
+    start: usize,
+    end: usize,
+    value_type: ValueType,
+    locale: &Locale,
+    excel: bool,
+) -> Result<Section, ValueFormatError> {
+    let mut parser = SectionParser {
+        chars,
+        end,
+        pos: start,
+        value_type,
+        locale,
+        excel,
+        section: Section {
+            start,
+            parts: Vec::new(),
+            condition: None,
+            color: None,
+            text: value_type == ValueType::Text,
+            elapsed: false,
+        },
+        text: String::new(),
+        value: false,
+        number: false,
+        currency: false,
+    };
+    while parser.pos < parser.end {
+        parser.token()?;
+    }
+    parser.push_text();
+    Ok(parser.section)
+}
+
+struct SectionParser<'a> {
+    chars: &'a [char],
+    end: usize,
+    pos: usize,
+    value_type: ValueType,
+    locale: &'a Locale,
+    /// Lenient parsing of xlsx format codes.
+    excel: bool,
+    section: Section,
+    /// Literal text that is not yet a part.
+    text: String,
+    /// Has a number, date, time or boolean part.
+    value: bool,
+    /// Has a number part.
+    number: bool,
+    /// Has a currency symbol.
+    currency: bool,
+}
+
+impl<'a> SectionParser<'a> {
+    /// Character at i within the section.
+    fn at(&self, i: usize) -> Option<char> {
+        if i < self.end {
+            Some(self.chars[i])
+        } else {
+            None
+        }
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        starts_with_ignore_case(&self.chars[self.pos..self.end], keyword)
+    }
+
+    /// Appends the collected literal text as a part.
+    fn push_text(&mut self) {
+        if !self.text.is_empty() {
+            self.section
+                .parts
+                .push(FormatPart::new_text(std::mem::take(&mut self.text)));
+        }
+    }
+
+    fn push_value(&mut self, pos: usize, part: FormatPart) -> Result<(), ValueFormatError> {
+        if self.section.text {
+            return Err(error(pos, "Text and value in one section"));
+        }
+        self.value = true;
+        self.push_text();
+        self.section.parts.push(part);
+        Ok(())
+    }
+
+    fn push_currency(&mut self, symbol: &str) {
+        self.push_text();
+        let mut part = FormatPart::new_with_content(FormatPartType::CurrencySymbol, symbol);
+        self.locale.apply_to(&mut part);
+        self.section.parts.push(part);
+        self.currency = true;
+    }
+
+    fn expect_number(&self, pos: usize) -> Result<(), ValueFormatError> {
+        match self.value_type {
+            ValueType::Number | ValueType::Percentage | ValueType::Currency => {
+                if self.number {
+                    Err(error(pos, "Only one number per section"))
+                } else {
+                    Ok(())
+                }
+            }
+            value_type => Err(error(pos, format!("Number in a {:?} format", value_type))),
+        }
+    }
+
+    fn token(&mut self) -> Result<(), ValueFormatError> {
+        let i = self.pos;
+        let c = self.chars[i];
+        let next = self.at(i + 1);
+        match c {
+            '"' => {
+                let close = (i + 1..self.end)
+                    .find(|j| self.chars[*j] == '"')
+                    .ok_or_else(|| error(i, "Missing closing quote"))?;
+                self.text.extend(&self.chars[i + 1..close]);
+                self.pos = close + 1;
+            }
+            '\\' => {
+                let next = next.ok_or_else(|| error(i, "Missing character after '\\'"))?;
+                self.text.push(next);
+                self.pos += 2;
+            }
+            '_' | '*' => {
+                if next.is_none() {
+                    return Err(error(i, format!("Missing character after '{}'", c)));
+                }
+                self.pos += 2;
+            }
+            '[' => self.bracket()?,
+            '0' | '#' | '?' => self.number()?,
+            '.' | ',' if matches!(next, Some('0') | Some('#') | Some('?')) => self.number()?,
+            '%' => {
+                self.text.push('%');
+                self.pos += 1;
+            }
+            '@' => {
+                if self.value {
+                    return Err(error(i, "Text and value in one section"));
+                }
+                self.push_text();
+                self.section.parts.push(FormatPart::new_text_content());
+                self.section.text = true;
+                self.pos += 1;
+            }
+            _ if self.keyword("GENERAL") || self.keyword("STANDARD") => {
+                self.expect_number(i)?;
+                let mut part = FormatPart::new(FormatPartType::Number);
+                part.set_attr("number:min-integer-digits", 1.to_string());
+                self.push_value(i, part)?;
+                self.number = true;
+                self.pos += if self.keyword("GENERAL") { 7 } else { 8 };
+            }
+            _ if self.keyword("BOOLEAN") => {
+                if self.value_type != ValueType::Boolean {
+                    return Err(error(
+                        i,
+                        format!("Boolean in a {:?} format", self.value_type),
+                    ));
+                }
+                self.push_value(i, FormatPart::new_boolean())?;
+                self.pos += 7;
+            }
+            _ if self.keyword("AM/PM") || self.keyword("A/P") => {
+                if !matches!(
+                    self.value_type,
+                    ValueType::DateTime | ValueType::TimeDuration
+                ) {
+                    return Err(error(i, format!("AM/PM in a {:?} format", self.value_type)));
+                }
+                self.pos += if self.keyword("A/P") { 3 } else { 5 };
+                self.push_value(i, FormatPart::new_am_pm())?;
+            }
+            'Y' | 'y' | 'M' | 'm' | 'D' | 'd' | 'H' | 'h' | 'S' | 's' | 'E' | 'e' | 'G' | 'g'
+            | 'Q' | 'q' | 'W' | 'w' | 'N' | 'n' => self.date_time()?,
+            '€' | '$' | '£' | '¥' if self.value_type == ValueType::Currency && !self.currency =>
+            {
+                self.push_currency(&c.to_string());
+                self.pos += 1;
+            }
+            c => {
+                self.text.push(c);
+                self.pos += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Elapsed time, currency, condition or colour.
+    fn bracket(&mut self) -> Result<(), ValueFormatError> {
+        let i = self.pos;
+        let close = (i + 1..self.end)
+            .find(|j| self.chars[*j] == ']')
+            .ok_or_else(|| error(i, "Missing ']'"))?;
+        let inner: String = self.chars[i + 1..close].iter().collect();
+        let upper = inner.to_uppercase();
+
+        if !upper.is_empty() && upper.chars().all(|c| c == 'H' || c == 'M' || c == 'S') {
+            if self.value_type != ValueType::TimeDuration {
+                return Err(error(
+                    i,
+                    format!("[{}] in a {:?} format", inner, self.value_type),
+                ));
+            }
+            let first = upper.chars().next();
+            if !upper.chars().all(|c| Some(c) == first) {
+                return Err(error(i, format!("Invalid elapsed time [{}]", inner)));
+            }
+            let style = number_style(upper.len());
+            let part = match first {
+                Some('H') => FormatPart::new_hours(style),
+                Some('M') => FormatPart::new_minutes(style),
+                _ => FormatPart::new_seconds(style),
+            };
+            self.push_value(i, part)?;
+            self.section.elapsed = true;
+        } else if let Some(currency) = inner.strip_prefix('$') {
+            // [$€-407] is a currency, [$-407] only a locale
+            let symbol = currency.split('-').next().unwrap_or("");
+            if !symbol.is_empty() {
+                self.push_currency(symbol);
+            }
+        } else if inner.starts_with(&['<', '>', '='][..]) {
+            self.condition(i + 1, &inner)?;
+        } else if let Some((_, (r, g, b))) = COLORS.iter().find(|(v, _)| *v == upper) {
+            self.section.color = Some(Rgb::new(*r, *g, *b));
+        } else if !self.excel {
+            return Err(error(i, format!("Unknown [{}]", inner)));
+        }
+
+        self.pos = close + 1;
+        Ok(())
+    }
+
+    /// Condition like [>=100] starting at pos.
+    fn condition(&mut self, pos: usize, condition: &str) -> Result<(), ValueFormatError> {
+        if self.section.condition.is_some() {
+            return Err(error(pos - 1, "Only one condition per section"));
+        }
+        let (op, len) = if condition.starts_with("<>") {
+            ("!=", 2)
+        } else if condition.starts_with("<=") || condition.starts_with(">=") {
+            (&condition[..2], 2)
+        } else {
+            (&condition[..1], 1)
+        };
+        let value = condition[len..].trim();
+        if value.parse::<f64>().is_err() {
+            return Err(error(
+                pos + len,
+                format!("Number expected in [{}]", condition),
+            ));
+        }
+        self.section.condition = Some(format!("value(){}{}", op, value));
+        Ok(())
+    }
+
+    /// Number, fraction or scientific number.
+    fn number(&mut self) -> Result<(), ValueFormatError> {
+        let start = self.pos;
+        self.expect_number(start)?;
+        let is_digit = |c: Option<char>| matches!(c, Some('0') | Some('#') | Some('?'));
+
+        let mut int = String::new();
+        let mut dec = String::new();
+        let mut in_dec = false;
+        let mut i = start;
+        while let Some(c) = self.at(i) {
+            match c {
+                '0' | '#' | '?' if in_dec => dec.push(c),
+                '0' | '#' | '?' => int.push(c),
+                ',' if !in_dec && is_digit(self.at(i + 1)) => int.push(c),
+                '.' if !in_dec && is_digit(self.at(i + 1)) => in_dec = true,
+                _ => break,
+            }
+            i += 1;
+        }
+        // trailing commas divide by 1000
+        let mut scale = 0;
+        while self.at(i) == Some(',') {
+            scale += 1;
+            i += 1;
+        }
+        let grouping = int.contains(',');
+        let min_int = int.matches('0').count();
+
+        // fractions, with or without the integer part
+        if !in_dec && scale == 0 {
+            if self.at(i) == Some('/') {
+                return self.fraction(start, None, int.len(), i);
+            }
+            if self.at(i) == Some(' ') {
+                let mut j = i + 1;
+                while is_digit(self.at(j)) {
+                    j += 1;
+                }
+                if j > i + 1 && self.at(j) == Some('/') {
+                    return self.fraction(start, Some(&int), j - i - 1, j);
+                }
+            }
+        }
+
+        let part = if matches!(self.at(i), Some('E') | Some('e'))
+            && matches!(self.at(i + 1), Some('+') | Some('-'))
+        {
+            let mut j = i + 2;
+            while is_digit(self.at(j)) {
+                j += 1;
+            }
+            if j == i + 2 {
+                return Err(error(i + 2, "Exponent expected"));
+            }
+            let mut part = FormatPart::new_scientific(dec.len() as u8);
+            part.set_attr("number:min-integer-digits", min_int.to_string());
+            part.set_attr("number:min-exponent-digits", (j - i - 2).to_string());
+            if grouping {
+                part.set_attr("number:grouping", "true".to_string());
+            }
+            i = j;
+            part
+        } else {
+            let mut part = FormatPart::new(FormatPartType::Number);
+            part.set_attr("number:min-integer-digits", min_int.to_string());
+            part.set_attr("number:decimal-places", dec.len().to_string());
+            part.set_attr(
+                "loext:min-decimal-places",
+                dec.matches('0').count().to_string(),
+            );
+            if grouping {
+                part.set_attr("number:grouping", "true".to_string());
+            }
+            if scale > 0 {
+                part.set_attr("number:display-factor", format!("1{}", "000".repeat(scale)));
+            }
+            part
+        };
+
+        self.push_value(start, part)?;
+        self.number = true;
+        self.pos = i;
+        Ok(())
+    }
+
+    /// Fraction with the slash at the given position.
+    fn fraction(
+        &mut self,
+        start: usize,
+        int: Option<&str>,
+        numerator: usize,
+        slash: usize,
+    ) -> Result<(), ValueFormatError> {
+        let mut i = slash + 1;
+        let mut denominator = String::new();
+        while let Some(c) = self.at(i) {
+            if c.is_ascii_digit() || c == '?' || c == '#' {
+                denominator.push(c);
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        if denominator.is_empty() {
+            return Err(error(slash + 1, "Denominator expected"));
+        }
+
+        let mut part = FormatPart::new(FormatPartType::Fraction);
+        if let Some(int) = int {
+            part.set_attr(
+                "number:min-integer-digits",
+                int.matches('0').count().to_string(),
+            );
+            if int.contains(',') {
+                part.set_attr("number:grouping", "true".to_string());
+            }
+        }
+        part.set_attr("number:min-numerator-digits", numerator.to_string());
+        if denominator.chars().all(|c| c.is_ascii_digit()) {
+            let value = denominator
+                .parse::<u32>()
+                .map_err(|_| error(slash + 1, "Denominator is too big"))?;
+            part.set_attr("number:denominator-value", value.to_string());
+        }
+        part.set_attr(
+            "number:min-denominator-digits",
+            denominator.len().to_string(),
+        );
+
+        self.push_value(start, part)?;
+        self.number = true;
+        self.pos = i;
+        Ok(())
+    }
+
+    /// Date and time codes. Letters are literal text in a text section,
+    /// and in xlsx codes if they are no codes for the value type.
+    fn date_time(&mut self) -> Result<(), ValueFormatError> {
+        let start = self.pos;
+        let c = self.chars[start].to_ascii_lowercase();
+        let mut n = 1;
+        while self.at(start + n).map(|v| v.to_ascii_lowercase()) == Some(c) {
+            n += 1;
+        }
+        let excel_text = self.excel && matches!(c, 'g' | 'q' | 'w' | 'n');
+        if self.value_type == ValueType::Text || excel_text {
+            self.text.extend(&self.chars[start..start + n]);
+            self.pos += n;
+            return Ok(());
+        }
+
+        let time = c == 'h'
+            || c == 's'
+            || (c == 'm'
+                && (self.value_type == ValueType::TimeDuration
+                    || is_minute(&self.section.parts, &self.chars[start + n..self.end])));
+        match self.value_type {
+            ValueType::DateTime => {}
+            ValueType::TimeDuration if time => {}
+            _ if self.excel => {
+                self.text.extend(&self.chars[start..start + n]);
+                self.pos += n;
+                return Ok(());
+            }
+            value_type => {
+                let what = if time { "Time" } else { "Date" };
+                return Err(error(
+                    start,
+                    format!("{} in a {:?} format", what, value_type),
+                ));
+            }
+        }
+
+        let calendar = FormatCalendarStyle::Gregorian;
+        let part = match c {
+            'y' | 'e' => FormatPart::new_year(number_style(if n <= 2 { 1 } else { 2 })),
+            'g' => FormatPart::new_era(number_style(if n <= 2 { 1 } else { 2 }), calendar),
+            'q' => FormatPart::new_quarter(number_style(n), calendar),
+            'w' => FormatPart::new_week_of_year(calendar),
+            'n' if n >= 2 => FormatPart::new_day_of_week(number_style(n - 1), calendar),
+            'n' => return Err(error(start, "Invalid date code N")),
+            'd' if n <= 2 => FormatPart::new_day(number_style(n)),
+            'd' => FormatPart::new_day_of_week(number_style(n - 2), calendar),
+            'h' => FormatPart::new_hours(number_style(n)),
+            's' => {
+                let mut part = FormatPart::new_seconds(number_style(n));
+                if self.at(start + n) == Some('.') {
+                    let mut decimals = 0;
+                    while self.at(start + n + 1 + decimals) == Some('0') {
+                        decimals += 1;
+                    }
+                    if decimals > 0 {
+                        part.set_attr("number:decimal-places", decimals.to_string());
+                        n += decimals + 1;
+                    }
+                }
+                part
+            }
+            _ if time => FormatPart::new_minutes(number_style(n)),
+            _ => {
+                // MM and MMMM are long, MMM is the short name
+                let mut part = FormatPart::new_month(if n == 2 || n >= 4 {
+                    FormatNumberStyle::Long
+                } else {
+                    FormatNumberStyle::Short
+                });
+                if n >= 3 {
+                    part.set_attr("number:textual", "true".to_string());
+                }
+                part
+            }
+        };
+        self.push_value(start, part)?;
+        self.pos = start + n;
+        Ok(())
+    }
+}
+
+fn starts_with_ignore_case(chars: &[char], prefix: &str) -> bool {
+    prefix.chars().enumerate().all(|(i, p)| {
+        chars
+            .get(i)
+            .map(|c| c.eq_ignore_ascii_case(&p))
+            .unwrap_or(false)
+    })
+}
+
+fn number_style(n: usize) -> FormatNumberStyle {
+    if n >= 2 {
+        FormatNumberStyle::Long
+    } else {
+        FormatNumberStyle::Short
+    }
+}
+
+/// m is a minute after hours or before seconds.
+fn is_minute(parts: &[FormatPart], rest: &[char]) -> bool {
+    let after_hours = parts
+        .iter()
+        .rev()
+        .find(|p| p.part_type() != FormatPartType::Text)
+        .map(|p| p.part_type() == FormatPartType::Hours)
+        .unwrap_or(false);
+    let before_seconds = rest
+        .iter()
+        .find(|c| c.is_ascii_alphabetic() || **c == '[')
+        .map(|c| c.eq_ignore_ascii_case(&'s') || *c == '[')
+        .unwrap_or(false);
+    after_hours || before_seconds
+}
@@ -1,8 +1,13 @@
 //!
-//! For now defines functions to create cell references for formulas.
+//! For now defines functions to create cell references for formulas,
+//! and the conversion from and to the A1 syntax of Excel.
 //!
 
-use crate::refs::{CellRange, CellRef};
+use std::convert::TryFrom;
+
+use crate::refs::{
+    parse_cellrange, push_colname, push_rowname, push_tablename, CellRange, CellRef,
+};
 use crate::ucell;
 
 // TODO: more formula stuff. parsing?
@@ -32,3 +37,324 @@ pub fn frangeref_table<S: Into<String>>(
 ) -> String {
     CellRange::remote(table, row, col, row_to, col_to).to_formula()
 }
+
+/// Converts an OpenFormula expression as used by set_formula() to the
+/// A1 syntax of Excel. The "of:=" prefix is removed, references lose
+/// their brackets and dots, and the parameter separator ';' becomes ','.
+///
+/// ```
+/// use spreadsheet_ods::formula::to_excel_formula;
+///
+/// assert_eq!(
+///     to_excel_formula("of:=SUM([.A1:.B3];['Sheet 2'.$C$1])"),
+///     "SUM(A1:B3,'Sheet 2'!$C$1)"
+/// );
+/// ```
+pub fn to_excel_formula(formula: &str) -> String {
+    let formula = formula.strip_prefix("of:").unwrap_or(formula);
+    let formula = formula.strip_prefix('=').unwrap_or(formula);
+
+    let mut buf = String::new();
+    let mut chars = formula.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => {
+                buf.push(c);
+                for (_, c) in chars.by_ref() {
+                    buf.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+            }
+            '[' => {
+                let mut end = formula.len();
+                let mut quoted = false;
+                for (p, c) in chars.by_ref() {
+                    match c {
+                        '\'' => quoted = !quoted,
+                        ']' if !quoted => {
+                            end = p;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                push_excel_ref(&mut buf, &formula[idx + 1..end]);
+            }
+            ';' => buf.push(','),
+            c => buf.push(c),
+        }
+    }
+    buf
+}
+
+/// Appends the reference in A1 syntax. Whole rows or columns are only
+/// stripped of the dots.
+fn push_excel_ref(buf: &mut String, reference: &str) {
+    let mut pos = 0;
+    match parse_cellrange(reference, &mut pos) {
+        Ok(range) if pos == reference.len() => {
+            if let Some(table) = range.table() {
+                push_excel_tablename(buf, table);
+            }
+            if range.col_abs() {
+                buf.push('$');
+            }
+            push_colname(buf, range.col());
+            if range.row_abs() {
+                buf.push('$');
+            }
+            push_rowname(buf, range.row());
+            if reference.contains(':') {
+                buf.push(':');
+                if range.to_col_abs() {
+                    buf.push('$');
+                }
+                push_colname(buf, range.to_col());
+                if range.to_row_abs() {
+                    buf.push('$');
+                }
+                push_rowname(buf, range.to_row());
+            }
+        }
+        _ => {
+            let reference = reference.strip_prefix('.').unwrap_or(reference);
+            buf.push_str(&reference.replace(":.", ":"));
+        }
+    }
+}
+
+/// Appends the sheet name and '!', quoted if necessary.
+fn push_excel_tablename(buf: &mut String, table: &str) {
+    let plain = table.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && table.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if plain {
+        buf.push_str(table);
+    } else {
+        buf.push('\'');
+        buf.push_str(&table.replace('\'', "''"));
+        buf.push('\'');
+    }
+    buf.push('!');
+}
+
+/// Converts a formula in the A1 syntax of Excel to OpenFormula, the
+/// result starts with "of:=". References get brackets and dots,
+/// the parameter separator ',' becomes ';' and the "_xlfn." prefix
+/// of newer functions is removed.
+///
+/// ```
+/// use spreadsheet_ods::formula::from_excel_formula;
+///
+/// assert_eq!(
+///     from_excel_formula("SUM(A1:B3,'Sheet 2'!$C$1)"),
+///     "of:=SUM([.A1:.B3];['Sheet 2'.$C$1])"
+/// );
+/// ```
+pub fn from_excel_formula(formula: &str) -> String {
+    from_excel_formula_moved(formula, 0, 0)
+}
+
+/// Converts the formula and moves all relative references. Used for
+/// the shared formulas of xlsx, where only the first cell contains
+/// the formula.
+pub(crate) fn from_excel_formula_moved(formula: &str, rows: i64, cols: i64) -> String {
+    let formula = formula.strip_prefix('=').unwrap_or(formula);
+
+    let mut buf = String::from("of:=");
+    let mut in_array = false;
+    let mut pos = 0;
+    while pos < formula.len() {
+        let rest = &formula[pos..];
+        let c = rest.chars().next().unwrap_or_default();
+
+        if c == '"' {
+            let end = rest[1..].find('"').map(|v| v + 2).unwrap_or(rest.len());
+            buf.push_str(&rest[..end]);
+            pos += end;
+        } else if c == '\'' || c == '$' || c.is_alphanumeric() || c == '_' {
+            if let Some(len) = push_of_ref(&mut buf, rest, rows, cols) {
+                pos += len;
+            } else if c == '\'' {
+                buf.push(c);
+                pos += 1;
+            } else {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '$'))
+                    .unwrap_or(rest.len());
+                let token = &rest[..len];
+                let token = token
+                    .strip_prefix("_xlfn.")
+                    .or_else(|| token.strip_prefix("_xlws."))
+                    .unwrap_or(token);
+                buf.push_str(token);
+                pos += len;
+            }
+        } else {
+            match c {
+                '{' => in_array = true,
+                '}' => in_array = false,
+                _ => {}
+            }
+            match c {
+                ',' => buf.push(';'),
+                ';' if in_array => buf.push('|'),
+                c => buf.push(c),
+            }
+            pos += c.len_utf8();
+        }
+    }
+    buf
+}
+
+/// One part of an A1 reference, column and/or row.
+#[derive(Debug, Clone, Copy)]
+struct ExcelRefPart {
+    col: Option<(bool, i64)>,
+    row: Option<(bool, i64)>,
+}
+
+/// Parses a reference at the start of buf and appends it in the
+/// OpenFormula syntax. Returns the consumed length.
+fn push_of_ref(buf: &mut String, s: &str, rows: i64, cols: i64) -> Option<usize> {
+    let mut pos = 0;
+    let table = parse_excel_tablename(s, &mut pos);
+
+    let from = parse_excel_refpart(s, &mut pos)?;
+    let to = if s[pos..].starts_with(':') {
+        let mut to_pos = pos + 1;
+        let to = parse_excel_refpart(s, &mut to_pos)?;
+        pos = to_pos;
+        Some(to)
+    } else {
+        None
+    };
+
+    // whole rows or columns only as a range, a single cell needs both.
+    match (to, from.col, from.row) {
+        (None, Some(_), Some(_)) => {}
+        (Some(to), Some(_), Some(_)) if to.col.is_some() && to.row.is_some() => {}
+        (Some(to), Some(_), None) if to.col.is_some() && to.row.is_none() => {}
+        (Some(to), None, Some(_)) if to.col.is_none() && to.row.is_some() => {}
+        _ => return None,
+    }
+    // no function call or longer name
+    if s[pos..].starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '(' || c == '.') {
+        return None;
+    }
+
+    let mut reference = String::from("[");
+    push_tablename(&mut reference, table.as_ref());
+    push_of_refpart(&mut reference, from, rows, cols)?;
+    if let Some(to) = to {
+        reference.push_str(":.");
+        push_of_refpart(&mut reference, to, rows, cols)?;
+    }
+    reference.push(']');
+    buf.push_str(&reference);
+
+    Some(pos)
+}
+
+/// Parses "Sheet1!" or "'Sheet 1'!". Leaves pos unchanged if there
+/// is none.
+fn parse_excel_tablename(s: &str, pos: &mut usize) -> Option<String> {
+    if s.starts_with('\'') {
+        let mut name = String::new();
+        let mut chars = s.char_indices().skip(1).peekable();
+        while let Some((p, c)) = chars.next() {
+            if c == '\'' {
+                if let Some((_, '\'')) = chars.peek() {
+                    name.push('\'');
+                    chars.next();
+                } else if s[p + 1..].starts_with('!') {
+                    *pos = p + 2;
+                    return Some(name);
+                } else {
+                    return None;
+                }
+            } else {
+                name.push(c);
+            }
+        }
+        None
+    } else {
+        let len = s.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))?;
+        if len > 0 && s[len..].starts_with('!') {
+            *pos = len + 1;
+            Some(s[..len].to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Parses a cell, a column or a row with the $ markers.
+fn parse_excel_refpart(s: &str, pos: &mut usize) -> Option<ExcelRefPart> {
+    let mut p = *pos;
+    let bytes = s.as_bytes();
+
+    let col_abs = bytes.get(p) == Some(&b'$');
+    if col_abs {
+        p += 1;
+    }
+    let col_start = p;
+    let mut col = 0i64;
+    while p < bytes.len() && bytes[p].is_ascii_alphabetic() && p - col_start < 3 {
+        col = col * 26 + i64::from(bytes[p].to_ascii_uppercase() - b'A' + 1);
+        p += 1;
+    }
+    let col = if p > col_start {
+        Some((col_abs, col - 1))
+    } else if col_abs {
+        // the $ belongs to the row
+        p -= 1;
+        None
+    } else {
+        None
+    };
+
+    let row_abs = bytes.get(p) == Some(&b'$');
+    if row_abs {
+        p += 1;
+    }
+    let row_start = p;
+    let mut row = 0i64;
+    while p < bytes.len() && bytes[p].is_ascii_digit() {
+        row = row.checked_mul(10)? + i64::from(bytes[p] - b'0');
+        p += 1;
+    }
+    let row = if p > row_start && row > 0 {
+        Some((row_abs, row - 1))
+    } else if p > row_start || row_abs {
+        return None;
+    } else {
+        None
+    };
+
+    if col.is_none() && row.is_none() {
+        return None;
+    }
+    *pos = p;
+    Some(ExcelRefPart { col, row })
+}
+
+/// Appends the part moved by rows and cols.
+fn push_of_refpart(buf: &mut String, part: ExcelRefPart, rows: i64, cols: i64) -> Option<()> {
+    if let Some((abs, col)) = part.col {
+        let col = if abs { col } else { col + cols };
+        if abs {
+            buf.push('$');
+        }
+        push_colname(buf, ucell::try_from(col).ok()?);
+    }
+    if let Some((abs, row)) = part.row {
+        let row = if abs { row } else { row + rows };
+        if abs {
+            buf.push('$');
+        }
+        push_rowname(buf, ucell::try_from(row).ok()?);
+    }
+    Some(())
+}
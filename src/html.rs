@@ -0,0 +1,420 @@
+//!
+//! HTML export.
+//!
+//! Each sheet becomes a table. Row and column spans, hidden rows and
+//! columns and the column widths are kept, the cell text is formatted
+//! with the ValueFormat of the cell style. The cell styles are converted
+//! to CSS classes, including the attributes of their parent styles.
+//!
+//! ```
+//! use color::Rgb;
+//! use spreadsheet_ods::{html, Sheet, Style, WorkBook};
+//! use spreadsheet_ods::style::{AttrFoBackgroundColor, AttrText};
+//!
+//! let mut wb = WorkBook::new();
+//!
+//! let mut style = Style::new_cell_style("head", "");
+//! style.cell_mut().set_background_color(Rgb::new(192, 192, 192));
+//! style.text_mut().set_font_bold();
+//! wb.add_style(style);
+//!
+//! let mut sheet = Sheet::new_with_name("Report");
+//! sheet.set_styled_value(0, 0, "Summary", "head");
+//! sheet.set_col_span(0, 0, 2);
+//! sheet.set_value(1, 0, "Total");
+//! sheet.set_value(1, 1, 42);
+//! wb.push_sheet(sheet);
+//!
+//! let html = html::sheet_to_html(&wb, 0);
+//! assert!(html.contains(".ods-head { background-color: #c0c0c0; font-weight: bold; }"));
+//! assert!(html.contains("<td class=\"ods-head\" colspan=\"2\">Summary</td>"));
+//! ```
+//!
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use crate::fonts;
+use crate::format::display_text;
+use crate::style::{AttrMap, Style, StyleFor};
+use crate::stylepool::with_automatic_styles;
+use crate::{ucell, OdsError, Sheet, Visibility, WorkBook};
+
+/// Writes all displayed sheets as one HTML document.
+pub fn write_html<P: AsRef<Path>>(book: &WorkBook, path: P) -> Result<(), OdsError> {
+    let mut file = File::create(path)?;
+    file.write_all(to_html(book).as_bytes())?;
+    Ok(())
+}
+
+/// All displayed sheets as one HTML document.
+pub fn to_html(book: &WorkBook) -> String {
+    let book: &WorkBook = &with_automatic_styles(book);
+    let sheets: Vec<usize> = (0..book.num_sheets())
+        .filter(|idx| book.sheet(*idx).display())
+        .collect();
+
+    let mut buf = String::new();
+    buf.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    push_css(book, &sheets, &mut buf);
+    buf.push_str("</head>\n<body>\n");
+    for idx in sheets {
+        push_table(book, idx, &mut buf);
+    }
+    buf.push_str("</body>\n</html>\n");
+    buf
+}
+
+/// One sheet as a table, preceded by a style element with the CSS
+/// classes it uses. Can be embedded in another page.
+pub fn sheet_to_html(book: &WorkBook, idx: usize) -> String {
+    let book: &WorkBook = &with_automatic_styles(book);
+    let mut buf = String::new();
+    push_css(book, &[idx], &mut buf);
+    push_table(book, idx, &mut buf);
+    buf
+}
+
+/// Writes the CSS classes for all cell styles used in the sheets.
+fn push_css(book: &WorkBook, sheets: &[usize], buf: &mut String) {
+    let mut used = BTreeSet::new();
+    for idx in sheets {
+        let sheet = book.sheet(*idx);
+        let (rows, cols) = sheet.used_grid_size();
+        used.extend(sheet.iter().filter_map(|(_, cell)| cell.style()));
+        used.extend((0..rows).filter_map(|row| sheet.row_cell_style(row)));
+        used.extend((0..cols).filter_map(|col| sheet.column_cell_style(col)));
+    }
+
+    buf.push_str("<style>\n");
+    buf.push_str("table.ods { border-collapse: collapse; }\n");
+    for style in used {
+        let css = style_css(book, style);
+        if css.is_empty() {
+            continue;
+        }
+        buf.push('.');
+        buf.push_str(&class_name(style));
+        buf.push_str(" {");
+        for (k, v) in css {
+            buf.push(' ');
+            buf.push_str(k);
+            buf.push_str(": ");
+            buf.push_str(&v);
+            buf.push(';');
+        }
+        buf.push_str(" }\n");
+    }
+    buf.push_str("</style>\n");
+}
+
+/// Writes the table for one sheet.
+fn push_table(book: &WorkBook, idx: usize, buf: &mut String) {
+    let sheet = book.sheet(idx);
+
+    buf.push_str("<table class=\"ods\">\n<caption>");
+    push_escaped(sheet.name(), buf);
+    buf.push_str("</caption>\n");
+
+    if sheet.iter().next().is_none() {
+        buf.push_str("</table>\n");
+        return;
+    }
+
+    let (rows, cols) = sheet.used_grid_size();
+    let visible_col = |col: ucell| sheet.column_visible(col) == Visibility::Visible;
+    let visible_row = |row: ucell| sheet.row_visible(row) == Visibility::Visible;
+
+    buf.push_str("<colgroup>");
+    for col in (0..cols).filter(|c| visible_col(*c)) {
+        let width = sheet
+            .column_style(col)
+            .and_then(|s| book.style(s))
+            .filter(|s| s.family() == StyleFor::TableColumn)
+            .and_then(|s| s.col().attr("style:column-width"));
+        match width {
+            Some(width) => {
+                buf.push_str("<col style=\"width: ");
+                push_escaped(&css_value(width), buf);
+                buf.push_str("\">");
+            }
+            None => buf.push_str("<col>"),
+        }
+    }
+    buf.push_str("</colgroup>\n");
+
+    let mut covered = HashSet::new();
+    for row in 0..rows {
+        let row_visible = visible_row(row);
+        if row_visible {
+            let height = sheet
+                .row_style(row)
+                .and_then(|s| book.style(s))
+                .filter(|s| s.family() == StyleFor::TableRow)
+                .and_then(|s| s.row().attr("style:row-height"));
+            match height {
+                Some(height) => {
+                    buf.push_str("<tr style=\"height: ");
+                    push_escaped(&css_value(height), buf);
+                    buf.push_str("\">");
+                }
+                None => buf.push_str("<tr>"),
+            }
+        }
+
+        for col in 0..cols {
+            if covered.contains(&(row, col)) {
+                continue;
+            }
+            let cell = sheet.cell(row, col);
+            let (row_end, col_end) = match cell {
+                Some(cell) => (
+                    rows.min(row + cell.row_span().max(1)),
+                    cols.min(col + cell.col_span().max(1)),
+                ),
+                None => (row + 1, col + 1),
+            };
+            for r in row..row_end {
+                for c in col..col_end {
+                    if r != row || c != col {
+                        covered.insert((r, c));
+                    }
+                }
+            }
+            if !row_visible || !visible_col(col) {
+                continue;
+            }
+
+            let style = cell_style(sheet, row, col);
+            buf.push_str("<td");
+            if let Some(style) = style {
+                buf.push_str(" class=\"");
+                buf.push_str(&class_name(style));
+                buf.push('"');
+            }
+            let row_span = (row..row_end).filter(|r| visible_row(*r)).count();
+            if row_span > 1 {
+                buf.push_str(&format!(" rowspan=\"{}\"", row_span));
+            }
+            let col_span = (col..col_end).filter(|c| visible_col(*c)).count();
+            if col_span > 1 {
+                buf.push_str(&format!(" colspan=\"{}\"", col_span));
+            }
+            buf.push('>');
+            if let Some(cell) = cell {
+                push_escaped(&display_text(book, style, cell.value()), buf);
+            }
+            buf.push_str("</td>");
+        }
+
+        if row_visible {
+            buf.push_str("</tr>\n");
+        }
+    }
+
+    buf.push_str("</table>\n");
+}
+
+/// Style of the cell, or the default cell style of the row or column.
+fn cell_style(sheet: &Sheet, row: ucell, col: ucell) -> Option<&String> {
+    sheet
+        .cell(row, col)
+        .and_then(|cell| cell.style())
+        .or_else(|| sheet.row_cell_style(row))
+        .or_else(|| sheet.column_cell_style(col))
+}
+
+/// CSS class for a style name. Any other character than a letter, digit or
+/// '-' is written as '_' and its hex code point, so different style names
+/// never share a class.
+fn class_name(style: &str) -> String {
+    let mut name = String::from("ods-");
+    for c in style.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            name.push(c);
+        } else {
+            name.push_str(&format!("_{:x}_", c as u32));
+        }
+    }
+    name
+}
+
+/// Drops the characters that could end the declaration, the rule or the
+/// style element. The values come from the file and can't be trusted.
+fn css_value(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '<' | '>' | '{' | '}' | ';' | '"' | '\'' | '\\'))
+        .collect()
+}
+
+/// Font families that are keywords in CSS.
+const GENERIC_FAMILIES: [&str; 5] = ["serif", "sans-serif", "monospace", "cursive", "fantasy"];
+
+/// A list of font families with each name in single quotes. Generic
+/// families like sans-serif stay unquoted.
+fn css_font_family(families: &str) -> String {
+    families
+        .split(',')
+        .map(|family| css_value(&fonts::unquote(family)))
+        .filter(|family| !family.trim().is_empty())
+        .map(|family| {
+            let family = family.trim();
+            if GENERIC_FAMILIES.contains(&family) {
+                family.to_string()
+            } else {
+                format!("'{}'", family)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// CSS declarations for the style and all its parents.
+fn style_css<'a>(book: &'a WorkBook, name: &'a str) -> BTreeMap<&'static str, String> {
+    let mut chain: Vec<&Style> = Vec::new();
+    let mut next = Some(name);
+    while let Some(name) = next {
+        match book.style(name) {
+            Some(style) if !chain.iter().any(|s| s.name() == style.name()) => {
+                chain.push(style);
+                next = style.parent().map(|v| v.as_str());
+            }
+            _ => break,
+        }
+    }
+
+    let mut css = BTreeMap::new();
+    for style in chain.iter().rev() {
+        push_style_css(book, style, &mut css);
+    }
+    css
+}
+
+/// Converts the attributes of one style. Overwrites the values of
+/// parent styles. All values are passed through css_value().
+fn push_style_css(book: &WorkBook, style: &Style, css: &mut BTreeMap<&'static str, String>) {
+    if style.family() == StyleFor::TableCell {
+        let cell = style.cell();
+        if let Some(v) = cell.attr("fo:background-color") {
+            css.insert("background-color", css_value(v));
+        }
+        if let Some(v) = cell.attr("fo:border") {
+            for side in &["border-top", "border-right", "border-bottom", "border-left"] {
+                css.insert(*side, css_value(v));
+            }
+        }
+        for side in &["border-top", "border-right", "border-bottom", "border-left"] {
+            if let Some(v) = cell.attr(&format!("fo:{}", side)) {
+                css.insert(*side, css_value(v));
+            }
+        }
+        if let Some(v) = cell.attr("fo:padding") {
+            for side in &[
+                "padding-top",
+                "padding-right",
+                "padding-bottom",
+                "padding-left",
+            ] {
+                css.insert(*side, css_value(v));
+            }
+        }
+        for side in &[
+            "padding-top",
+            "padding-right",
+            "padding-bottom",
+            "padding-left",
+        ] {
+            if let Some(v) = cell.attr(&format!("fo:{}", side)) {
+                css.insert(*side, css_value(v));
+            }
+        }
+        match cell.attr("style:vertical-align").map(|v| v.as_str()) {
+            Some("automatic") => {
+                css.remove("vertical-align");
+            }
+            Some(v) => {
+                css.insert("vertical-align", css_value(v));
+            }
+            None => {}
+        }
+        match cell.attr("fo:wrap-option").map(|v| v.as_str()) {
+            Some("wrap") => {
+                css.insert("white-space", "normal".to_string());
+            }
+            Some("no-wrap") => {
+                css.insert("white-space", "nowrap".to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let paragraph = style.paragraph();
+    if let Some(v) = paragraph.attr("fo:text-align") {
+        let align = match v.as_str() {
+            "start" => "left",
+            "end" => "right",
+            v => v,
+        };
+        css.insert("text-align", css_value(align));
+    }
+
+    let text = style.text();
+    if let Some(v) = text.attr("fo:font-weight") {
+        css.insert("font-weight", css_value(v));
+    }
+    if let Some(v) = text.attr("fo:font-style") {
+        css.insert("font-style", css_value(v));
+    }
+    if let Some(v) = text.attr("fo:font-size") {
+        css.insert("font-size", css_value(v));
+    }
+    if let Some(v) = text.attr("fo:color") {
+        css.insert("color", css_value(v));
+    }
+    if let Some(v) = text.attr("style:font-name") {
+        let family = match book.font(v).and_then(|f| f.attr("svg:font-family")) {
+            Some(family) => css_font_family(family),
+            None => css_font_family(v),
+        };
+        css.insert("font-family", family);
+    }
+    if let Some(v) = text.attr("fo:font-family") {
+        css.insert("font-family", css_font_family(v));
+    }
+
+    let underline = text
+        .attr("style:text-underline-style")
+        .map(|v| v.as_str() != "none");
+    let line_through = text
+        .attr("style:text-line-through-style")
+        .map(|v| v.as_str() != "none");
+    if underline.is_some() || line_through.is_some() {
+        let current = css.get("text-decoration").map(|v| v.as_str()).unwrap_or("");
+        let underline = underline.unwrap_or_else(|| current.contains("underline"));
+        let line_through = line_through.unwrap_or_else(|| current.contains("line-through"));
+        let decoration = match (underline, line_through) {
+            (true, true) => "underline line-through",
+            (true, false) => "underline",
+            (false, true) => "line-through",
+            (false, false) => "none",
+        };
+        css.insert("text-decoration", decoration.to_string());
+    }
+}
+
+/// Escapes the text, line breaks become br.
+fn push_escaped(s: &str, buf: &mut String) {
+    for c in s.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            '\n' => buf.push_str("<br>"),
+            c => buf.push(c),
+        }
+    }
+}
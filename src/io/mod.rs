@@ -1,9 +1,13 @@
 pub use read::read_ods;
-pub use write::write_ods;
+pub use write::{write_ods, write_ods_with_options, MissingFonts, OdsWriteOptions};
+pub use xlsx_read::read_xlsx;
+pub use xlsx_write::write_xlsx;
 
 pub use crate::error::OdsError;
 
 mod read;
 mod tmp2zip;
 mod write;
+mod xlsx_read;
+mod xlsx_write;
 mod xmlwriter;
@@ -6,18 +6,24 @@ use chrono::{Duration, NaiveDate, NaiveDateTime};
 use quick_xml::events::{BytesStart, Event};
 use zip::read::ZipFile;
 
-use crate::attrmap::AttrMap;
+use crate::attrmap::{parse_color, AttrMap};
+use crate::chart::{Chart, ChartType, Series};
+use crate::draw::{Shape, ShapeType};
 use crate::error::OdsError;
 use crate::format::{FormatPart, FormatPartType};
-use crate::refs::{parse_cellranges, parse_cellref, CellRef};
+use crate::protection::{ProtectionDigest, ProtectionOptions, SheetProtection};
+use crate::refs::{parse_cellrange, parse_cellranges, parse_cellref, CellRange, CellRef};
+use crate::scenario::Scenario;
 use crate::style::{
-    FontFaceDecl, HeaderFooter, PageLayout, Style, StyleFor, StyleMap, StyleOrigin, StyleUse,
-    TabStop,
+    default_style_name, FontFaceDecl, HeaderFooter, PageLayout, Style, StyleFor, StyleMap,
+    StyleOrigin, StyleUse, TabStop,
 };
 use crate::text::TextTag;
-use crate::xmltree::XmlTag;
+use crate::trackedchanges::{ChangeType, TrackedChange};
+use crate::xmltree::{XmlContent, XmlTag};
 use crate::{
-    ucell, ColRange, RowRange, SCell, Sheet, Value, ValueFormat, ValueType, Visibility, WorkBook,
+    ucell, ColRange, Length, RowRange, SCell, Sheet, Value, ValueFormat, ValueType, Visibility,
+    WorkBook,
 };
 
 /// Reads an ODS-file.
@@ -30,6 +36,8 @@ pub fn read_ods<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
     book.file = Some(path.as_ref().to_path_buf());
 
     read_content(&mut book, &mut zip.by_name("content.xml")?)?;
+    read_tracked_changes(&mut book)?;
+    read_charts(&mut book, &mut zip)?;
     read_styles(&mut book, &mut zip.by_name("styles.xml")?)?;
 
     Ok(book)
@@ -199,7 +207,14 @@ fn read_table(
                 /* epilogue */
                 xml_tag.name() == b"table:named-expressions" ||
                 xml_tag.name() == b"calcext:conditional-formats" => {
-                sheet.extra.push(read_xml(xml_tag.name(), xml, &xml_tag, empty_tag)?);
+                let tag = read_xml(xml_tag.name(), xml, &xml_tag, empty_tag)?;
+                if tag.name() == "table:shapes" {
+                    read_table_shapes(&mut sheet, tag)?;
+                } else if tag.name() == "table:scenario" {
+                    sheet.set_scenario(read_scenario(&tag)?);
+                } else {
+                    sheet.extra.push(tag);
+                }
             }
 
             Event::End(xml_tag)
@@ -214,6 +229,11 @@ fn read_table(
                 xml_tag.name() == b"table:named-expressions" ||
                 xml_tag.name() == b"calcext:conditional-formats" => {}
 
+            Event::Empty(xml_tag)
+            if xml_tag.name() == b"loext:table-protection" => {
+                read_table_protection(&mut sheet, xml, &xml_tag)?;
+            }
+
             Event::Start(xml_tag)
             if xml_tag.name() == b"table:table-header-columns" => {
                 col_range_from = table_col;
@@ -302,6 +322,10 @@ fn read_table_attr(
     xml: &quick_xml::Reader<BufReader<&mut ZipFile>>,
     xml_tag: BytesStart,
 ) -> Result<(), OdsError> {
+    let mut protected = false;
+    let mut protection_key = None;
+    let mut protection_digest = ProtectionDigest::Sha1;
+
     for attr in xml_tag.attributes().with_checks(false) {
         match attr? {
             attr if attr.key == b"table:name" => {
@@ -325,6 +349,18 @@ fn read_table_attr(
                 let mut pos = 0usize;
                 sheet.print_ranges = parse_cellranges(v.as_str(), &mut pos)?;
             }
+            attr if attr.key == b"table:protected" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                protected = v.parse()?;
+            }
+            attr if attr.key == b"table:protection-key" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                protection_key = Some(v);
+            }
+            attr if attr.key == b"table:protection-key-digest-algorithm" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                protection_digest = v.parse()?;
+            }
             attr => {
                 if cfg!(feature = "dump_unused") {
                     let n = xml.decode(xml_tag.name())?;
@@ -336,6 +372,92 @@ fn read_table_attr(
         }
     }
 
+    if protected {
+        let mut options = ProtectionOptions::new();
+        options.set_digest(protection_digest);
+        sheet.set_protection(SheetProtection::new_with_key(protection_key, options));
+    }
+
+    Ok(())
+}
+
+// Settings of a scenario sheet.
+fn read_scenario(tag: &XmlTag) -> Result<Scenario, OdsError> {
+    let mut scenario = Scenario::new();
+    for (k, v) in tag.attr_iter() {
+        match k.as_ref() {
+            "table:scenario-ranges" => {
+                let mut pos = 0usize;
+                if let Some(ranges) = parse_cellranges(v, &mut pos)? {
+                    scenario.set_ranges(ranges);
+                }
+            }
+            "table:is-active" => scenario.set_active(v.parse()?),
+            "table:display-border" => scenario.set_display_border(v.parse()?),
+            "table:border-color" => scenario.set_border_color(parse_color(v)?),
+            "table:copy-back" => scenario.set_copy_back(v.parse()?),
+            "table:copy-styles" => scenario.set_copy_styles(v.parse()?),
+            "table:copy-formulas" => scenario.set_copy_formulas(v.parse()?),
+            "table:comment" => scenario.set_comment(v.as_str()),
+            "table:protected" => scenario.set_protected(v.parse()?),
+            _ => {}
+        }
+    }
+    Ok(scenario)
+}
+
+// Reads the loext:table-protection options. Must come after read_table_attr.
+// Only the allowed actions are written, everything missing is false.
+fn read_table_protection(
+    sheet: &mut Sheet,
+    xml: &quick_xml::Reader<BufReader<&mut ZipFile>>,
+    xml_tag: &BytesStart,
+) -> Result<(), OdsError> {
+    let options = if let Some(protection) = sheet.protection_mut() {
+        protection.options_mut()
+    } else {
+        return Ok(());
+    };
+    options.set_select_protected_cells(false);
+    options.set_select_unprotected_cells(false);
+
+    for attr in xml_tag.attributes().with_checks(false) {
+        match attr? {
+            attr if attr.key == b"loext:select-protected-cells" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                options.set_select_protected_cells(v.parse()?);
+            }
+            attr if attr.key == b"loext:select-unprotected-cells" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                options.set_select_unprotected_cells(v.parse()?);
+            }
+            attr if attr.key == b"loext:insert-columns" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                options.set_insert_columns(v.parse()?);
+            }
+            attr if attr.key == b"loext:insert-rows" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                options.set_insert_rows(v.parse()?);
+            }
+            attr if attr.key == b"loext:delete-columns" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                options.set_delete_columns(v.parse()?);
+            }
+            attr if attr.key == b"loext:delete-rows" => {
+                let v = attr.unescape_and_decode_value(xml)?;
+                options.set_delete_rows(v.parse()?);
+            }
+            attr => {
+                if cfg!(feature = "dump_unused") {
+                    let n = xml.decode(xml_tag.name())?;
+                    let k = xml.decode(attr.key)?;
+                    let v = attr.unescape_and_decode_value(xml)?;
+                    println!(" read_table_protection unused {} {} {}", n, k, v);
+                }
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -549,6 +671,16 @@ fn read_table_cell(
                 // noop
             }
 
+            Event::Start(xml_tag) if is_shape_name(xml_tag.name()) => {
+                let tag = read_xml(xml_tag.name(), xml, &xml_tag, false)?;
+                read_cell_shape(sheet, row, col, &tag)?;
+            }
+
+            Event::Empty(xml_tag) if is_shape_name(xml_tag.name()) => {
+                let tag = read_xml(xml_tag.name(), xml, &xml_tag, true)?;
+                read_cell_shape(sheet, row, col, &tag)?;
+            }
+
             Event::End(xml_tag) if xml_tag.name() == tag_name => {
                 cell.value = parse_value(
                     value_type,
@@ -718,9 +850,11 @@ fn parse_value(
                     let mut have_sec = false;
                     let mut nanos: u32 = 0;
                     let mut nanos_digits: u8 = 0;
+                    let mut negative = false;
 
                     for c in cell_value.drain(..) {
                         match c {
+                            '-' => negative = true,
                             'P' | 'T' => {}
                             '0'..='9' => {
                                 if !have_hour {
@@ -750,7 +884,7 @@ fn parse_value(
                     let secs: u64 = hour as u64 * 3600 + min as u64 * 60 + sec as u64;
                     let dur = Duration::from_std(std::time::Duration::new(secs, nanos))?;
 
-                    Ok(Value::TimeDuration(dur))
+                    Ok(Value::TimeDuration(if negative { -dur } else { dur }))
                 } else {
                     Err(OdsError::Ods(format!(
                         "{} has type time-duration, but no value!",
@@ -1549,6 +1683,9 @@ fn read_style_style(
     style.set_styleuse(styleuse);
 
     read_style_attr(&mut style, xml, xml_tag)?;
+    if styleuse == StyleUse::Default {
+        style.set_name(default_style_name(style.family()));
+    }
 
     // In case of an empty xml-tag we are done here.
     if empty_tag {
@@ -1964,3 +2101,634 @@ fn read_text_or_tag(
 
     Ok((str, text))
 }
+
+// Parses the table:tracked-changes that where passed through into book.extra.
+// Must run after the tables are read, the table names are needed for the ranges.
+fn read_tracked_changes(book: &mut WorkBook) -> Result<(), OdsError> {
+    let mut changes = Vec::new();
+
+    if let Some(tag) = book
+        .extra
+        .iter()
+        .find(|v| v.name() == "table:tracked-changes")
+    {
+        for content in tag.content() {
+            if let XmlContent::Tag(change_tag) = content {
+                if let Some(change) = read_tracked_change(book, change_tag)? {
+                    changes.push(change);
+                }
+            }
+        }
+    }
+
+    book.tracked_changes = changes;
+
+    Ok(())
+}
+
+// Reads one of table:cell-content-change, table:insertion, table:deletion or
+// table:movement.
+fn read_tracked_change(book: &WorkBook, tag: &XmlTag) -> Result<Option<TrackedChange>, OdsError> {
+    let id = tag.attr("table:id").cloned().unwrap_or_default();
+
+    let change_type = match tag.name() {
+        "table:cell-content-change" => ChangeType::CellContent,
+        "table:insertion" => match tag.attr("table:type").map(|v| v.as_str()) {
+            Some("row") => ChangeType::InsertRows,
+            Some("column") => ChangeType::InsertColumns,
+            Some("table") => ChangeType::InsertTable,
+            v => {
+                return Err(OdsError::Ods(format!(
+                    "Unknown table:type {:?} for table:insertion {}",
+                    v, id
+                )))
+            }
+        },
+        "table:deletion" => match tag.attr("table:type").map(|v| v.as_str()) {
+            Some("row") => ChangeType::DeleteRows,
+            Some("column") => ChangeType::DeleteColumns,
+            Some("table") => ChangeType::DeleteTable,
+            v => {
+                return Err(OdsError::Ods(format!(
+                    "Unknown table:type {:?} for table:deletion {}",
+                    v, id
+                )))
+            }
+        },
+        "table:movement" => ChangeType::Movement,
+        _ => {
+            if cfg!(feature = "dump_unused") {
+                println!(" read_tracked_change unused {:?}", tag);
+            }
+            return Ok(None);
+        }
+    };
+
+    let mut change = TrackedChange::new(id, change_type);
+
+    if let Some(v) = tag.attr("table:acceptance-state") {
+        change.state = v.parse()?;
+    }
+
+    // insertion and deletion
+    if let Some(v) = tag.attr("table:table") {
+        change.table = v.parse()?;
+    }
+    if let Some(v) = tag.attr("table:position") {
+        let position = v.parse::<ucell>()?;
+        let count = if let Some(v) = tag.attr("table:count") {
+            v.parse::<ucell>()?
+        } else {
+            1
+        };
+        let last = position.checked_add(count.max(1) - 1).ok_or_else(|| {
+            OdsError::Ods(format!(
+                "Invalid table:position {} and table:count {} for {}",
+                position, count, change.id
+            ))
+        })?;
+        change.range = match change_type {
+            ChangeType::InsertRows | ChangeType::DeleteRows => {
+                tracked_range(book, change.table, position, 0, last, ucell::MAX)
+            }
+            ChangeType::InsertColumns | ChangeType::DeleteColumns => {
+                tracked_range(book, change.table, 0, position, ucell::MAX, last)
+            }
+            ChangeType::DeleteTable => CellRange::local(0, 0, ucell::MAX, ucell::MAX),
+            _ => tracked_range(book, position as usize, 0, 0, ucell::MAX, ucell::MAX),
+        };
+        if change_type == ChangeType::InsertTable || change_type == ChangeType::DeleteTable {
+            change.table = position as usize;
+        }
+    }
+
+    for content in tag.content() {
+        if let XmlContent::Tag(child) = content {
+            match child.name() {
+                "office:change-info" => read_change_info(&mut change, child)?,
+                "table:cell-address" => {
+                    let (table, range) = read_tracked_address(book, child)?;
+                    change.table = table;
+                    change.range = range;
+                }
+                "table:target-range-address" => {
+                    let (table, range) = read_tracked_address(book, child)?;
+                    change.table = table;
+                    change.range = range;
+                }
+                "table:source-range-address" => {
+                    let (_, range) = read_tracked_address(book, child)?;
+                    change.source = Some(range);
+                }
+                "table:previous" => {
+                    for content in child.content() {
+                        if let XmlContent::Tag(cell) = content {
+                            if cell.name() == "table:change-track-table-cell" {
+                                let (value, formula) = read_change_track_cell(
+                                    cell,
+                                    change.range.row(),
+                                    change.range.col(),
+                                )?;
+                                change.previous = Some(value);
+                                change.previous_formula = formula;
+                            }
+                        }
+                    }
+                }
+                "table:deletions" => {
+                    for content in child.content() {
+                        if let XmlContent::Tag(deletion) = content {
+                            if deletion.name() == "table:cell-content-deletion" {
+                                read_cell_content_deletion(book, &mut change, deletion)?;
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    if cfg!(feature = "dump_unused") {
+                        println!(" read_tracked_change unused {:?}", child);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Some(change))
+}
+
+// Author, date and comment.
+fn read_change_info(change: &mut TrackedChange, tag: &XmlTag) -> Result<(), OdsError> {
+    for content in tag.content() {
+        if let XmlContent::Tag(child) = content {
+            match child.name() {
+                "dc:creator" => change.author = Some(xml_text(child)),
+                "dc:date" => {
+                    change.date = Some(NaiveDateTime::parse_from_str(
+                        xml_text(child).as_str(),
+                        "%Y-%m-%dT%H:%M:%S%.f",
+                    )?)
+                }
+                "text:p" => {
+                    let comment = change.comment.get_or_insert_with(String::new);
+                    if !comment.is_empty() {
+                        comment.push('\n');
+                    }
+                    comment.push_str(xml_text(child).as_str());
+                }
+                _ => {
+                    if cfg!(feature = "dump_unused") {
+                        println!(" read_change_info unused {:?}", child);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Cell contents of deleted rows/columns.
+fn read_cell_content_deletion(
+    book: &WorkBook,
+    change: &mut TrackedChange,
+    tag: &XmlTag,
+) -> Result<(), OdsError> {
+    let mut cellref = None;
+    let mut value = None;
+
+    for content in tag.content() {
+        if let XmlContent::Tag(child) = content {
+            match child.name() {
+                "table:cell-address" => {
+                    let (_, range) = read_tracked_address(book, child)?;
+                    let mut c = CellRef::local(range.row(), range.col());
+                    // The table name for a deleted table would be the one
+                    // of the table that's now at its position.
+                    if change.change_type != ChangeType::DeleteTable {
+                        if let Some(table) = range.table() {
+                            c.set_table(table.as_str());
+                        }
+                    }
+                    cellref = Some(c);
+                }
+                "table:change-track-table-cell" => {
+                    value = Some(read_change_track_cell(child, 0, 0)?.0);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let (Some(cellref), Some(value)) = (cellref, value) {
+        change.deleted.push((cellref, value));
+    }
+
+    Ok(())
+}
+
+// Reads either a single cell address or a range address.
+fn read_tracked_address(book: &WorkBook, tag: &XmlTag) -> Result<(usize, CellRange), OdsError> {
+    fn attr_or<T: std::str::FromStr>(tag: &XmlTag, name: &str, default: T) -> Result<T, OdsError>
+    where
+        OdsError: From<T::Err>,
+    {
+        if let Some(v) = tag.attr(name) {
+            Ok(v.parse::<T>()?)
+        } else {
+            Ok(default)
+        }
+    }
+
+    if tag.attr("table:start-column").is_some() || tag.attr("table:start-row").is_some() {
+        let table = attr_or(tag, "table:start-table", 0usize)?;
+        let row = attr_or(tag, "table:start-row", 0)?;
+        let col = attr_or(tag, "table:start-column", 0)?;
+        let to_row = attr_or(tag, "table:end-row", row)?;
+        let to_col = attr_or(tag, "table:end-column", col)?;
+        Ok((table, tracked_range(book, table, row, col, to_row, to_col)))
+    } else {
+        let table = attr_or(tag, "table:table", 0usize)?;
+        let row = attr_or(tag, "table:row", 0)?;
+        let col = attr_or(tag, "table:column", 0)?;
+        Ok((table, tracked_range(book, table, row, col, row, col)))
+    }
+}
+
+// Range with the table-name set, if the table exists.
+fn tracked_range(
+    book: &WorkBook,
+    table: usize,
+    row: ucell,
+    col: ucell,
+    to_row: ucell,
+    to_col: ucell,
+) -> CellRange {
+    if let Some(sheet) = book.sheets.get(table) {
+        CellRange::remote(sheet.name().as_str(), row, col, to_row, to_col)
+    } else {
+        CellRange::local(row, col, to_row, to_col)
+    }
+}
+
+// Value and formula of a table:change-track-table-cell.
+fn read_change_track_cell(
+    tag: &XmlTag,
+    row: ucell,
+    col: ucell,
+) -> Result<(Value, Option<String>), OdsError> {
+    let value_type = match tag.attr("office:value-type").map(|v| v.as_str()) {
+        Some("string") => Some(ValueType::Text),
+        Some("float") => Some(ValueType::Number),
+        Some("percentage") => Some(ValueType::Percentage),
+        Some("date") => Some(ValueType::DateTime),
+        Some("time") => Some(ValueType::TimeDuration),
+        Some("boolean") => Some(ValueType::Boolean),
+        Some("currency") => Some(ValueType::Currency),
+        Some(other) => return Err(OdsError::Ods(format!("Unknown cell-type {:?}", other))),
+        None => None,
+    };
+
+    let cell_value = tag
+        .attr("office:value")
+        .or_else(|| tag.attr("office:date-value"))
+        .or_else(|| tag.attr("office:time-value"))
+        .or_else(|| tag.attr("office:boolean-value"))
+        .or_else(|| tag.attr("office:string-value"))
+        .cloned();
+    let cell_currency = tag.attr("office:currency").cloned();
+    let formula = tag.attr("table:formula").cloned();
+
+    let mut cell_content: Option<String> = None;
+    for content in tag.content() {
+        if let XmlContent::Tag(child) = content {
+            if child.name() == "text:p" {
+                let c = cell_content.get_or_insert_with(String::new);
+                if !c.is_empty() {
+                    c.push('\n');
+                }
+                c.push_str(xml_text(child).as_str());
+            }
+        }
+    }
+
+    let value = parse_value(
+        value_type,
+        cell_value,
+        cell_content,
+        None,
+        cell_currency,
+        row,
+        col,
+    )?;
+
+    Ok((value, formula))
+}
+
+// Concatenated text of the tag and all its children.
+fn xml_text(tag: &XmlTag) -> String {
+    let mut buf = String::new();
+    for content in tag.content() {
+        match content {
+            XmlContent::Text(t) => buf.push_str(t.as_str()),
+            XmlContent::Tag(t) => buf.push_str(xml_text(t).as_str()),
+        }
+    }
+    buf
+}
+
+// Splits the draw:frames with an embedded object and the supported
+// drawing shapes from the other shapes. The embedded objects are possibly
+// charts, which is decided later in read_charts.
+fn read_table_shapes(sheet: &mut Sheet, mut shapes: XmlTag) -> Result<(), OdsError> {
+    let mut others = Vec::new();
+    for content in shapes.content_mut().drain(..) {
+        match content {
+            XmlContent::Tag(tag) if is_shape(&tag) => {
+                if let Some(shape) = read_shape(&tag)? {
+                    sheet.shapes.push(shape);
+                } else {
+                    others.push(XmlContent::Tag(tag));
+                }
+            }
+            XmlContent::Tag(frame) if frame.name() == "draw:frame" => {
+                let href = frame.content().iter().find_map(|c| match c {
+                    XmlContent::Tag(obj) if obj.name() == "draw:object" => {
+                        obj.attr("xlink:href").cloned()
+                    }
+                    _ => None,
+                });
+
+                if let Some(href) = href {
+                    // the type is a guess for now.
+                    let mut chart = Chart::new(ChartType::Bar);
+                    if let Some(name) = frame.attr("draw:name") {
+                        chart.set_name(name.as_str());
+                    }
+                    chart.href = Some(href.trim_start_matches("./").to_string());
+                    chart.frame = Some(frame);
+                    sheet.charts.push(chart);
+                } else {
+                    others.push(XmlContent::Tag(frame));
+                }
+            }
+            content => others.push(content),
+        }
+    }
+
+    if !others.is_empty() {
+        shapes.content_mut().append(&mut others);
+        sheet.extra.push(shapes);
+    }
+
+    Ok(())
+}
+
+// Possible shapes inside a table-cell.
+fn is_shape_name(name: &[u8]) -> bool {
+    name == b"draw:rect"
+        || name == b"draw:ellipse"
+        || name == b"draw:line"
+        || name == b"draw:connector"
+        || name == b"draw:frame"
+}
+
+// A shape anchored to a cell. Anything else is dropped.
+fn read_cell_shape(
+    sheet: &mut Sheet,
+    row: ucell,
+    col: ucell,
+    tag: &XmlTag,
+) -> Result<(), OdsError> {
+    if let Some(mut shape) = read_shape(tag)? {
+        shape.set_anchor_cell(row, col);
+        sheet.shapes.push(shape);
+    }
+    Ok(())
+}
+
+// Shapes that are mapped to a Shape.
+fn is_shape(tag: &XmlTag) -> bool {
+    match tag.name() {
+        "draw:rect" | "draw:ellipse" | "draw:line" | "draw:connector" => true,
+        "draw:frame" => tag
+            .content()
+            .iter()
+            .any(|c| matches!(c, XmlContent::Tag(t) if t.name() == "draw:text-box")),
+        _ => false,
+    }
+}
+
+// Reads one of the supported drawing shapes. Returns None otherwise.
+fn read_shape(tag: &XmlTag) -> Result<Option<Shape>, OdsError> {
+    if !is_shape(tag) {
+        return Ok(None);
+    }
+
+    let mut shape = Shape::new(match tag.name() {
+        "draw:rect" => ShapeType::Rect,
+        "draw:ellipse" => ShapeType::Ellipse,
+        "draw:line" => ShapeType::Line,
+        "draw:connector" => ShapeType::Connector,
+        _ => ShapeType::TextBox,
+    });
+
+    for (k, v) in tag.attr_iter() {
+        match k.as_ref() {
+            "draw:name" => shape.set_name(v.as_str()),
+            "draw:style-name" => shape.set_style(v.as_str()),
+            "draw:text-style-name" => shape.set_text_style(v.as_str()),
+            "draw:z-index" => shape.set_z_index(v.parse()?),
+            _ => {}
+        }
+    }
+
+    let length = |name: &str| match tag.attr(name) {
+        Some(v) => v.parse::<Length>(),
+        None => Ok(Length::Cm(0.0)),
+    };
+    match shape.shape_type() {
+        ShapeType::Line | ShapeType::Connector => {
+            shape.set_position(length("svg:x1")?, length("svg:y1")?);
+            shape.set_end(length("svg:x2")?, length("svg:y2")?);
+        }
+        _ => {
+            shape.set_position(length("svg:x")?, length("svg:y")?);
+            shape.set_size(length("svg:width")?, length("svg:height")?);
+        }
+    }
+
+    let text_tag = if shape.shape_type() == ShapeType::TextBox {
+        tag.content().iter().find_map(|c| match c {
+            XmlContent::Tag(t) if t.name() == "draw:text-box" => Some(t),
+            _ => None,
+        })
+    } else {
+        Some(tag)
+    };
+    if let Some(text_tag) = text_tag {
+        let paragraphs: Vec<String> = text_tag
+            .content()
+            .iter()
+            .filter_map(|c| match c {
+                XmlContent::Tag(t) if t.name() == "text:p" => Some(xml_text(t)),
+                _ => None,
+            })
+            .collect();
+        if !paragraphs.is_empty() {
+            shape.set_text(paragraphs.join("\n"));
+        }
+    }
+
+    Ok(Some(shape))
+}
+
+// Reads the chart sub-documents for all embedded objects.
+// Anything that is not a chart goes back to the table:shapes.
+fn read_charts(book: &mut WorkBook, zip: &mut zip::ZipArchive<File>) -> Result<(), OdsError> {
+    for sheet in book.sheets.iter_mut() {
+        let mut not_charts = Vec::new();
+
+        for mut chart in std::mem::take(&mut sheet.charts) {
+            let href = chart.href.clone().unwrap_or_default();
+            let is_chart = match zip.by_name(format!("{}/content.xml", href).as_str()) {
+                Ok(mut zip_file) => read_chart(&mut chart, &mut zip_file)?,
+                Err(_) => false,
+            };
+            if is_chart {
+                sheet.charts.push(chart);
+            } else if let Some(frame) = chart.frame {
+                not_charts.push(frame);
+            }
+        }
+
+        if !not_charts.is_empty() {
+            let idx = if let Some(idx) = sheet.extra.iter().position(|v| v.name() == "table:shapes")
+            {
+                idx
+            } else {
+                sheet.extra.push(XmlTag::new("table:shapes"));
+                sheet.extra.len() - 1
+            };
+            for frame in not_charts {
+                sheet.extra[idx].push_tag(frame);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Reads the content.xml of an embedded object. Returns false if this
+// is not a supported chart.
+fn read_chart(chart: &mut Chart, zip_file: &mut ZipFile) -> Result<bool, OdsError> {
+    let mut xml = quick_xml::Reader::from_reader(BufReader::new(zip_file));
+    xml.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut office_chart = None;
+    loop {
+        let evt = xml.read_event(&mut buf)?;
+        if cfg!(feature = "dump_xml") {
+            println!(" read_chart {:?}", evt);
+        }
+        match evt {
+            Event::Start(xml_tag) if xml_tag.name() == b"office:chart" => {
+                office_chart = Some(read_xml(b"office:chart", &mut xml, &xml_tag, false)?);
+            }
+            Event::Eof => {
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let chart_tag = office_chart.as_ref().and_then(|v| {
+        v.content().iter().find_map(|c| match c {
+            XmlContent::Tag(t) if t.name() == "chart:chart" => Some(t),
+            _ => None,
+        })
+    });
+    let chart_tag = if let Some(chart_tag) = chart_tag {
+        chart_tag
+    } else {
+        return Ok(false);
+    };
+
+    match chart_tag
+        .attr("chart:class")
+        .map(|v| v.parse::<ChartType>())
+    {
+        Some(Ok(chart_type)) => chart.set_chart_type(chart_type),
+        _ => return Ok(false),
+    }
+
+    for content in chart_tag.content() {
+        if let XmlContent::Tag(child) = content {
+            match child.name() {
+                "chart:title" => chart.set_title(xml_text(child)),
+                "chart:legend" => {
+                    if let Some(v) = child.attr("chart:legend-position") {
+                        chart.set_legend(v.parse()?);
+                    }
+                }
+                "chart:plot-area" => read_chart_plot_area(chart, child)?,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+// Axis and series of the chart.
+fn read_chart_plot_area(chart: &mut Chart, tag: &XmlTag) -> Result<(), OdsError> {
+    for content in tag.content() {
+        if let XmlContent::Tag(child) = content {
+            match child.name() {
+                "chart:axis" => {
+                    let x_axis = child.attr("chart:dimension").map(|v| v.as_str()) == Some("x");
+                    for content in child.content() {
+                        if let XmlContent::Tag(axis_child) = content {
+                            match axis_child.name() {
+                                "chart:title" if x_axis => {
+                                    chart.set_x_axis_title(xml_text(axis_child))
+                                }
+                                "chart:title" => chart.set_y_axis_title(xml_text(axis_child)),
+                                "chart:categories" => {
+                                    if let Some(v) = axis_child.attr("table:cell-range-address") {
+                                        let mut pos = 0usize;
+                                        chart.set_categories(parse_cellrange(v, &mut pos)?);
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                "chart:series" => {
+                    if let Some(v) = child.attr("chart:values-cell-range-address") {
+                        let mut pos = 0usize;
+                        let mut series = Series::new(parse_cellrange(v, &mut pos)?);
+                        if let Some(v) = child.attr("chart:label-cell-address") {
+                            let mut pos = 0usize;
+                            series.set_label(parse_cellref(v, &mut pos)?);
+                        }
+                        for content in child.content() {
+                            if let XmlContent::Tag(domain) = content {
+                                if domain.name() == "chart:domain" {
+                                    if let Some(v) = domain.attr("table:cell-range-address") {
+                                        let mut pos = 0usize;
+                                        series.set_domain(parse_cellrange(v, &mut pos)?);
+                                    }
+                                }
+                            }
+                        }
+                        chart.add_series(series);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
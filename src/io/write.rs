@@ -5,58 +5,166 @@ use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 use chrono::NaiveDateTime;
+use quick_xml::events::Event;
 use zip::write::FileOptions;
 
-use crate::attrmap::AttrMap;
+use crate::attrmap::{color_string, AttrMap};
+use crate::chart::Chart;
+use crate::draw::{Shape, ShapeType};
 use crate::error::OdsError;
-use crate::format::FormatPartType;
+use crate::format::{duration_string, FormatPartType};
 use crate::io::tmp2zip::{TempWrite, TempZip};
 use crate::io::xmlwriter::XmlWriter;
-use crate::refs::{cellranges_string, CellRange};
+use crate::protection::ProtectionOptions;
+use crate::refs::{cellranges_string, CellRange, CellRef};
+use crate::scenario::Scenario;
 use crate::style::{
-    FontFaceDecl, HeaderFooter, PageLayout, Style, StyleFor, StyleOrigin, StyleUse,
+    family_name, FontFaceDecl, HeaderFooter, PageLayout, Style, StyleFor, StyleOrigin, StyleUse,
 };
+use crate::stylepool::with_automatic_styles;
 use crate::xmltree::{XmlContent, XmlTag};
 use crate::{ucell, SCell, Sheet, Value, ValueFormat, ValueType, Visibility, WorkBook};
 
 type OdsWriter = TempZip;
 type XmlOdsWriter<'a> = XmlWriter<TempWrite<'a>>;
 
+/// What to do with fonts that are used by a style but have no font
+/// declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingFonts {
+    /// Write as is. Applications fall back to some default font.
+    Ignore,
+    /// Fail with an error.
+    Error,
+    /// Add a declaration, see WorkBook::create_missing_fonts().
+    Create,
+}
+
+/// Settings for writing the ODS file.
+#[derive(Debug, Clone)]
+pub struct OdsWriteOptions {
+    remove_unused_styles: bool,
+    missing_fonts: MissingFonts,
+}
+
+impl Default for OdsWriteOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OdsWriteOptions {
+    /// Writes the workbook as it is.
+    pub fn new() -> Self {
+        Self {
+            remove_unused_styles: false,
+            missing_fonts: MissingFonts::Ignore,
+        }
+    }
+
+    /// Writes only the styles, value formats, fonts and page layouts
+    /// that are used, see WorkBook::remove_unused_styles(). The workbook
+    /// itself is not changed.
+    pub fn set_remove_unused_styles(&mut self, remove: bool) {
+        self.remove_unused_styles = remove;
+    }
+
+    /// Removes unused styles before writing.
+    pub fn remove_unused_styles(&self) -> bool {
+        self.remove_unused_styles
+    }
+
+    /// Checks for used fonts without a font declaration. The workbook
+    /// itself is not changed.
+    pub fn set_missing_fonts(&mut self, missing_fonts: MissingFonts) {
+        self.missing_fonts = missing_fonts;
+    }
+
+    /// Handling of fonts without declaration.
+    pub fn missing_fonts(&self) -> MissingFonts {
+        self.missing_fonts
+    }
+}
+
 /// Writes the ODS file.
 ///
 /// All the parts are written to a temp directory and then zipped together.
 ///
 pub fn write_ods<P: AsRef<Path>>(book: &WorkBook, ods_path: P) -> Result<(), OdsError> {
-    let mut zip_writer = TempZip::new(ods_path.as_ref())?;
+    write_ods_with_options(book, ods_path, &OdsWriteOptions::new())
+}
+
+/// Writes the ODS file with the given settings.
+pub fn write_ods_with_options<P: AsRef<Path>>(
+    book: &WorkBook,
+    ods_path: P,
+    options: &OdsWriteOptions,
+) -> Result<(), OdsError> {
+    let mut book = with_automatic_styles(book);
+    if options.remove_unused_styles {
+        book.to_mut().remove_unused_styles();
+    }
+    match options.missing_fonts {
+        MissingFonts::Ignore => {}
+        MissingFonts::Error => {
+            let missing = book.missing_fonts();
+            if !missing.is_empty() {
+                return Err(OdsError::Ods(format!(
+                    "No font declaration for {}",
+                    missing.join(", ")
+                )));
+            }
+        }
+        MissingFonts::Create => {
+            if !book.missing_fonts().is_empty() {
+                book.to_mut().create_missing_fonts();
+            }
+        }
+    }
+    write_ods_book(&book, ods_path.as_ref())
+}
+
+fn write_ods_book(book: &WorkBook, ods_path: &Path) -> Result<(), OdsError> {
+    let mut zip_writer = TempZip::new(ods_path)?;
 
     let mut file_set = HashSet::<String>::new();
 
-    if let Some(orig) = &book.file {
-        copy_workbook(&orig, &mut file_set, &mut zip_writer)?;
-    }
+    let manifest = if let Some(orig) = &book.file {
+        copy_workbook(orig, &mut file_set, &mut zip_writer)?
+    } else {
+        Vec::new()
+    };
+
+    let chart_objects = chart_objects(book, &file_set);
 
     write_mimetype(&mut zip_writer, &mut file_set)?;
-    write_manifest(&mut zip_writer, &mut file_set)?;
+    write_manifest(&mut zip_writer, &mut file_set, &manifest, &chart_objects)?;
     write_manifest_rdf(&mut zip_writer, &mut file_set)?;
     write_meta(&mut zip_writer, &mut file_set)?;
     //write_settings(&mut zip_writer, &mut file_set)?;
     //write_configurations(&mut zip_writer, &mut file_set)?;
-    write_ods_styles(&book, &mut zip_writer, &mut file_set)?;
-    write_ods_content(&book, &mut zip_writer, &mut file_set)?;
+    write_ods_styles(book, &mut zip_writer, &mut file_set)?;
+    write_ods_content(book, &chart_objects, &mut zip_writer, &mut file_set)?;
+    write_charts(book, &chart_objects, &mut zip_writer, &mut file_set)?;
 
     zip_writer.zip()?;
 
     Ok(())
 }
 
+/// Copies all files of the original workbook. The manifest is not copied
+/// but returned as a list of manifest:file-entry, it is extended and
+/// written by write_manifest.
 fn copy_workbook(
     ods_orig_name: &PathBuf,
     file_set: &mut HashSet<String>,
     zip_writer: &mut OdsWriter,
-) -> Result<(), OdsError> {
+) -> Result<Vec<XmlTag>, OdsError> {
     let ods_orig = File::open(ods_orig_name)?;
     let mut zip_orig = zip::ZipArchive::new(ods_orig)?;
 
+    let mut manifest = Vec::new();
+
     for i in 0..zip_orig.len() {
         let mut zip_entry = zip_orig.by_index(i)?;
 
@@ -65,6 +173,8 @@ fn copy_workbook(
                 file_set.insert(zip_entry.name().to_string());
                 zip_writer.add_directory(zip_entry.name(), FileOptions::default())?;
             }
+        } else if zip_entry.name() == "META-INF/manifest.xml" {
+            manifest = read_manifest(&mut zip_entry)?;
         } else if !file_set.contains(zip_entry.name()) {
             file_set.insert(zip_entry.name().to_string());
             let mut wr = zip_writer.start_file(zip_entry.name(), FileOptions::default())?;
@@ -80,7 +190,67 @@ fn copy_workbook(
         }
     }
 
-    Ok(())
+    Ok(manifest)
+}
+
+/// Reads the file-entries of the original manifest.
+fn read_manifest(zip_entry: &mut zip::read::ZipFile) -> Result<Vec<XmlTag>, OdsError> {
+    let mut xml = quick_xml::Reader::from_reader(io::BufReader::new(zip_entry));
+    xml.trim_text(true);
+
+    let mut manifest = Vec::new();
+
+    let mut buf = Vec::new();
+    loop {
+        match xml.read_event(&mut buf)? {
+            Event::Empty(xml_tag) | Event::Start(xml_tag)
+                if xml_tag.name() == b"manifest:file-entry" =>
+            {
+                let mut entry = XmlTag::new("manifest:file-entry");
+                for attr in xml_tag.attributes().with_checks(false) {
+                    let attr = attr?;
+                    let k = xml.decode(attr.key)?;
+                    let v = attr.unescape_and_decode_value(&xml)?;
+                    entry.set_attr(k, v);
+                }
+                manifest.push(entry);
+            }
+            Event::Eof => {
+                break;
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(manifest)
+}
+
+/// Assigns an object name "Object N" to every new chart.
+/// Charts read from a file keep their original document.
+fn chart_objects(book: &WorkBook, file_set: &HashSet<String>) -> Vec<Vec<Option<String>>> {
+    let mut next = 1;
+    let mut chart_objects = Vec::new();
+    for sheet in &book.sheets {
+        let mut objects = Vec::new();
+        for chart in &sheet.charts {
+            if chart.frame.is_some() {
+                objects.push(None);
+            } else {
+                let name = loop {
+                    let name = format!("Object {}", next);
+                    next += 1;
+                    let prefix = format!("{}/", name);
+                    if !file_set.iter().any(|v| v.starts_with(prefix.as_str())) {
+                        break name;
+                    }
+                };
+                objects.push(Some(name));
+            }
+        }
+        chart_objects.push(objects);
+    }
+    chart_objects
 }
 
 fn write_mimetype(
@@ -102,11 +272,19 @@ fn write_mimetype(
     Ok(())
 }
 
-fn write_manifest(zip_out: &mut OdsWriter, file_set: &mut HashSet<String>) -> Result<(), OdsError> {
+fn write_manifest(
+    zip_out: &mut OdsWriter,
+    file_set: &mut HashSet<String>,
+    manifest: &[XmlTag],
+    chart_objects: &[Vec<Option<String>>],
+) -> Result<(), OdsError> {
     if !file_set.contains("META-INF/manifest.xml") {
         file_set.insert(String::from("META-INF/manifest.xml"));
 
-        zip_out.add_directory("META-INF", FileOptions::default())?;
+        if !file_set.contains("META-INF/") {
+            file_set.insert(String::from("META-INF/"));
+            zip_out.add_directory("META-INF", FileOptions::default())?;
+        }
         let w = zip_out.start_file("META-INF/manifest.xml", FileOptions::default())?;
 
         let mut xml_out = XmlWriter::new(w);
@@ -120,39 +298,63 @@ fn write_manifest(zip_out: &mut OdsWriter, file_set: &mut HashSet<String>) -> Re
         )?;
         xml_out.attr("manifest:version", "1.2")?;
 
-        xml_out.empty("manifest:file-entry")?;
-        xml_out.attr("manifest:full-path", "/")?;
-        xml_out.attr("manifest:version", "1.2")?;
-        xml_out.attr(
-            "manifest:media-type",
-            "application/vnd.oasis.opendocument.spreadsheet",
-        )?;
+        if manifest.is_empty() {
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr("manifest:full-path", "/")?;
+            xml_out.attr("manifest:version", "1.2")?;
+            xml_out.attr(
+                "manifest:media-type",
+                "application/vnd.oasis.opendocument.spreadsheet",
+            )?;
+
+            //        xml_out.write_event(xml_empty_a("manifest:file-entry", vec![
+            //            ("manifest:full-path", String::from("Configurations2/")),
+            //            ("manifest:media-type", String::from("application/vnd.sun.xml.ui.configuration")),
+            //        ]))?;
+
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr("manifest:full-path", "manifest.rdf")?;
+            xml_out.attr("manifest:media-type", "application/rdf+xml")?;
 
-        //        xml_out.write_event(xml_empty_a("manifest:file-entry", vec![
-        //            ("manifest:full-path", String::from("Configurations2/")),
-        //            ("manifest:media-type", String::from("application/vnd.sun.xml.ui.configuration")),
-        //        ]))?;
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr("manifest:full-path", "styles.xml")?;
+            xml_out.attr("manifest:media-type", "text/xml")?;
 
-        xml_out.empty("manifest:file-entry")?;
-        xml_out.attr("manifest:full-path", "manifest.rdf")?;
-        xml_out.attr("manifest:media-type", "application/rdf+xml")?;
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr("manifest:full-path", "meta.xml")?;
+            xml_out.attr("manifest:media-type", "text/xml")?;
 
-        xml_out.empty("manifest:file-entry")?;
-        xml_out.attr("manifest:full-path", "styles.xml")?;
-        xml_out.attr("manifest:media-type", "text/xml")?;
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr("manifest:full-path", "content.xml")?;
+            xml_out.attr("manifest:media-type", "text/xml")?;
 
-        xml_out.empty("manifest:file-entry")?;
-        xml_out.attr("manifest:full-path", "meta.xml")?;
-        xml_out.attr("manifest:media-type", "text/xml")?;
+            //        xml_out.write_event(xml::xml_empty_a("manifest:file-entry", vec![
+            //            ("manifest:full-path", String::from("settings.xml")),
+            //            ("manifest:media-type", String::from("text/xml")),
+            //        ]))?;
+        } else {
+            for entry in manifest {
+                write_xmltag(entry, &mut xml_out)?;
+            }
+        }
 
-        xml_out.empty("manifest:file-entry")?;
-        xml_out.attr("manifest:full-path", "content.xml")?;
-        xml_out.attr("manifest:media-type", "text/xml")?;
+        for object in chart_objects.iter().flatten().flatten() {
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr_esc("manifest:full-path", &format!("{}/", object))?;
+            xml_out.attr("manifest:version", "1.2")?;
+            xml_out.attr(
+                "manifest:media-type",
+                "application/vnd.oasis.opendocument.chart",
+            )?;
+
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr_esc("manifest:full-path", &format!("{}/content.xml", object))?;
+            xml_out.attr("manifest:media-type", "text/xml")?;
 
-        //        xml_out.write_event(xml::xml_empty_a("manifest:file-entry", vec![
-        //            ("manifest:full-path", String::from("settings.xml")),
-        //            ("manifest:media-type", String::from("text/xml")),
-        //        ]))?;
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr_esc("manifest:full-path", &format!("{}/styles.xml", object))?;
+            xml_out.attr("manifest:media-type", "text/xml")?;
+        }
 
         xml_out.end_elem("manifest:manifest")?;
 
@@ -422,9 +624,9 @@ fn write_ods_styles(
         &mut xml_out,
     )?;
     write_value_styles(
-        &book.formats,
-        StyleOrigin::Styles,
-        StyleUse::Named,
+        book.formats
+            .values()
+            .filter(|v| v.styleuse() != StyleUse::Automatic),
         &mut xml_out,
     )?;
     xml_out.end_elem("office:styles")?;
@@ -437,10 +639,14 @@ fn write_ods_styles(
         StyleUse::Automatic,
         &mut xml_out,
     )?;
+    // Automatic formats of content.xml can't be seen from styles.xml.
+    // The ones needed here are written in both parts.
+    let content_formats = styles_xml_content_formats(book);
     write_value_styles(
-        &book.formats,
-        StyleOrigin::Styles,
-        StyleUse::Automatic,
+        book.formats.values().filter(|v| {
+            v.styleuse() == StyleUse::Automatic
+                && (v.origin() == StyleOrigin::Styles || content_formats.contains(v.name()))
+        }),
         &mut xml_out,
     )?;
     xml_out.end_elem("office:automatic-styles")?;
@@ -458,6 +664,7 @@ fn write_ods_styles(
 
 fn write_ods_content(
     book: &WorkBook,
+    chart_objects: &[Vec<Option<String>>],
     zip_out: &mut OdsWriter,
     file_set: &mut HashSet<String>,
 ) -> Result<(), OdsError> {
@@ -574,9 +781,9 @@ fn write_ods_content(
         &mut xml_out,
     )?;
     write_value_styles(
-        &book.formats,
-        StyleOrigin::Content,
-        StyleUse::Automatic,
+        book.formats
+            .values()
+            .filter(|v| v.origin() == StyleOrigin::Content && v.styleuse() == StyleUse::Automatic),
         &mut xml_out,
     )?;
     xml_out.end_elem("office:automatic-styles")?;
@@ -601,8 +808,8 @@ fn write_ods_content(
         }
     }
 
-    for sheet in &book.sheets {
-        write_sheet(&book, &sheet, &mut xml_out)?;
+    for (sheet, objects) in book.sheets.iter().zip(chart_objects.iter()) {
+        write_sheet(book, sheet, objects, &mut xml_out)?;
     }
 
     // extra tags. pass through only
@@ -643,7 +850,12 @@ pub(crate) fn remove_outlooped(ranges: &mut Vec<CellRange>, row: ucell, col: uce
         .collect();
 }
 
-fn write_sheet(book: &WorkBook, sheet: &Sheet, xml_out: &mut XmlOdsWriter) -> Result<(), OdsError> {
+fn write_sheet(
+    book: &WorkBook,
+    sheet: &Sheet,
+    chart_objects: &[Option<String>],
+    xml_out: &mut XmlOdsWriter,
+) -> Result<(), OdsError> {
     xml_out.elem("table:table")?;
     xml_out.attr_esc("table:name", &*sheet.name)?;
     if let Some(style) = &sheet.style {
@@ -658,23 +870,52 @@ fn write_sheet(book: &WorkBook, sheet: &Sheet, xml_out: &mut XmlOdsWriter) -> Re
     if !sheet.display() {
         xml_out.attr("table:display", "false")?;
     }
-
+    if let Some(protection) = &sheet.protection {
+        xml_out.attr("table:protected", "true")?;
+        if let Some(key) = protection.key() {
+            xml_out.attr_esc("table:protection-key", key.as_str())?;
+            xml_out.attr(
+                "table:protection-key-digest-algorithm",
+                &protection.options().digest().to_string(),
+            )?;
+        }
+        write_table_protection(protection.options(), xml_out)?;
+    }
 
     let max_cell = sheet.used_grid_size();
 
+    // Shapes anchored to an existing cell are written with the cell.
+    let mut cell_shapes = HashMap::<(ucell, ucell), Vec<&Shape>>::new();
+    for shape in &sheet.shapes {
+        if let Some(anchor) = shape.anchor_cell() {
+            if sheet.data.contains_key(&anchor) {
+                cell_shapes.entry(anchor).or_default().push(shape);
+            }
+        }
+    }
+
     for tag in &sheet.extra {
         if tag.name() == "table:title"
             || tag.name() == "table:desc"
             || tag.name() == "table:table-source"
             || tag.name() == "office:dde-source"
-            || tag.name() == "table:scenario"
-            || tag.name() == "office:forms"
-            || tag.name() == "table:shapes"
         {
             write_xmltag(tag, xml_out)?;
         }
     }
 
+    if let Some(scenario) = &sheet.scenario {
+        write_scenario(sheet, scenario, xml_out)?;
+    }
+
+    for tag in &sheet.extra {
+        if tag.name() == "office:forms" {
+            write_xmltag(tag, xml_out)?;
+        }
+    }
+
+    write_table_shapes(sheet, chart_objects, xml_out)?;
+
     write_table_columns(&sheet, max_cell, xml_out)?;
 
     // list of current spans
@@ -748,7 +989,11 @@ fn write_sheet(book: &WorkBook, sheet: &Sheet, xml_out: &mut XmlOdsWriter) -> Re
         let (is_hidden, hidden_cols) = check_hidden(&spans, *cur_row, *cur_col);
 
         // And now to something completely different ...
-        write_cell(book, cell, is_hidden, xml_out)?;
+        let shapes = cell_shapes
+            .get(&(*cur_row, *cur_col))
+            .map(|v| v.as_slice())
+            .unwrap_or(&[]);
+        write_cell(book, cell, shapes, is_hidden, xml_out)?;
 
         // There may be some blank cells until the next one, but only one less the forward.
         if forward_dc > 1 {
@@ -783,6 +1028,452 @@ fn write_sheet(book: &WorkBook, sheet: &Sheet, xml_out: &mut XmlOdsWriter) -> Re
     Ok(())
 }
 
+/// Writes the passed through table:shapes, all the charts and the
+/// shapes that are not anchored to a cell.
+fn write_table_shapes(
+    sheet: &Sheet,
+    chart_objects: &[Option<String>],
+    xml_out: &mut XmlOdsWriter,
+) -> Result<(), OdsError> {
+    let sheet_shapes: Vec<&Shape> = sheet
+        .shapes
+        .iter()
+        .filter(|v| match v.anchor_cell() {
+            None => true,
+            Some(anchor) => !sheet.data.contains_key(&anchor),
+        })
+        .collect();
+
+    let shapes = sheet.extra.iter().find(|v| v.name() == "table:shapes");
+    if shapes.is_none() && sheet.charts.is_empty() && sheet_shapes.is_empty() {
+        return Ok(());
+    }
+
+    xml_out.elem("table:shapes")?;
+    if let Some(shapes) = shapes {
+        for (k, v) in shapes.attr_iter() {
+            xml_out.attr_esc(k.as_ref(), v.as_str())?;
+        }
+        for c in shapes.content() {
+            match c {
+                XmlContent::Text(t) => {
+                    xml_out.text_esc(t)?;
+                }
+                XmlContent::Tag(t) => {
+                    write_xmltag(t, xml_out)?;
+                }
+            }
+        }
+    }
+
+    for (chart, object) in sheet.charts.iter().zip(chart_objects.iter()) {
+        if let Some(frame) = &chart.frame {
+            write_xmltag(frame, xml_out)?;
+        } else if let Some(object) = object {
+            write_chart_frame(sheet, chart, object, xml_out)?;
+        }
+    }
+
+    for shape in sheet_shapes {
+        write_shape(shape, xml_out)?;
+    }
+
+    xml_out.end_elem("table:shapes")?;
+
+    Ok(())
+}
+
+fn write_shape(shape: &Shape, xml_out: &mut XmlOdsWriter) -> Result<(), OdsError> {
+    let tag = match shape.shape_type() {
+        ShapeType::Rect => "draw:rect",
+        ShapeType::Ellipse => "draw:ellipse",
+        ShapeType::Line => "draw:line",
+        ShapeType::Connector => "draw:connector",
+        ShapeType::TextBox => "draw:frame",
+    };
+
+    let has_content = shape.text().is_some() || shape.shape_type() == ShapeType::TextBox;
+    if has_content {
+        xml_out.elem(tag)?;
+    } else {
+        xml_out.empty(tag)?;
+    }
+
+    if let Some(z_index) = shape.z_index() {
+        xml_out.attr("draw:z-index", &z_index.to_string())?;
+    }
+    if !shape.name().is_empty() {
+        xml_out.attr_esc("draw:name", shape.name().as_str())?;
+    }
+    if let Some(style) = shape.style() {
+        xml_out.attr_esc("draw:style-name", style.as_str())?;
+    }
+    if let Some(text_style) = shape.text_style() {
+        xml_out.attr_esc("draw:text-style-name", text_style.as_str())?;
+    }
+
+    let (x, y) = shape.position();
+    match shape.shape_type() {
+        ShapeType::Line | ShapeType::Connector => {
+            let (x2, y2) = shape.end();
+            xml_out.attr("svg:x1", &x.to_string())?;
+            xml_out.attr("svg:y1", &y.to_string())?;
+            xml_out.attr("svg:x2", &x2.to_string())?;
+            xml_out.attr("svg:y2", &y2.to_string())?;
+        }
+        _ => {
+            let (width, height) = shape.size();
+            xml_out.attr("svg:x", &x.to_string())?;
+            xml_out.attr("svg:y", &y.to_string())?;
+            xml_out.attr("svg:width", &width.to_string())?;
+            xml_out.attr("svg:height", &height.to_string())?;
+        }
+    }
+
+    if shape.shape_type() == ShapeType::TextBox {
+        xml_out.elem("draw:text-box")?;
+    }
+    if let Some(text) = shape.text() {
+        for l in text.split('\n') {
+            xml_out.elem("text:p")?;
+            xml_out.text_esc(l)?;
+            xml_out.end_elem("text:p")?;
+        }
+    }
+    if shape.shape_type() == ShapeType::TextBox {
+        xml_out.end_elem("draw:text-box")?;
+    }
+
+    if has_content {
+        xml_out.end_elem(tag)?;
+    }
+
+    Ok(())
+}
+
+/// Range with the table name of the sheet, if none is given.
+fn qualified_range(sheet: &Sheet, range: &CellRange) -> String {
+    if range.table().is_some() {
+        range.to_string()
+    } else {
+        let mut range = range.clone();
+        range.set_table(sheet.name().as_str());
+        range.to_string()
+    }
+}
+
+/// Cell reference with the table name of the sheet, if none is given.
+fn qualified_cellref(sheet: &Sheet, cellref: &CellRef) -> String {
+    if cellref.table().is_some() {
+        cellref.to_string()
+    } else {
+        let mut cellref = cellref.clone();
+        cellref.set_table(sheet.name().as_str());
+        cellref.to_string()
+    }
+}
+
+fn write_chart_frame(
+    sheet: &Sheet,
+    chart: &Chart,
+    object: &str,
+    xml_out: &mut XmlOdsWriter,
+) -> Result<(), OdsError> {
+    let (x, y) = chart.position();
+    let (width, height) = chart.size();
+
+    xml_out.elem("draw:frame")?;
+    xml_out.attr("draw:z-index", "0")?;
+    if chart.name().is_empty() {
+        xml_out.attr_esc("draw:name", object)?;
+    } else {
+        xml_out.attr_esc("draw:name", chart.name().as_str())?;
+    }
+    xml_out.attr("svg:x", &x.to_string())?;
+    xml_out.attr("svg:y", &y.to_string())?;
+    xml_out.attr("svg:width", &width.to_string())?;
+    xml_out.attr("svg:height", &height.to_string())?;
+
+    xml_out.empty("draw:object")?;
+    let ranges: Vec<String> = chart
+        .ranges()
+        .iter()
+        .map(|r| qualified_range(sheet, r))
+        .collect();
+    xml_out.attr_esc("draw:notify-on-update-of-ranges", &ranges.join(" "))?;
+    xml_out.attr_esc("xlink:href", &format!("./{}", object))?;
+    xml_out.attr("xlink:type", "simple")?;
+    xml_out.attr("xlink:show", "embed")?;
+    xml_out.attr("xlink:actuate", "onLoad")?;
+
+    xml_out.end_elem("draw:frame")?;
+
+    Ok(())
+}
+
+/// Writes the chart sub-documents.
+fn write_charts(
+    book: &WorkBook,
+    chart_objects: &[Vec<Option<String>>],
+    zip_out: &mut OdsWriter,
+    file_set: &mut HashSet<String>,
+) -> Result<(), OdsError> {
+    for (sheet, objects) in book.sheets.iter().zip(chart_objects.iter()) {
+        for (chart, object) in sheet.charts.iter().zip(objects.iter()) {
+            if let Some(object) = object {
+                zip_out.add_directory(object, FileOptions::default())?;
+                write_chart_content(sheet, chart, object, zip_out, file_set)?;
+                write_chart_styles(object, zip_out, file_set)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_chart_content(
+    sheet: &Sheet,
+    chart: &Chart,
+    object: &str,
+    zip_out: &mut OdsWriter,
+    file_set: &mut HashSet<String>,
+) -> Result<(), OdsError> {
+    let path = format!("{}/content.xml", object);
+    file_set.insert(path.clone());
+
+    let w = zip_out.start_file(&path, FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+
+    xml_out.elem("office:document-content")?;
+    xml_out.attr(
+        "xmlns:office",
+        "urn:oasis:names:tc:opendocument:xmlns:office:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:style",
+        "urn:oasis:names:tc:opendocument:xmlns:style:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:text",
+        "urn:oasis:names:tc:opendocument:xmlns:text:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:table",
+        "urn:oasis:names:tc:opendocument:xmlns:table:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:draw",
+        "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:fo",
+        "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0",
+    )?;
+    xml_out.attr("xmlns:xlink", "http://www.w3.org/1999/xlink")?;
+    xml_out.attr(
+        "xmlns:svg",
+        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:chart",
+        "urn:oasis:names:tc:opendocument:xmlns:chart:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:number",
+        "urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0",
+    )?;
+    xml_out.attr("office:version", "1.2")?;
+
+    xml_out.elem("office:body")?;
+    xml_out.elem("office:chart")?;
+
+    let (width, height) = chart.size();
+    xml_out.elem("chart:chart")?;
+    xml_out.attr("svg:width", &width.to_string())?;
+    xml_out.attr("svg:height", &height.to_string())?;
+    xml_out.attr("chart:class", &chart.chart_type().to_string())?;
+
+    if let Some(title) = chart.title() {
+        xml_out.elem("chart:title")?;
+        xml_out.elem_text_esc("text:p", title)?;
+        xml_out.end_elem("chart:title")?;
+    }
+
+    if let Some(legend) = chart.legend() {
+        xml_out.empty("chart:legend")?;
+        xml_out.attr("chart:legend-position", &legend.to_string())?;
+    }
+
+    xml_out.elem("chart:plot-area")?;
+    let ranges: Vec<String> = chart
+        .ranges()
+        .iter()
+        .map(|r| qualified_range(sheet, r))
+        .collect();
+    xml_out.attr_esc("table:cell-range-address", &ranges.join(" "))?;
+    xml_out.attr("chart:data-source-has-labels", "none")?;
+
+    if chart.x_axis_title().is_none() && chart.categories().is_none() {
+        xml_out.empty("chart:axis")?;
+    } else {
+        xml_out.elem("chart:axis")?;
+    }
+    xml_out.attr("chart:dimension", "x")?;
+    xml_out.attr("chart:name", "primary-x")?;
+    if let Some(title) = chart.x_axis_title() {
+        xml_out.elem("chart:title")?;
+        xml_out.elem_text_esc("text:p", title)?;
+        xml_out.end_elem("chart:title")?;
+    }
+    if let Some(categories) = chart.categories() {
+        xml_out.empty("chart:categories")?;
+        xml_out.attr_esc(
+            "table:cell-range-address",
+            &qualified_range(sheet, categories),
+        )?;
+    }
+    if chart.x_axis_title().is_some() || chart.categories().is_some() {
+        xml_out.end_elem("chart:axis")?;
+    }
+
+    if let Some(title) = chart.y_axis_title() {
+        xml_out.elem("chart:axis")?;
+        xml_out.attr("chart:dimension", "y")?;
+        xml_out.attr("chart:name", "primary-y")?;
+        xml_out.elem("chart:title")?;
+        xml_out.elem_text_esc("text:p", title)?;
+        xml_out.end_elem("chart:title")?;
+        xml_out.end_elem("chart:axis")?;
+    } else {
+        xml_out.empty("chart:axis")?;
+        xml_out.attr("chart:dimension", "y")?;
+        xml_out.attr("chart:name", "primary-y")?;
+    }
+
+    for series in chart.series() {
+        if series.domain().is_some() {
+            xml_out.elem("chart:series")?;
+        } else {
+            xml_out.empty("chart:series")?;
+        }
+        xml_out.attr_esc(
+            "chart:values-cell-range-address",
+            &qualified_range(sheet, series.values()),
+        )?;
+        if let Some(label) = series.label() {
+            xml_out.attr_esc("chart:label-cell-address", &qualified_cellref(sheet, label))?;
+        }
+        xml_out.attr("chart:class", &chart.chart_type().to_string())?;
+        if let Some(domain) = series.domain() {
+            xml_out.empty("chart:domain")?;
+            xml_out.attr_esc("table:cell-range-address", &qualified_range(sheet, domain))?;
+            xml_out.end_elem("chart:series")?;
+        }
+    }
+
+    xml_out.end_elem("chart:plot-area")?;
+    xml_out.end_elem("chart:chart")?;
+    xml_out.end_elem("office:chart")?;
+    xml_out.end_elem("office:body")?;
+    xml_out.end_elem("office:document-content")?;
+
+    xml_out.close()?;
+
+    Ok(())
+}
+
+fn write_chart_styles(
+    object: &str,
+    zip_out: &mut OdsWriter,
+    file_set: &mut HashSet<String>,
+) -> Result<(), OdsError> {
+    let path = format!("{}/styles.xml", object);
+    file_set.insert(path.clone());
+
+    let w = zip_out.start_file(&path, FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+
+    xml_out.elem("office:document-styles")?;
+    xml_out.attr(
+        "xmlns:office",
+        "urn:oasis:names:tc:opendocument:xmlns:office:1.0",
+    )?;
+    xml_out.attr(
+        "xmlns:style",
+        "urn:oasis:names:tc:opendocument:xmlns:style:1.0",
+    )?;
+    xml_out.attr("office:version", "1.2")?;
+    xml_out.empty("office:styles")?;
+    xml_out.end_elem("office:document-styles")?;
+
+    xml_out.close()?;
+
+    Ok(())
+}
+
+fn write_scenario(
+    sheet: &Sheet,
+    scenario: &Scenario,
+    xml_out: &mut XmlOdsWriter,
+) -> Result<(), OdsError> {
+    xml_out.empty("table:scenario")?;
+    let ranges: Vec<String> = scenario
+        .ranges()
+        .iter()
+        .map(|r| qualified_range(sheet, r))
+        .collect();
+    xml_out.attr_esc("table:scenario-ranges", &ranges.join(" "))?;
+    xml_out.attr("table:is-active", &scenario.active().to_string())?;
+    xml_out.attr(
+        "table:display-border",
+        &scenario.display_border().to_string(),
+    )?;
+    if let Some(color) = scenario.border_color() {
+        xml_out.attr("table:border-color", &color_string(color))?;
+    }
+    xml_out.attr("table:copy-back", &scenario.copy_back().to_string())?;
+    xml_out.attr("table:copy-styles", &scenario.copy_styles().to_string())?;
+    xml_out.attr("table:copy-formulas", &scenario.copy_formulas().to_string())?;
+    if let Some(comment) = scenario.comment() {
+        xml_out.attr_esc("table:comment", comment.as_str())?;
+    }
+    if scenario.protected() {
+        xml_out.attr("table:protected", "true")?;
+    }
+
+    Ok(())
+}
+
+fn write_table_protection(
+    options: &ProtectionOptions,
+    xml_out: &mut XmlOdsWriter,
+) -> Result<(), OdsError> {
+    xml_out.empty("loext:table-protection")?;
+    if options.select_protected_cells() {
+        xml_out.attr("loext:select-protected-cells", "true")?;
+    }
+    if options.select_unprotected_cells() {
+        xml_out.attr("loext:select-unprotected-cells", "true")?;
+    }
+    if options.insert_columns() {
+        xml_out.attr("loext:insert-columns", "true")?;
+    }
+    if options.insert_rows() {
+        xml_out.attr("loext:insert-rows", "true")?;
+    }
+    if options.delete_columns() {
+        xml_out.attr("loext:delete-columns", "true")?;
+    }
+    if options.delete_rows() {
+        xml_out.attr("loext:delete-rows", "true")?;
+    }
+    Ok(())
+}
+
 fn write_empty_cells(
     mut forward_dc: u32,
     hidden_cols: u32,
@@ -1055,6 +1746,7 @@ fn write_table_columns(
 fn write_cell(
     book: &WorkBook,
     cell: &SCell,
+    shapes: &[&Shape],
     is_hidden: bool,
     xml_out: &mut XmlOdsWriter,
 ) -> Result<(), OdsError> {
@@ -1064,9 +1756,14 @@ fn write_cell(
         "table:table-cell"
     };
 
-    match cell.value {
-        Value::Empty => xml_out.empty(tag)?,
-        _ => xml_out.elem(tag)?,
+    let has_content = match cell.value {
+        Value::Empty => !shapes.is_empty(),
+        _ => true,
+    };
+    if has_content {
+        xml_out.elem(tag)?;
+    } else {
+        xml_out.empty(tag)?;
     }
 
     if let Some(formula) = &cell.formula {
@@ -1130,17 +1827,7 @@ fn write_cell(
         Value::TimeDuration(d) => {
             xml_out.attr("office:value-type", "time")?;
 
-            let mut value = String::from("PT");
-            value.push_str(&d.num_hours().to_string());
-            value.push_str("H");
-            value.push_str(&(d.num_minutes() % 60).to_string());
-            value.push_str("M");
-            value.push_str(&(d.num_seconds() % 60).to_string());
-            value.push_str(".");
-            value.push_str(&(d.num_milliseconds() % 1000).to_string());
-            value.push_str("S");
-
-            xml_out.attr("office:time-value", value.as_str())?;
+            xml_out.attr("office:time-value", duration_string(d).as_str())?;
 
             xml_out.elem("text:p")?;
             if let Some(value_style) = value_style {
@@ -1207,9 +1894,12 @@ fn write_cell(
         }
     }
 
-    match cell.value {
-        Value::Empty => {}
-        _ => xml_out.end_elem(tag)?,
+    for shape in shapes {
+        write_shape(shape, xml_out)?;
+    }
+
+    if has_content {
+        xml_out.end_elem(tag)?;
     }
 
     Ok(())
@@ -1220,7 +1910,11 @@ fn write_font_decl(
     origin: StyleOrigin,
     xml_out: &mut XmlOdsWriter,
 ) -> Result<(), OdsError> {
-    for font in fonts.values().filter(|s| s.origin() == origin) {
+    // styles.xml declares all fonts, common styles may use any of them.
+    for font in fonts
+        .values()
+        .filter(|s| origin == StyleOrigin::Styles || s.origin() == origin)
+    {
         xml_out.empty("style:font-face")?;
         xml_out.attr_esc("style:name", font.name().as_str())?;
         for (a, v) in font.attr_iter() {
@@ -1230,6 +1924,41 @@ fn write_font_decl(
     Ok(())
 }
 
+/// Named styles and default-styles always go to styles.xml, automatic
+/// styles to the part they come from.
+fn written_origin(style: &Style) -> StyleOrigin {
+    match style.styleuse() {
+        StyleUse::Automatic => style.origin(),
+        StyleUse::Named | StyleUse::Default => StyleOrigin::Styles,
+    }
+}
+
+/// Automatic value formats from content.xml that are referenced by the
+/// styles in styles.xml.
+fn styles_xml_content_formats(book: &WorkBook) -> HashSet<String> {
+    let mut names: Vec<&String> = book
+        .styles
+        .values()
+        .filter(|v| written_origin(v) == StyleOrigin::Styles)
+        .filter_map(|v| v.value_format())
+        .collect();
+
+    let mut content_formats = HashSet::new();
+    while let Some(name) = names.pop() {
+        if let Some(format) = book.formats.get(name) {
+            if format.origin() == StyleOrigin::Content
+                && format.styleuse() == StyleUse::Automatic
+                && content_formats.insert(name.clone())
+            {
+                if let Some(stylemaps) = format.stylemaps() {
+                    names.extend(stylemaps.iter().map(|v| v.applied_style()));
+                }
+            }
+        }
+    }
+    content_formats
+}
+
 fn write_styles(
     styles: &HashMap<String, Style>,
     origin: StyleOrigin,
@@ -1238,7 +1967,7 @@ fn write_styles(
 ) -> Result<(), OdsError> {
     for style in styles
         .values()
-        .filter(|s| s.origin() == origin && s.styleuse() == styleuse)
+        .filter(|s| s.styleuse() == styleuse && written_origin(s) == origin)
     {
         if styleuse == StyleUse::Default {
             xml_out.elem("style:default-style")?;
@@ -1246,18 +1975,11 @@ fn write_styles(
             xml_out.elem("style:style")?;
             xml_out.attr_esc("style:name", style.name().as_str())?;
         }
-        let family = match style.family() {
-            StyleFor::Table => "table",
-            StyleFor::TableColumn => "table-column",
-            StyleFor::TableRow => "table-row",
-            StyleFor::TableCell => "table-cell",
-            StyleFor::Paragraph => "paragraph",
-            StyleFor::Graphic => "graphic",
-            StyleFor::None => "",
-        };
-        xml_out.attr("style:family", family)?;
+        xml_out.attr("style:family", family_name(style.family()))?;
         if let Some(display_name) = &style.display_name() {
             xml_out.attr_esc("style:display-name", display_name.as_str())?;
+        } else if styleuse == StyleUse::Named {
+            xml_out.attr_esc("style:display-name", style.name().as_str())?;
         }
         if let Some(parent) = &style.parent() {
             xml_out.attr_esc("style:parent-style-name", parent.as_str())?;
@@ -1290,6 +2012,12 @@ fn write_styles(
                 xml_out.attr_esc(a.as_ref(), v.as_str())?;
             }
         }
+        if !style.graphic().has_attr() {
+            xml_out.empty("style:graphic-properties")?;
+            for (a, v) in style.graphic() {
+                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            }
+        }
         if !&style.paragraph().has_attr() {
             if style.paragraph().tabstops().is_none() {
                 xml_out.empty("style:paragraph-properties")?;
@@ -1320,12 +2048,6 @@ fn write_styles(
                 xml_out.attr_esc(a.as_ref(), v.as_str())?;
             }
         }
-        if !style.graphic().has_attr() {
-            xml_out.empty("style:graphic-properties")?;
-            for (a, v) in style.graphic() {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
-            }
-        }
 
         if let Some(stylemaps) = style.stylemaps() {
             for sm in stylemaps {
@@ -1346,16 +2068,11 @@ fn write_styles(
     Ok(())
 }
 
-fn write_value_styles(
-    value_formats: &HashMap<String, ValueFormat>,
-    origin: StyleOrigin,
-    styleuse: StyleUse,
+fn write_value_styles<'a, I: Iterator<Item = &'a ValueFormat>>(
+    value_formats: I,
     xml_out: &mut XmlOdsWriter,
 ) -> Result<(), OdsError> {
-    for value_format in value_formats
-        .values()
-        .filter(|s| s.origin() == origin && s.styleuse() == styleuse)
-    {
+    for value_format in value_formats {
         let tag = match value_format.value_type() {
             ValueType::Empty => "number:empty_style", // ???
             ValueType::Boolean => "number:boolean-style",
@@ -0,0 +1,1008 @@
+//! Reads an Office Open XML spreadsheet.
+//!
+//! Only the data and the basic formatting are read: values, formulas,
+//! merged cells, column widths, row heights, hidden rows and columns,
+//! fonts, fills, borders, alignment and number formats.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use color::Rgb;
+use quick_xml::events::{BytesStart, Event};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::error::OdsError;
+use crate::formatcode::parse_excel_format_code;
+use crate::formula::{from_excel_formula, from_excel_formula_moved};
+use crate::refs::{parse_colname, parse_rowname};
+use crate::style::{
+    AttrFoBackgroundColor, AttrFoBorder, AttrParagraph, AttrTableCell, AttrText, Border,
+    CellAlignVertical, LineStyle, Style, TextAlign, TextAlignSource, WrapOption,
+};
+use crate::{ucell, Length, Sheet, Value, ValueType, Visibility, WorkBook};
+
+type XlsxXmlReader<'a> = quick_xml::Reader<&'a [u8]>;
+
+/// Reads a xlsx file.
+///
+/// Dates use the 1900 or 1904 date system of the workbook. Formulas are
+/// converted with formula::from_excel_formula(). Each cell format that
+/// is used becomes a cell style "xf<index>", each number format a
+/// ValueFormat "N<id>". The sections of a number format become extra
+/// formats "N<id>P0", ... referenced by style maps.
+pub fn read_xlsx<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
+    let file = File::open(path.as_ref())?;
+    let mut zip = ZipArchive::new(file)?;
+
+    let rels = match read_part(&mut zip, "xl/_rels/workbook.xml.rels")? {
+        Some(v) => read_rels(&v)?,
+        None => Vec::new(),
+    };
+    let workbook = match read_part(&mut zip, "xl/workbook.xml")? {
+        Some(v) => v,
+        None => return Err(OdsError::Ods("No xl/workbook.xml in the file".to_string())),
+    };
+    let (sheets, date1904) = read_workbook(&workbook)?;
+
+    let strings_part =
+        rel_target(&rels, "/sharedStrings").unwrap_or_else(|| "xl/sharedStrings.xml".to_string());
+    let strings = match read_part(&mut zip, &strings_part)? {
+        Some(v) => read_shared_strings(&v)?,
+        None => Vec::new(),
+    };
+    let styles_part = rel_target(&rels, "/styles").unwrap_or_else(|| "xl/styles.xml".to_string());
+    let stylesheet = match read_part(&mut zip, &styles_part)? {
+        Some(v) => read_stylesheet(&v)?,
+        None => XlsxStyleSheet::default(),
+    };
+
+    let mut ctx = XlsxContext {
+        date1904,
+        strings,
+        stylesheet,
+        cell_styles: HashMap::new(),
+        formats: HashMap::new(),
+    };
+
+    let mut book = WorkBook::new();
+    for (idx, entry) in sheets.iter().enumerate() {
+        let part = rels
+            .iter()
+            .find(|r| r.id == entry.rel_id)
+            .map(|r| part_name(&r.target))
+            .unwrap_or_else(|| format!("xl/worksheets/sheet{}.xml", idx + 1));
+        let data = match read_part(&mut zip, &part)? {
+            Some(v) => v,
+            None => return Err(OdsError::Ods(format!("No {} in the file", part))),
+        };
+
+        let mut sheet = Sheet::new_with_name(entry.name.as_str());
+        sheet.set_display(!entry.hidden);
+        read_sheet(&mut book, &mut ctx, &mut sheet, &data)?;
+        book.push_sheet(sheet);
+    }
+
+    Ok(book)
+}
+
+/// Contents of a part, None if it doesn't exist.
+fn read_part(zip: &mut ZipArchive<File>, name: &str) -> Result<Option<String>, OdsError> {
+    match zip.by_name(name) {
+        Ok(mut file) => {
+            let mut buf = String::new();
+            file.read_to_string(&mut buf)?;
+            Ok(Some(buf))
+        }
+        Err(ZipError::FileNotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Targets are relative to xl/ or absolute.
+fn part_name(target: &str) -> String {
+    match target.strip_prefix('/') {
+        Some(v) => v.to_string(),
+        None => format!("xl/{}", target),
+    }
+}
+
+/// Attribute by its local name.
+fn attr(xml: &XlsxXmlReader, tag: &BytesStart, name: &[u8]) -> Result<Option<String>, OdsError> {
+    for attr in tag.attributes().with_checks(false) {
+        let attr = attr?;
+        let local = attr.key.rsplit(|c| *c == b':').next().unwrap_or(attr.key);
+        if local == name {
+            return Ok(Some(attr.unescape_and_decode_value(xml)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Boolean attributes are "1" or "true", missing ones are the default.
+fn attr_bool(
+    xml: &XlsxXmlReader,
+    tag: &BytesStart,
+    name: &[u8],
+    default: bool,
+) -> Result<bool, OdsError> {
+    Ok(match attr(xml, tag, name)? {
+        Some(v) => v == "1" || v == "true",
+        None => default,
+    })
+}
+
+fn attr_usize(xml: &XlsxXmlReader, tag: &BytesStart, name: &[u8]) -> Result<usize, OdsError> {
+    match attr(xml, tag, name)? {
+        Some(v) => Ok(v.parse()?),
+        None => Ok(0),
+    }
+}
+
+struct XlsxRel {
+    id: String,
+    kind: String,
+    target: String,
+}
+
+/// Part of the first relationship with the type.
+fn rel_target(rels: &[XlsxRel], kind: &str) -> Option<String> {
+    rels.iter()
+        .find(|r| r.kind.ends_with(kind))
+        .map(|r| part_name(&r.target))
+}
+
+fn read_rels(data: &str) -> Result<Vec<XlsxRel>, OdsError> {
+    let mut xml = quick_xml::Reader::from_str(data);
+    let mut rels = Vec::new();
+
+    let mut buf = Vec::new();
+    loop {
+        match xml.read_event(&mut buf)? {
+            Event::Start(tag) | Event::Empty(tag) if tag.local_name() == b"Relationship" => {
+                rels.push(XlsxRel {
+                    id: attr(&xml, &tag, b"Id")?.unwrap_or_default(),
+                    kind: attr(&xml, &tag, b"Type")?.unwrap_or_default(),
+                    target: attr(&xml, &tag, b"Target")?.unwrap_or_default(),
+                });
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(rels)
+}
+
+struct XlsxSheetEntry {
+    name: String,
+    hidden: bool,
+    rel_id: String,
+}
+
+/// Sheets and the date system.
+fn read_workbook(data: &str) -> Result<(Vec<XlsxSheetEntry>, bool), OdsError> {
+    let mut xml = quick_xml::Reader::from_str(data);
+    let mut sheets = Vec::new();
+    let mut date1904 = false;
+
+    let mut buf = Vec::new();
+    loop {
+        match xml.read_event(&mut buf)? {
+            Event::Start(tag) | Event::Empty(tag) => match tag.local_name() {
+                b"workbookPr" => {
+                    date1904 = attr_bool(&xml, &tag, b"date1904", false)?;
+                }
+                b"sheet" => {
+                    let state = attr(&xml, &tag, b"state")?;
+                    sheets.push(XlsxSheetEntry {
+                        name: attr(&xml, &tag, b"name")?.unwrap_or_default(),
+                        hidden: state.map(|v| v != "visible").unwrap_or(false),
+                        rel_id: attr(&xml, &tag, b"id")?.unwrap_or_default(),
+                    });
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((sheets, date1904))
+}
+
+/// The text of each si, without phonetic runs.
+fn read_shared_strings(data: &str) -> Result<Vec<String>, OdsError> {
+    let mut xml = quick_xml::Reader::from_str(data);
+    let mut strings = Vec::new();
+
+    let mut text = String::new();
+    let mut in_t = false;
+    let mut in_phonetic = false;
+
+    let mut buf = Vec::new();
+    loop {
+        match xml.read_event(&mut buf)? {
+            Event::Start(tag) => match tag.local_name() {
+                b"t" if !in_phonetic => in_t = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::Empty(tag) if tag.local_name() == b"si" => strings.push(String::new()),
+            Event::Text(v) if in_t => text.push_str(&v.unescape_and_decode(&xml)?),
+            Event::End(tag) => match tag.local_name() {
+                b"t" => in_t = false,
+                b"rPh" => in_phonetic = false,
+                b"si" => strings.push(decode_escapes(&std::mem::take(&mut text))),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(strings)
+}
+
+/// Control characters are written as _xHHHH_.
+fn decode_escapes(s: &str) -> String {
+    let mut buf = String::new();
+    let mut rest = s;
+    while let Some(idx) = rest.find("_x") {
+        buf.push_str(&rest[..idx]);
+        let decoded = rest
+            .get(idx + 2..idx + 7)
+            .filter(|v| v.ends_with('_'))
+            .and_then(|v| u32::from_str_radix(&v[..4], 16).ok())
+            .and_then(std::char::from_u32);
+        match decoded {
+            Some(c) => {
+                buf.push(c);
+                rest = &rest[idx + 7..];
+            }
+            None => {
+                buf.push_str("_x");
+                rest = &rest[idx + 2..];
+            }
+        }
+    }
+    buf.push_str(rest);
+    buf
+}
+
+#[derive(Debug, Clone, Default)]
+struct XlsxFont {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    size: Option<f64>,
+    color: Option<Rgb<u8>>,
+    name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct XlsxBorder {
+    left: Option<(String, Option<Rgb<u8>>)>,
+    right: Option<(String, Option<Rgb<u8>>)>,
+    top: Option<(String, Option<Rgb<u8>>)>,
+    bottom: Option<(String, Option<Rgb<u8>>)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct XlsxXf {
+    num_fmt: u32,
+    font: usize,
+    fill: usize,
+    border: usize,
+    horizontal: Option<String>,
+    vertical: Option<String>,
+    wrap: bool,
+}
+
+#[derive(Debug, Default)]
+struct XlsxStyleSheet {
+    num_fmts: HashMap<u32, String>,
+    fonts: Vec<XlsxFont>,
+    fills: Vec<Option<Rgb<u8>>>,
+    borders: Vec<XlsxBorder>,
+    xfs: Vec<XlsxXf>,
+}
+
+/// Color from "AARRGGBB" or "RRGGBB". Theme and indexed colors are
+/// not supported.
+fn parse_argb(v: &str) -> Option<Rgb<u8>> {
+    if v.len() == 8 || v.len() == 6 {
+        let v = &v[v.len() - 6..];
+        let r = u8::from_str_radix(&v[0..2], 16).ok()?;
+        let g = u8::from_str_radix(&v[2..4], 16).ok()?;
+        let b = u8::from_str_radix(&v[4..6], 16).ok()?;
+        Some(Rgb::new(r, g, b))
+    } else {
+        None
+    }
+}
+
+fn read_stylesheet(data: &str) -> Result<XlsxStyleSheet, OdsError> {
+    let mut xml = quick_xml::Reader::from_str(data);
+    let mut sheet = XlsxStyleSheet::default();
+
+    let mut in_cell_xfs = false;
+    let mut font: Option<XlsxFont> = None;
+    let mut fill: Option<Option<Rgb<u8>>> = None;
+    let mut solid = false;
+    let mut border: Option<XlsxBorder> = None;
+    let mut side: Option<(&'static str, String, Option<Rgb<u8>>)> = None;
+
+    let mut buf = Vec::new();
+    loop {
+        let evt = xml.read_event(&mut buf)?;
+        let empty_tag = matches!(evt, Event::Empty(_));
+        match evt {
+            Event::Start(tag) | Event::Empty(tag) => match tag.local_name() {
+                b"numFmt" => {
+                    let id = attr_usize(&xml, &tag, b"numFmtId")? as u32;
+                    let code = attr(&xml, &tag, b"formatCode")?.unwrap_or_default();
+                    sheet.num_fmts.insert(id, code);
+                }
+                b"font" if empty_tag => sheet.fonts.push(XlsxFont::default()),
+                b"font" => font = Some(XlsxFont::default()),
+                b"b" | b"i" | b"strike" if font.is_some() => {
+                    let on = attr_bool(&xml, &tag, b"val", true)?;
+                    if let Some(font) = &mut font {
+                        match tag.local_name() {
+                            b"b" => font.bold = on,
+                            b"i" => font.italic = on,
+                            _ => font.strike = on,
+                        }
+                    }
+                }
+                b"u" if font.is_some() => {
+                    let on = attr(&xml, &tag, b"val")?
+                        .map(|v| v != "none")
+                        .unwrap_or(true);
+                    if let Some(font) = &mut font {
+                        font.underline = on;
+                    }
+                }
+                b"sz" if font.is_some() => {
+                    let size = attr(&xml, &tag, b"val")?.and_then(|v| v.parse().ok());
+                    if let Some(font) = &mut font {
+                        font.size = size;
+                    }
+                }
+                b"name" if font.is_some() => {
+                    let name = attr(&xml, &tag, b"val")?;
+                    if let Some(font) = &mut font {
+                        font.name = name;
+                    }
+                }
+                b"fill" if empty_tag => sheet.fills.push(None),
+                b"fill" => {
+                    fill = Some(None);
+                    solid = false;
+                }
+                b"patternFill" => {
+                    solid = attr(&xml, &tag, b"patternType")?.as_deref() == Some("solid");
+                }
+                b"border" if empty_tag => sheet.borders.push(XlsxBorder::default()),
+                b"border" => border = Some(XlsxBorder::default()),
+                b"left" | b"start" | b"right" | b"end" | b"top" | b"bottom" if border.is_some() => {
+                    let name = match tag.local_name() {
+                        b"left" | b"start" => "left",
+                        b"right" | b"end" => "right",
+                        b"top" => "top",
+                        _ => "bottom",
+                    };
+                    if let Some(style) = attr(&xml, &tag, b"style")? {
+                        side = Some((name, style, None));
+                        if empty_tag {
+                            set_border_side(&mut border, side.take());
+                        }
+                    }
+                }
+                b"color" | b"fgColor" => {
+                    let color = attr(&xml, &tag, b"rgb")?.and_then(|v| parse_argb(&v));
+                    if let Some((_, _, side_color)) = &mut side {
+                        *side_color = color;
+                    } else if let Some(font) = &mut font {
+                        font.color = color;
+                    } else if let Some(fill) = &mut fill {
+                        if solid && tag.local_name() == b"fgColor" {
+                            *fill = color;
+                        }
+                    }
+                }
+                b"cellXfs" => in_cell_xfs = !empty_tag,
+                b"xf" if in_cell_xfs => {
+                    sheet.xfs.push(XlsxXf {
+                        num_fmt: attr_usize(&xml, &tag, b"numFmtId")? as u32,
+                        font: attr_usize(&xml, &tag, b"fontId")?,
+                        fill: attr_usize(&xml, &tag, b"fillId")?,
+                        border: attr_usize(&xml, &tag, b"borderId")?,
+                        ..Default::default()
+                    });
+                }
+                b"alignment" if in_cell_xfs => {
+                    let horizontal = attr(&xml, &tag, b"horizontal")?;
+                    let vertical = attr(&xml, &tag, b"vertical")?;
+                    let wrap = attr_bool(&xml, &tag, b"wrapText", false)?;
+                    if let Some(xf) = sheet.xfs.last_mut() {
+                        xf.horizontal = horizontal;
+                        xf.vertical = vertical;
+                        xf.wrap = wrap;
+                    }
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag.local_name() {
+                b"font" => {
+                    if let Some(font) = font.take() {
+                        sheet.fonts.push(font);
+                    }
+                }
+                b"fill" => {
+                    if let Some(fill) = fill.take() {
+                        sheet.fills.push(fill);
+                    }
+                }
+                b"left" | b"start" | b"right" | b"end" | b"top" | b"bottom" => {
+                    set_border_side(&mut border, side.take());
+                }
+                b"border" => {
+                    if let Some(border) = border.take() {
+                        sheet.borders.push(border);
+                    }
+                }
+                b"cellXfs" => in_cell_xfs = false,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(sheet)
+}
+
+fn set_border_side(
+    border: &mut Option<XlsxBorder>,
+    side: Option<(&'static str, String, Option<Rgb<u8>>)>,
+) {
+    if let (Some(border), Some((name, style, color))) = (border, side) {
+        if style == "none" {
+            return;
+        }
+        let value = Some((style, color));
+        match name {
+            "left" => border.left = value,
+            "right" => border.right = value,
+            "top" => border.top = value,
+            _ => border.bottom = value,
+        }
+    }
+}
+
+/// State while reading the sheets.
+struct XlsxContext {
+    date1904: bool,
+    strings: Vec<String>,
+    stylesheet: XlsxStyleSheet,
+    /// Cell style and value type for each cell format.
+    cell_styles: HashMap<usize, Option<(String, ValueType)>>,
+    /// ValueFormat and value type for each number format.
+    formats: HashMap<u32, Option<(String, ValueType)>>,
+}
+
+impl XlsxContext {
+    /// Cell style for the cell format. Creates the style and its value
+    /// format when used for the first time. None if it's the default.
+    fn cell_style(&mut self, book: &mut WorkBook, idx: usize) -> Option<(String, ValueType)> {
+        if let Some(style) = self.cell_styles.get(&idx) {
+            return style.clone();
+        }
+        let style = self.create_cell_style(book, idx);
+        self.cell_styles.insert(idx, style.clone());
+        style
+    }
+
+    fn create_cell_style(
+        &mut self,
+        book: &mut WorkBook,
+        idx: usize,
+    ) -> Option<(String, ValueType)> {
+        let xf = self.stylesheet.xfs.get(idx)?.clone();
+        let format = self.value_format(book, xf.num_fmt);
+        let value_type = format.as_ref().map(|v| v.1).unwrap_or(ValueType::Number);
+
+        let name = format!("xf{}", idx);
+        let mut style = Style::new_cell_style(
+            name.as_str(),
+            format.as_ref().map(|v| v.0.as_str()).unwrap_or(""),
+        );
+        let mut used = format.is_some();
+
+        if xf.font != 0 {
+            if let Some(font) = self.stylesheet.fonts.get(xf.font) {
+                used = true;
+                let text = style.text_mut();
+                if font.bold {
+                    text.set_font_bold();
+                }
+                if font.italic {
+                    text.set_font_italic();
+                }
+                if font.underline {
+                    text.set_font_underline_style(LineStyle::Solid);
+                }
+                if font.strike {
+                    text.set_font_line_through_style(LineStyle::Solid);
+                }
+                if let Some(size) = font.size {
+                    text.set_font_size(Length::Pt(size));
+                }
+                if let Some(color) = font.color {
+                    text.set_color(color);
+                }
+                if let Some(name) = &font.name {
+                    text.set_font_name(book.font_for(name));
+                }
+            }
+        }
+
+        if let Some(Some(color)) = self.stylesheet.fills.get(xf.fill) {
+            used = true;
+            style.cell_mut().set_background_color(*color);
+        }
+
+        if let Some(border) = self.stylesheet.borders.get(xf.border) {
+            let cell = style.cell_mut();
+            if let Some((width, border, color)) = border_attr(&border.left) {
+                used = true;
+                cell.set_border_left(width, border, color);
+            }
+            if let Some((width, border, color)) = border_attr(&border.right) {
+                used = true;
+                cell.set_border_right(width, border, color);
+            }
+            if let Some((width, border, color)) = border_attr(&border.top) {
+                used = true;
+                cell.set_border_top(width, border, color);
+            }
+            if let Some((width, border, color)) = border_attr(&border.bottom) {
+                used = true;
+                cell.set_border_bottom(width, border, color);
+            }
+        }
+
+        let align = match xf.horizontal.as_deref() {
+            Some("left") => Some(TextAlign::Start),
+            Some("center") | Some("centerContinuous") => Some(TextAlign::Center),
+            Some("right") => Some(TextAlign::End),
+            Some("justify") | Some("distributed") => Some(TextAlign::Justify),
+            _ => None,
+        };
+        if let Some(align) = align {
+            used = true;
+            style.paragraph_mut().set_text_align(align);
+            style
+                .paragraph_mut()
+                .set_text_align_source(TextAlignSource::Fix);
+        }
+        let vertical = match xf.vertical.as_deref() {
+            Some("top") => Some(CellAlignVertical::Top),
+            Some("center") => Some(CellAlignVertical::Middle),
+            Some("bottom") => Some(CellAlignVertical::Bottom),
+            _ => None,
+        };
+        if let Some(vertical) = vertical {
+            used = true;
+            style.cell_mut().set_vertical_align(vertical);
+        }
+        if xf.wrap {
+            used = true;
+            style.cell_mut().set_wrap_option(WrapOption::Wrap);
+        }
+
+        if used {
+            book.add_style(style);
+            Some((name, value_type))
+        } else {
+            None
+        }
+    }
+
+    /// ValueFormat for the number format. Creates it when used for the
+    /// first time. None for "General".
+    fn value_format(&mut self, book: &mut WorkBook, id: u32) -> Option<(String, ValueType)> {
+        if let Some(format) = self.formats.get(&id) {
+            return format.clone();
+        }
+
+        let code = self
+            .stylesheet
+            .num_fmts
+            .get(&id)
+            .map(|v| v.as_str())
+            .or_else(|| builtin_format(id));
+        let name = format!("N{}", id);
+        // Invalid codes are ignored like "General".
+        let formats = code.and_then(|code| parse_excel_format_code(name.clone(), code).ok());
+        let result = formats.flatten().map(|(value_type, formats)| {
+            for format in formats {
+                book.add_format(format);
+            }
+            (name, value_type)
+        });
+
+        self.formats.insert(id, result.clone());
+        result
+    }
+
+    /// Converts the serial date.
+    fn datetime(&self, serial: f64) -> NaiveDateTime {
+        let (base, serial) = if self.date1904 {
+            (NaiveDate::from_ymd(1904, 1, 1), serial)
+        } else if serial < 60f64 {
+            // Excel has a 1900-02-29
+            (NaiveDate::from_ymd(1899, 12, 31), serial)
+        } else {
+            (NaiveDate::from_ymd(1899, 12, 30), serial)
+        };
+        base.and_hms(0, 0, 0) + Duration::milliseconds((serial * 86_400_000f64).round() as i64)
+    }
+}
+
+/// Border width, style and color.
+fn border_attr(side: &Option<(String, Option<Rgb<u8>>)>) -> Option<(Length, Border, Rgb<u8>)> {
+    let (style, color) = side.as_ref()?;
+    let color = color.unwrap_or_else(|| Rgb::new(0, 0, 0));
+    let (width, border) = match style.as_str() {
+        "thin" => (0.75, Border::Solid),
+        "medium" => (1.75, Border::Solid),
+        "thick" => (2.5, Border::Solid),
+        "double" => (2.5, Border::Double),
+        "hair" | "dotted" => (0.75, Border::Dotted),
+        "dashed" | "dashDot" | "dashDotDot" | "slantDashDot" => (0.75, Border::Dashed),
+        "mediumDashed" | "mediumDashDot" | "mediumDashDotDot" => (1.75, Border::Dashed),
+        _ => return None,
+    };
+    Some((Length::Pt(width), border, color))
+}
+
+/// The predefined number formats.
+fn builtin_format(id: u32) -> Option<&'static str> {
+    Some(match id {
+        1 => "0",
+        2 => "0.00",
+        3 => "#,##0",
+        4 => "#,##0.00",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        12 => "# ?/?",
+        13 => "# ??/??",
+        14 => "mm-dd-yy",
+        15 => "d-mmm-yy",
+        16 => "d-mmm",
+        17 => "mmm-yy",
+        18 => "h:mm AM/PM",
+        19 => "h:mm:ss AM/PM",
+        20 => "h:mm",
+        21 => "h:mm:ss",
+        22 => "m/d/yy h:mm",
+        37 => "#,##0 ;(#,##0)",
+        38 => "#,##0 ;[Red](#,##0)",
+        39 => "#,##0.00;(#,##0.00)",
+        40 => "#,##0.00;[Red](#,##0.00)",
+        45 => "mm:ss",
+        46 => "[h]:mm:ss",
+        47 => "mmss.0",
+        48 => "##0.0E+0",
+        49 => "@",
+        _ => return None,
+    })
+}
+
+/// Text of the current cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CellText {
+    None,
+    Value,
+    Formula,
+    Inline,
+}
+
+/// One c element.
+#[derive(Debug, Default)]
+struct XlsxCell {
+    row: ucell,
+    col: ucell,
+    cell_type: String,
+    style: usize,
+    value: String,
+    formula: String,
+    shared: Option<String>,
+    shared_master: bool,
+}
+
+/// Row and column of "A1".
+fn parse_a1(s: &str) -> Option<(ucell, ucell)> {
+    let s = s.replace('$', "").to_uppercase();
+    let mut pos = 0;
+    let col = parse_colname(&s, &mut pos)?;
+    let row = parse_rowname(&s, &mut pos)?;
+    if pos == s.len() {
+        Some((row, col))
+    } else {
+        None
+    }
+}
+
+fn read_sheet(
+    book: &mut WorkBook,
+    ctx: &mut XlsxContext,
+    sheet: &mut Sheet,
+    data: &str,
+) -> Result<(), OdsError> {
+    let mut xml = quick_xml::Reader::from_str(data);
+
+    let mut row: ucell = 0;
+    let mut first_row = true;
+    let mut col: ucell = 0;
+    let mut cell: Option<XlsxCell> = None;
+    let mut text = CellText::None;
+    let mut in_phonetic = false;
+    // shared formulas: formula and position of the first cell
+    let mut shared: HashMap<String, (String, ucell, ucell)> = HashMap::new();
+
+    let mut buf = Vec::new();
+    loop {
+        let evt = xml.read_event(&mut buf)?;
+        let empty_tag = matches!(evt, Event::Empty(_));
+        match evt {
+            Event::Start(tag) | Event::Empty(tag) => match tag.local_name() {
+                b"col" => read_col(book, ctx, sheet, &xml, &tag)?,
+                b"row" => {
+                    row = match attr(&xml, &tag, b"r")? {
+                        Some(v) => v.parse::<ucell>()?.saturating_sub(1),
+                        None if first_row => 0,
+                        None => row + 1,
+                    };
+                    first_row = false;
+                    col = 0;
+                    read_row(book, ctx, sheet, &xml, &tag, row)?;
+                }
+                b"c" => {
+                    let (r, c) = match attr(&xml, &tag, b"r")?.and_then(|v| parse_a1(&v)) {
+                        Some(v) => v,
+                        None => (row, col),
+                    };
+                    col = c + 1;
+                    let new_cell = XlsxCell {
+                        row: r,
+                        col: c,
+                        cell_type: attr(&xml, &tag, b"t")?.unwrap_or_default(),
+                        style: attr_usize(&xml, &tag, b"s")?,
+                        ..Default::default()
+                    };
+                    if empty_tag {
+                        set_cell(book, ctx, sheet, &shared, new_cell)?;
+                    } else {
+                        cell = Some(new_cell);
+                    }
+                }
+                b"v" if !empty_tag => text = CellText::Value,
+                b"f" => {
+                    if let Some(cell) = &mut cell {
+                        if attr(&xml, &tag, b"t")?.as_deref() == Some("shared") {
+                            cell.shared = attr(&xml, &tag, b"si")?;
+                            cell.shared_master = !empty_tag;
+                        }
+                    }
+                    if !empty_tag {
+                        text = CellText::Formula;
+                    }
+                }
+                b"rPh" => in_phonetic = true,
+                b"t" if !empty_tag && !in_phonetic && cell.is_some() => {
+                    text = CellText::Inline;
+                }
+                b"mergeCell" => {
+                    if let Some(range) = attr(&xml, &tag, b"ref")? {
+                        let mut it = range.split(':').map(parse_a1);
+                        if let (Some(Some(from)), Some(Some(to))) = (it.next(), it.next()) {
+                            if to.0 > from.0 {
+                                sheet.set_row_span(from.0, from.1, to.0 - from.0 + 1);
+                            }
+                            if to.1 > from.1 {
+                                sheet.set_col_span(from.0, from.1, to.1 - from.1 + 1);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(v) if text != CellText::None => {
+                let v = v.unescape_and_decode(&xml)?;
+                if let Some(cell) = &mut cell {
+                    match text {
+                        CellText::Formula => cell.formula.push_str(&v),
+                        _ => cell.value.push_str(&v),
+                    }
+                }
+            }
+            Event::End(tag) => match tag.local_name() {
+                b"v" | b"f" | b"t" => text = CellText::None,
+                b"rPh" => in_phonetic = false,
+                b"c" => {
+                    if let Some(mut cell) = cell.take() {
+                        if cell.shared_master {
+                            if let Some(si) = &cell.shared {
+                                shared
+                                    .insert(si.clone(), (cell.formula.clone(), cell.row, cell.col));
+                            }
+                            cell.shared = None;
+                        }
+                        set_cell(book, ctx, sheet, &shared, cell)?;
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(())
+}
+
+/// Width, visibility and default cell style of the columns.
+fn read_col(
+    book: &mut WorkBook,
+    ctx: &mut XlsxContext,
+    sheet: &mut Sheet,
+    xml: &XlsxXmlReader,
+    tag: &BytesStart,
+) -> Result<(), OdsError> {
+    let min = attr_usize(xml, tag, b"min")?.max(1) as ucell;
+    if min > 16384 {
+        return Err(OdsError::Ods(format!("Invalid column {} in col", min)));
+    }
+    let max = (attr_usize(xml, tag, b"max")? as ucell).clamp(min, 16384);
+    let width = if attr_bool(xml, tag, b"customWidth", false)? {
+        match attr(xml, tag, b"width")? {
+            // 7 pixels per digit at 96 dpi
+            Some(v) => {
+                let cm = v.parse::<f64>()? * 7f64 * 2.54 / 96f64;
+                Some(Length::Cm((cm * 1000f64).round() / 1000f64))
+            }
+            None => None,
+        }
+    } else {
+        None
+    };
+    let hidden = attr_bool(xml, tag, b"hidden", false)?;
+    let style = match attr_usize(xml, tag, b"style")? {
+        0 => None,
+        v => ctx.cell_style(book, v).map(|v| v.0),
+    };
+
+    for col in min - 1..max {
+        if let Some(width) = width {
+            sheet.set_col_width(col, width);
+        }
+        if hidden {
+            sheet.set_column_visible(col, Visibility::Collapsed);
+        }
+        if let Some(style) = &style {
+            sheet.set_column_cell_style(col, style.as_str());
+        }
+    }
+
+    Ok(())
+}
+
+/// Height, visibility and default cell style of the row.
+fn read_row(
+    book: &mut WorkBook,
+    ctx: &mut XlsxContext,
+    sheet: &mut Sheet,
+    xml: &XlsxXmlReader,
+    tag: &BytesStart,
+    row: ucell,
+) -> Result<(), OdsError> {
+    if attr_bool(xml, tag, b"customHeight", false)? {
+        if let Some(height) = attr(xml, tag, b"ht")? {
+            sheet.set_row_height(row, Length::Pt(height.parse()?));
+        }
+    }
+    if attr_bool(xml, tag, b"hidden", false)? {
+        sheet.set_row_visible(row, Visibility::Collapsed);
+    }
+    if attr_bool(xml, tag, b"customFormat", false)? {
+        if let Some((style, _)) = ctx.cell_style(book, attr_usize(xml, tag, b"s")?) {
+            sheet.set_row_cell_style(row, style);
+        }
+    }
+    Ok(())
+}
+
+/// Converts the value and formula of the cell.
+fn set_cell(
+    book: &mut WorkBook,
+    ctx: &mut XlsxContext,
+    sheet: &mut Sheet,
+    shared: &HashMap<String, (String, ucell, ucell)>,
+    cell: XlsxCell,
+) -> Result<(), OdsError> {
+    let style = match cell.style {
+        0 => None,
+        v => ctx.cell_style(book, v),
+    };
+
+    let value = match cell.cell_type.as_str() {
+        "s" => {
+            let idx = cell.value.trim().parse::<usize>()?;
+            match ctx.strings.get(idx) {
+                Some(v) => Value::Text(v.clone()),
+                None => return Err(OdsError::Ods(format!("Invalid shared string {}", idx))),
+            }
+        }
+        "inlineStr" | "str" | "e" => Value::Text(decode_escapes(&cell.value)),
+        "b" => Value::Boolean(cell.value.trim() == "1" || cell.value.trim() == "true"),
+        "d" => {
+            let v = cell.value.trim();
+            match NaiveDateTime::parse_from_str(v, "%Y-%m-%dT%H:%M:%S%.f") {
+                Ok(v) => Value::DateTime(v),
+                Err(_) => {
+                    Value::DateTime(NaiveDate::parse_from_str(v, "%Y-%m-%d")?.and_hms(0, 0, 0))
+                }
+            }
+        }
+        _ if cell.value.trim().is_empty() => Value::Empty,
+        _ => {
+            let v = cell.value.trim().parse::<f64>()?;
+            match style.as_ref().map(|v| v.1) {
+                Some(ValueType::DateTime) => Value::DateTime(ctx.datetime(v)),
+                Some(ValueType::TimeDuration) => {
+                    Value::TimeDuration(Duration::milliseconds((v * 86_400_000f64).round() as i64))
+                }
+                Some(ValueType::Percentage) => Value::Percentage(v),
+                _ => Value::Number(v),
+            }
+        }
+    };
+
+    let formula = if !cell.formula.is_empty() {
+        Some(from_excel_formula(&cell.formula))
+    } else if let Some((formula, row, col)) = cell.shared.as_ref().and_then(|si| shared.get(si)) {
+        Some(from_excel_formula_moved(
+            formula,
+            i64::from(cell.row) - i64::from(*row),
+            i64::from(cell.col) - i64::from(*col),
+        ))
+    } else {
+        None
+    };
+
+    if !matches!(value, Value::Empty) {
+        sheet.set_value(cell.row, cell.col, value);
+    }
+    if let Some(formula) = formula {
+        sheet.set_formula(cell.row, cell.col, formula);
+    }
+    if let Some((style, _)) = style {
+        sheet.set_cell_style(cell.row, cell.col, style);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1165 @@
+//! Writes the workbook as an Office Open XML spreadsheet.
+//!
+//! The package contains the workbook, one worksheet per sheet, the
+//! shared strings and a stylesheet. Cell styles are flattened along
+//! their parents and mapped to fonts, fills, borders, alignments and
+//! number formats.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use zip::write::FileOptions;
+
+use crate::error::OdsError;
+use crate::format::FormatPartType;
+use crate::formula::to_excel_formula;
+use crate::io::tmp2zip::{TempWrite, TempZip};
+use crate::io::xmlwriter::XmlWriter;
+use crate::refs::{push_colname, push_rowname};
+use crate::style::{AttrMap, Style, StyleFor};
+use crate::stylepool::with_automatic_styles;
+use crate::{ucell, Length, Sheet, Value, ValueFormat, ValueType, Visibility, WorkBook};
+
+type XlsxWriter = TempZip;
+type XmlXlsxWriter<'a> = XmlWriter<TempWrite<'a>>;
+
+const NS_MAIN: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const NS_REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const NS_PKG_REL: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+const NS_TYPES: &str = "http://schemas.openxmlformats.org/package/2006/content-types";
+const CT_BASE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml";
+
+/// Writes the workbook as a xlsx file.
+///
+/// Values, formulas, column widths, row heights, hidden rows and columns,
+/// merged cells and cell styles are converted. Everything else has no
+/// counterpart here and is not written.
+///
+/// Sheet names are adjusted to the rules of Excel: no []:*?/\ and at
+/// most 31 characters. Formulas are converted with
+/// formula::to_excel_formula().
+pub fn write_xlsx<P: AsRef<Path>>(book: &WorkBook, xlsx_path: P) -> Result<(), OdsError> {
+    let book: &WorkBook = &with_automatic_styles(book);
+    let mut zip_writer = TempZip::new(xlsx_path.as_ref())?;
+
+    // Excel needs at least one sheet.
+    let empty = Sheet::new();
+    let mut sheets: Vec<&Sheet> = (0..book.num_sheets()).map(|i| book.sheet(i)).collect();
+    if sheets.is_empty() {
+        sheets.push(&empty);
+    }
+    let names = sheet_names(&sheets);
+
+    let mut styles = XlsxStyles::new();
+    let mut strings = SharedStrings::default();
+
+    write_content_types(sheets.len(), &mut zip_writer)?;
+    write_rels(&mut zip_writer)?;
+    write_workbook(&sheets, &names, &mut zip_writer)?;
+    write_workbook_rels(sheets.len(), &mut zip_writer)?;
+    for (idx, sheet) in sheets.iter().enumerate() {
+        write_sheet(book, idx, sheet, &mut styles, &mut strings, &mut zip_writer)?;
+    }
+    write_styles(&styles, &mut zip_writer)?;
+    write_shared_strings(&strings, &mut zip_writer)?;
+
+    zip_writer.zip()?;
+
+    Ok(())
+}
+
+/// Unique sheet names without the characters Excel forbids.
+fn sheet_names(sheets: &[&Sheet]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (idx, sheet) in sheets.iter().enumerate() {
+        let base: String = sheet
+            .name()
+            .trim_matches('\'')
+            .chars()
+            .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+            .take(31)
+            .collect();
+        let base = if base.is_empty() {
+            format!("Sheet{}", idx + 1)
+        } else {
+            base
+        };
+
+        let mut name = base.clone();
+        let mut n = 2;
+        while names
+            .iter()
+            .any(|v| v.to_lowercase() == name.to_lowercase())
+        {
+            let suffix = format!(" ({})", n);
+            name = base.chars().take(31 - suffix.len()).collect::<String>() + &suffix;
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn write_content_types(sheet_count: usize, zip_out: &mut XlsxWriter) -> Result<(), OdsError> {
+    let w = zip_out.start_file("[Content_Types].xml", FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("Types")?;
+    xml_out.attr("xmlns", NS_TYPES)?;
+
+    xml_out.empty("Default")?;
+    xml_out.attr("Extension", "rels")?;
+    xml_out.attr(
+        "ContentType",
+        "application/vnd.openxmlformats-package.relationships+xml",
+    )?;
+    xml_out.empty("Default")?;
+    xml_out.attr("Extension", "xml")?;
+    xml_out.attr("ContentType", "application/xml")?;
+
+    xml_out.empty("Override")?;
+    xml_out.attr("PartName", "/xl/workbook.xml")?;
+    xml_out.attr("ContentType", &format!("{}.sheet.main+xml", CT_BASE))?;
+    for idx in 0..sheet_count {
+        xml_out.empty("Override")?;
+        xml_out.attr("PartName", &format!("/xl/worksheets/sheet{}.xml", idx + 1))?;
+        xml_out.attr("ContentType", &format!("{}.worksheet+xml", CT_BASE))?;
+    }
+    xml_out.empty("Override")?;
+    xml_out.attr("PartName", "/xl/styles.xml")?;
+    xml_out.attr("ContentType", &format!("{}.styles+xml", CT_BASE))?;
+    xml_out.empty("Override")?;
+    xml_out.attr("PartName", "/xl/sharedStrings.xml")?;
+    xml_out.attr("ContentType", &format!("{}.sharedStrings+xml", CT_BASE))?;
+
+    xml_out.end_elem("Types")?;
+    xml_out.close()?;
+
+    Ok(())
+}
+
+fn write_rels(zip_out: &mut XlsxWriter) -> Result<(), OdsError> {
+    let w = zip_out.start_file("_rels/.rels", FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("Relationships")?;
+    xml_out.attr("xmlns", NS_PKG_REL)?;
+    xml_out.empty("Relationship")?;
+    xml_out.attr("Id", "rId1")?;
+    xml_out.attr("Type", &format!("{}/officeDocument", NS_REL))?;
+    xml_out.attr("Target", "xl/workbook.xml")?;
+    xml_out.end_elem("Relationships")?;
+    xml_out.close()?;
+
+    Ok(())
+}
+
+fn write_workbook(
+    sheets: &[&Sheet],
+    names: &[String],
+    zip_out: &mut XlsxWriter,
+) -> Result<(), OdsError> {
+    let w = zip_out.start_file("xl/workbook.xml", FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    // One sheet must stay visible.
+    let active = sheets.iter().position(|s| s.display()).unwrap_or(0);
+
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("workbook")?;
+    xml_out.attr("xmlns", NS_MAIN)?;
+    xml_out.attr("xmlns:r", NS_REL)?;
+
+    xml_out.elem("bookViews")?;
+    xml_out.empty("workbookView")?;
+    xml_out.attr("activeTab", &active.to_string())?;
+    xml_out.end_elem("bookViews")?;
+
+    xml_out.elem("sheets")?;
+    for (idx, (sheet, name)) in sheets.iter().zip(names).enumerate() {
+        xml_out.empty("sheet")?;
+        xml_out.attr_esc("name", name)?;
+        xml_out.attr("sheetId", &(idx + 1).to_string())?;
+        if !sheet.display() && idx != active {
+            xml_out.attr("state", "hidden")?;
+        }
+        xml_out.attr("r:id", &format!("rId{}", idx + 1))?;
+    }
+    xml_out.end_elem("sheets")?;
+
+    xml_out.end_elem("workbook")?;
+    xml_out.close()?;
+
+    Ok(())
+}
+
+fn write_workbook_rels(sheet_count: usize, zip_out: &mut XlsxWriter) -> Result<(), OdsError> {
+    let w = zip_out.start_file("xl/_rels/workbook.xml.rels", FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("Relationships")?;
+    xml_out.attr("xmlns", NS_PKG_REL)?;
+    for idx in 0..sheet_count {
+        xml_out.empty("Relationship")?;
+        xml_out.attr("Id", &format!("rId{}", idx + 1))?;
+        xml_out.attr("Type", &format!("{}/worksheet", NS_REL))?;
+        xml_out.attr("Target", &format!("worksheets/sheet{}.xml", idx + 1))?;
+    }
+    xml_out.empty("Relationship")?;
+    xml_out.attr("Id", &format!("rId{}", sheet_count + 1))?;
+    xml_out.attr("Type", &format!("{}/styles", NS_REL))?;
+    xml_out.attr("Target", "styles.xml")?;
+    xml_out.empty("Relationship")?;
+    xml_out.attr("Id", &format!("rId{}", sheet_count + 2))?;
+    xml_out.attr("Type", &format!("{}/sharedStrings", NS_REL))?;
+    xml_out.attr("Target", "sharedStrings.xml")?;
+    xml_out.end_elem("Relationships")?;
+    xml_out.close()?;
+
+    Ok(())
+}
+
+fn write_sheet(
+    book: &WorkBook,
+    idx: usize,
+    sheet: &Sheet,
+    styles: &mut XlsxStyles,
+    strings: &mut SharedStrings,
+    zip_out: &mut XlsxWriter,
+) -> Result<(), OdsError> {
+    let w = zip_out.start_file(
+        &format!("xl/worksheets/sheet{}.xml", idx + 1),
+        FileOptions::default(),
+    )?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("worksheet")?;
+    xml_out.attr("xmlns", NS_MAIN)?;
+    xml_out.attr("xmlns:r", NS_REL)?;
+
+    let (rows, cols) = if sheet.iter().next().is_some() {
+        sheet.used_grid_size()
+    } else {
+        (0, 0)
+    };
+
+    write_cols(book, sheet, cols, styles, &mut xml_out)?;
+
+    let mut merged = Vec::new();
+    xml_out.elem("sheetData")?;
+    for row in 0..rows {
+        let height = sheet
+            .row_style(row)
+            .and_then(|s| book.style(s))
+            .filter(|s| s.family() == StyleFor::TableRow)
+            .and_then(|s| s.row().attr("style:row-height"))
+            .and_then(|v| v.parse::<Length>().ok());
+        let hidden = sheet.row_visible(row) != Visibility::Visible;
+        let row_style = sheet
+            .row_cell_style(row)
+            .map(|s| styles.xf(book, Some(s), &Value::Empty));
+        let mut cells = sheet.row_cells(row).peekable();
+        if cells.peek().is_none() && height.is_none() && !hidden && row_style.is_none() {
+            continue;
+        }
+
+        xml_out.elem("row")?;
+        xml_out.attr("r", &(row + 1).to_string())?;
+        if let Some(s) = row_style {
+            xml_out.attr("s", &s.to_string())?;
+            xml_out.attr("customFormat", "1")?;
+        }
+        if let Some(height) = height {
+            xml_out.attr("ht", &format!("{:.2}", height.to_pt()))?;
+            xml_out.attr("customHeight", "1")?;
+        }
+        if hidden {
+            xml_out.attr("hidden", "1")?;
+        }
+
+        for (col, cell) in cells {
+            if cell.row_span() > 1 || cell.col_span() > 1 {
+                let mut range = cellname(row, col);
+                range.push(':');
+                range.push_str(&cellname(
+                    row + cell.row_span().max(1) - 1,
+                    col + cell.col_span().max(1) - 1,
+                ));
+                merged.push(range);
+            }
+
+            let style = cell
+                .style()
+                .or_else(|| sheet.row_cell_style(row))
+                .or_else(|| sheet.column_cell_style(col))
+                .or_else(|| book.def_style(cell.value().value_type()));
+            let s = styles.xf(book, style, cell.value());
+            write_cell(
+                &cellname(row, col),
+                s,
+                cell.value(),
+                cell.formula(),
+                strings,
+                &mut xml_out,
+            )?;
+        }
+
+        xml_out.end_elem("row")?;
+    }
+    xml_out.end_elem("sheetData")?;
+
+    if !merged.is_empty() {
+        xml_out.elem("mergeCells")?;
+        xml_out.attr("count", &merged.len().to_string())?;
+        for range in merged {
+            xml_out.empty("mergeCell")?;
+            xml_out.attr("ref", &range)?;
+        }
+        xml_out.end_elem("mergeCells")?;
+    }
+
+    xml_out.end_elem("worksheet")?;
+    xml_out.close()?;
+
+    Ok(())
+}
+
+/// Column widths, hidden columns and the default cell style of the
+/// columns.
+fn write_cols(
+    book: &WorkBook,
+    sheet: &Sheet,
+    cols: ucell,
+    styles: &mut XlsxStyles,
+    xml_out: &mut XmlXlsxWriter,
+) -> Result<(), OdsError> {
+    let mut any = false;
+    for col in 0..cols {
+        let width = sheet
+            .column_style(col)
+            .and_then(|s| book.style(s))
+            .filter(|s| s.family() == StyleFor::TableColumn)
+            .and_then(|s| s.col().attr("style:column-width"))
+            .and_then(|v| v.parse::<Length>().ok());
+        let hidden = sheet.column_visible(col) != Visibility::Visible;
+        let style = sheet
+            .column_cell_style(col)
+            .map(|s| styles.xf(book, Some(s), &Value::Empty));
+        if width.is_none() && !hidden && style.is_none() {
+            continue;
+        }
+
+        if !any {
+            xml_out.elem("cols")?;
+            any = true;
+        }
+        xml_out.empty("col")?;
+        xml_out.attr("min", &(col + 1).to_string())?;
+        xml_out.attr("max", &(col + 1).to_string())?;
+        match width {
+            Some(width) => {
+                xml_out.attr("width", &column_width(width).to_string())?;
+                xml_out.attr("customWidth", "1")?;
+            }
+            None => {
+                xml_out.attr("width", "9.140625")?;
+            }
+        }
+        if let Some(style) = style {
+            xml_out.attr("style", &style.to_string())?;
+        }
+        if hidden {
+            xml_out.attr("hidden", "1")?;
+        }
+    }
+    if any {
+        xml_out.end_elem("cols")?;
+    }
+
+    Ok(())
+}
+
+fn write_cell(
+    cellname: &str,
+    s: usize,
+    value: &Value,
+    formula: Option<&String>,
+    strings: &mut SharedStrings,
+    xml_out: &mut XmlXlsxWriter,
+) -> Result<(), OdsError> {
+    if matches!(value, Value::Empty) && formula.is_none() {
+        if s != 0 {
+            xml_out.empty("c")?;
+            xml_out.attr("r", cellname)?;
+            xml_out.attr("s", &s.to_string())?;
+        }
+        return Ok(());
+    }
+
+    let text = match value {
+        Value::Text(v) => Some(v.clone()),
+        Value::TextXml(v) => Some(v.text_content()),
+        _ => None,
+    };
+
+    xml_out.elem("c")?;
+    xml_out.attr("r", cellname)?;
+    if s != 0 {
+        xml_out.attr("s", &s.to_string())?;
+    }
+    match value {
+        Value::Boolean(_) => xml_out.attr("t", "b")?,
+        Value::Number(v) | Value::Percentage(v) | Value::Currency(_, v) if !v.is_finite() => {
+            xml_out.attr("t", "e")?
+        }
+        Value::Text(_) | Value::TextXml(_) if formula.is_some() => xml_out.attr("t", "str")?,
+        Value::Text(_) | Value::TextXml(_) => xml_out.attr("t", "s")?,
+        _ => {}
+    }
+
+    if let Some(formula) = formula {
+        xml_out.elem_text_esc("f", &to_excel_formula(formula))?;
+    }
+
+    match value {
+        Value::Empty => {}
+        Value::Boolean(v) => xml_out.elem_text("v", if *v { "1" } else { "0" })?,
+        Value::Number(v) | Value::Percentage(v) | Value::Currency(_, v) => {
+            if v.is_finite() {
+                xml_out.elem_text("v", &v.to_string())?;
+            } else {
+                xml_out.elem_text("v", "#NUM!")?;
+            }
+        }
+        Value::Text(_) | Value::TextXml(_) => {
+            let text = text.unwrap_or_default();
+            if formula.is_some() {
+                xml_out.elem_text_esc("v", &text)?;
+            } else {
+                xml_out.elem_text("v", &strings.index(text).to_string())?;
+            }
+        }
+        Value::DateTime(v) => xml_out.elem_text("v", &datetime_serial(v).to_string())?,
+        Value::TimeDuration(v) => {
+            let days = v.num_milliseconds() as f64 / 86_400_000f64;
+            xml_out.elem_text("v", &days.to_string())?;
+        }
+    }
+
+    xml_out.end_elem("c")?;
+
+    Ok(())
+}
+
+fn write_shared_strings(strings: &SharedStrings, zip_out: &mut XlsxWriter) -> Result<(), OdsError> {
+    let w = zip_out.start_file("xl/sharedStrings.xml", FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("sst")?;
+    xml_out.attr("xmlns", NS_MAIN)?;
+    xml_out.attr("count", &strings.count.to_string())?;
+    xml_out.attr("uniqueCount", &strings.strings.len().to_string())?;
+    for s in &strings.strings {
+        xml_out.elem("si")?;
+        xml_out.elem("t")?;
+        if s.starts_with(char::is_whitespace) || s.ends_with(char::is_whitespace) {
+            xml_out.attr("xml:space", "preserve")?;
+        }
+        xml_out.text_esc(s)?;
+        xml_out.end_elem("t")?;
+        xml_out.end_elem("si")?;
+    }
+    xml_out.end_elem("sst")?;
+    xml_out.close()?;
+
+    Ok(())
+}
+
+fn write_styles(styles: &XlsxStyles, zip_out: &mut XlsxWriter) -> Result<(), OdsError> {
+    let w = zip_out.start_file("xl/styles.xml", FileOptions::default())?;
+    let mut xml_out = XmlWriter::new(w);
+
+    xml_out.dtd("UTF-8")?;
+    xml_out.elem("styleSheet")?;
+    xml_out.attr("xmlns", NS_MAIN)?;
+
+    if !styles.num_fmts.is_empty() {
+        xml_out.elem("numFmts")?;
+        xml_out.attr("count", &styles.num_fmts.len().to_string())?;
+        for (idx, code) in styles.num_fmts.iter().enumerate() {
+            xml_out.empty("numFmt")?;
+            xml_out.attr("numFmtId", &(FIRST_CUSTOM_NUMFMT + idx as u32).to_string())?;
+            xml_out.attr_esc("formatCode", code)?;
+        }
+        xml_out.end_elem("numFmts")?;
+    }
+
+    xml_out.elem("fonts")?;
+    xml_out.attr("count", &styles.fonts.len().to_string())?;
+    for font in &styles.fonts {
+        xml_out.elem("font")?;
+        if font.bold {
+            xml_out.empty("b")?;
+        }
+        if font.italic {
+            xml_out.empty("i")?;
+        }
+        if font.strike {
+            xml_out.empty("strike")?;
+        }
+        if font.underline {
+            xml_out.empty("u")?;
+        }
+        xml_out.empty("sz")?;
+        xml_out.attr("val", &font.size)?;
+        if let Some(color) = &font.color {
+            xml_out.empty("color")?;
+            xml_out.attr("rgb", color)?;
+        }
+        xml_out.empty("name")?;
+        xml_out.attr_esc("val", &font.name)?;
+        xml_out.end_elem("font")?;
+    }
+    xml_out.end_elem("fonts")?;
+
+    xml_out.elem("fills")?;
+    xml_out.attr("count", &(styles.fills.len() + 2).to_string())?;
+    xml_out.elem("fill")?;
+    xml_out.empty("patternFill")?;
+    xml_out.attr("patternType", "none")?;
+    xml_out.end_elem("fill")?;
+    xml_out.elem("fill")?;
+    xml_out.empty("patternFill")?;
+    xml_out.attr("patternType", "gray125")?;
+    xml_out.end_elem("fill")?;
+    for color in &styles.fills {
+        xml_out.elem("fill")?;
+        xml_out.elem("patternFill")?;
+        xml_out.attr("patternType", "solid")?;
+        xml_out.empty("fgColor")?;
+        xml_out.attr("rgb", color)?;
+        xml_out.empty("bgColor")?;
+        xml_out.attr("indexed", "64")?;
+        xml_out.end_elem("patternFill")?;
+        xml_out.end_elem("fill")?;
+    }
+    xml_out.end_elem("fills")?;
+
+    xml_out.elem("borders")?;
+    xml_out.attr("count", &styles.borders.len().to_string())?;
+    for border in &styles.borders {
+        xml_out.elem("border")?;
+        for (tag, side) in &[
+            ("left", &border.left),
+            ("right", &border.right),
+            ("top", &border.top),
+            ("bottom", &border.bottom),
+        ] {
+            match side {
+                Some((style, color)) => {
+                    xml_out.elem(tag)?;
+                    xml_out.attr("style", style)?;
+                    xml_out.empty("color")?;
+                    xml_out.attr("rgb", color)?;
+                    xml_out.end_elem(tag)?;
+                }
+                None => {
+                    xml_out.empty(tag)?;
+                }
+            }
+        }
+        xml_out.empty("diagonal")?;
+        xml_out.end_elem("border")?;
+    }
+    xml_out.end_elem("borders")?;
+
+    xml_out.elem("cellStyleXfs")?;
+    xml_out.attr("count", "1")?;
+    xml_out.empty("xf")?;
+    xml_out.attr("numFmtId", "0")?;
+    xml_out.attr("fontId", "0")?;
+    xml_out.attr("fillId", "0")?;
+    xml_out.attr("borderId", "0")?;
+    xml_out.end_elem("cellStyleXfs")?;
+
+    xml_out.elem("cellXfs")?;
+    xml_out.attr("count", &styles.xfs.len().to_string())?;
+    for xf in &styles.xfs {
+        let has_align = xf.align != XlsxAlign::default();
+        if has_align {
+            xml_out.elem("xf")?;
+        } else {
+            xml_out.empty("xf")?;
+        }
+        xml_out.attr("numFmtId", &xf.num_fmt.to_string())?;
+        xml_out.attr("fontId", &xf.font.to_string())?;
+        xml_out.attr("fillId", &xf.fill.to_string())?;
+        xml_out.attr("borderId", &xf.border.to_string())?;
+        xml_out.attr("xfId", "0")?;
+        if xf.num_fmt != 0 {
+            xml_out.attr("applyNumberFormat", "1")?;
+        }
+        if xf.font != 0 {
+            xml_out.attr("applyFont", "1")?;
+        }
+        if xf.fill != 0 {
+            xml_out.attr("applyFill", "1")?;
+        }
+        if xf.border != 0 {
+            xml_out.attr("applyBorder", "1")?;
+        }
+        if has_align {
+            xml_out.attr("applyAlignment", "1")?;
+            xml_out.empty("alignment")?;
+            if let Some(horizontal) = xf.align.horizontal {
+                xml_out.attr("horizontal", horizontal)?;
+            }
+            if let Some(vertical) = xf.align.vertical {
+                xml_out.attr("vertical", vertical)?;
+            }
+            if xf.align.wrap {
+                xml_out.attr("wrapText", "1")?;
+            }
+            xml_out.end_elem("xf")?;
+        }
+    }
+    xml_out.end_elem("cellXfs")?;
+
+    xml_out.elem("cellStyles")?;
+    xml_out.attr("count", "1")?;
+    xml_out.empty("cellStyle")?;
+    xml_out.attr("name", "Normal")?;
+    xml_out.attr("xfId", "0")?;
+    xml_out.attr("builtinId", "0")?;
+    xml_out.end_elem("cellStyles")?;
+
+    xml_out.end_elem("styleSheet")?;
+    xml_out.close()?;
+
+    Ok(())
+}
+
+/// A1 name of the cell.
+fn cellname(row: ucell, col: ucell) -> String {
+    let mut buf = String::new();
+    push_colname(&mut buf, col);
+    push_rowname(&mut buf, row);
+    buf
+}
+
+/// Days since 1899-12-30. Excel has a 1900-02-29, so all dates
+/// before March 1900 are one day off.
+fn datetime_serial(v: &NaiveDateTime) -> f64 {
+    let base = NaiveDate::from_ymd(1899, 12, 30).and_hms(0, 0, 0);
+    let serial = (*v - base).num_milliseconds() as f64 / 86_400_000f64;
+    if serial < 61f64 {
+        serial - 1f64
+    } else {
+        serial
+    }
+}
+
+/// Column width as stored by Excel: the pixels divided by the 7 pixel
+/// digit width of the default font, in steps of 1/256.
+fn column_width(length: Length) -> f64 {
+    let pixel = length.to_px();
+    (pixel / 7f64 * 256f64).trunc() / 256f64
+}
+
+/// Shared string table.
+#[derive(Default)]
+struct SharedStrings {
+    strings: Vec<String>,
+    index: HashMap<String, usize>,
+    count: usize,
+}
+
+impl SharedStrings {
+    fn index(&mut self, text: String) -> usize {
+        self.count += 1;
+        if let Some(idx) = self.index.get(&text) {
+            *idx
+        } else {
+            let idx = self.strings.len();
+            self.strings.push(text.clone());
+            self.index.insert(text, idx);
+            idx
+        }
+    }
+}
+
+const FIRST_CUSTOM_NUMFMT: u32 = 164;
+
+#[derive(Debug, Clone, PartialEq)]
+struct XlsxFont {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    size: String,
+    color: Option<String>,
+    name: String,
+}
+
+impl Default for XlsxFont {
+    fn default() -> Self {
+        Self {
+            bold: false,
+            italic: false,
+            underline: false,
+            strike: false,
+            size: "11".to_string(),
+            color: None,
+            name: "Calibri".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct XlsxBorder {
+    left: Option<(&'static str, String)>,
+    right: Option<(&'static str, String)>,
+    top: Option<(&'static str, String)>,
+    bottom: Option<(&'static str, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct XlsxAlign {
+    horizontal: Option<&'static str>,
+    vertical: Option<&'static str>,
+    wrap: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct XlsxXf {
+    num_fmt: u32,
+    font: usize,
+    fill: usize,
+    border: usize,
+    align: XlsxAlign,
+}
+
+/// Collects the parts of the stylesheet. Index 0 is the default
+/// everywhere, fill 0 and 1 are the two fills Excel expects.
+struct XlsxStyles {
+    num_fmts: Vec<String>,
+    fonts: Vec<XlsxFont>,
+    fills: Vec<String>,
+    borders: Vec<XlsxBorder>,
+    xfs: Vec<XlsxXf>,
+    cache: HashMap<(Option<String>, u32), usize>,
+}
+
+impl XlsxStyles {
+    fn new() -> Self {
+        Self {
+            num_fmts: Vec::new(),
+            fonts: vec![XlsxFont::default()],
+            fills: Vec::new(),
+            borders: vec![XlsxBorder::default()],
+            xfs: vec![XlsxXf::default()],
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Index of the cell format for the style and value. Values that
+    /// Excel only knows as numbers get a builtin format if the style
+    /// has none.
+    fn xf(&mut self, book: &WorkBook, style: Option<&String>, value: &Value) -> usize {
+        let default_fmt = match value {
+            Value::DateTime(v) if v.time() == NaiveTime::from_hms(0, 0, 0) => 14,
+            Value::DateTime(_) => 22,
+            Value::TimeDuration(_) => 46,
+            Value::Percentage(_) => 10,
+            _ => 0,
+        };
+
+        let key = (style.cloned(), default_fmt);
+        if let Some(idx) = self.cache.get(&key) {
+            return *idx;
+        }
+
+        let chain = style.map(|s| style_chain(book, s)).unwrap_or_default();
+        let num_fmt = chain
+            .iter()
+            .find_map(|s| s.value_format())
+            .and_then(|f| book.format(f))
+            .and_then(format_code)
+            .map(|code| self.num_fmt(code))
+            .unwrap_or(default_fmt);
+        let xf = XlsxXf {
+            num_fmt,
+            font: index_of(&mut self.fonts, font(book, &chain)),
+            fill: match fill(&chain) {
+                Some(color) => index_of(&mut self.fills, color) + 2,
+                None => 0,
+            },
+            border: index_of(&mut self.borders, border(&chain)),
+            align: align(&chain),
+        };
+        let idx = index_of(&mut self.xfs, xf);
+
+        self.cache.insert(key, idx);
+        idx
+    }
+
+    fn num_fmt(&mut self, code: String) -> u32 {
+        FIRST_CUSTOM_NUMFMT + index_of(&mut self.num_fmts, code) as u32
+    }
+}
+
+/// Index of the value, appended if it's not in the list yet.
+fn index_of<T: PartialEq>(list: &mut Vec<T>, value: T) -> usize {
+    match list.iter().position(|v| *v == value) {
+        Some(idx) => idx,
+        None => {
+            list.push(value);
+            list.len() - 1
+        }
+    }
+}
+
+/// The cell style and all its parents, starting with the style itself.
+fn style_chain<'a>(book: &'a WorkBook, name: &str) -> Vec<&'a Style> {
+    let mut chain: Vec<&Style> = Vec::new();
+    let mut next = Some(name);
+    while let Some(name) = next {
+        match book.style(name) {
+            Some(style)
+                if style.family() == StyleFor::TableCell
+                    && !chain.iter().any(|s| s.name() == style.name()) =>
+            {
+                chain.push(style);
+                next = style.parent().map(|v| v.as_str());
+            }
+            _ => break,
+        }
+    }
+    chain
+}
+
+/// First value found along the chain.
+fn chain_attr<'a, F>(chain: &[&'a Style], f: F) -> Option<&'a String>
+where
+    F: Fn(&'a Style) -> Option<&'a String>,
+{
+    chain.iter().find_map(|s| f(s))
+}
+
+/// Color as ARGB.
+fn argb(color: &str) -> Option<String> {
+    if color.len() == 7 && color.starts_with('#') {
+        Some(format!("FF{}", color[1..].to_uppercase()))
+    } else {
+        None
+    }
+}
+
+fn font(book: &WorkBook, chain: &[&Style]) -> XlsxFont {
+    let mut font = XlsxFont::default();
+
+    if let Some(v) = chain_attr(chain, |s| s.text().attr("fo:font-weight")) {
+        font.bold = v == "bold" || v.parse::<u32>().map(|v| v >= 600).unwrap_or(false);
+    }
+    if let Some(v) = chain_attr(chain, |s| s.text().attr("fo:font-style")) {
+        font.italic = v == "italic" || v == "oblique";
+    }
+    if let Some(v) = chain_attr(chain, |s| s.text().attr("style:text-underline-style")) {
+        font.underline = v != "none";
+    }
+    if let Some(v) = chain_attr(chain, |s| s.text().attr("style:text-line-through-style")) {
+        font.strike = v != "none";
+    }
+    if let Some(v) = chain_attr(chain, |s| s.text().attr("fo:font-size")) {
+        if let Ok(size) = v.parse::<Length>() {
+            font.size = format!("{}", (size.to_pt() * 100f64).round() / 100f64);
+        }
+    }
+    font.color = chain_attr(chain, |s| s.text().attr("fo:color")).and_then(|v| argb(v));
+
+    let family = chain.iter().find_map(|s| {
+        if let Some(v) = s.text().attr("style:font-name") {
+            Some(
+                book.font(v)
+                    .and_then(|f| f.attr("svg:font-family"))
+                    .unwrap_or(v)
+                    .clone(),
+            )
+        } else {
+            s.text().attr("fo:font-family").cloned()
+        }
+    });
+    if let Some(family) = family {
+        font.name = family.trim_matches(|c| c == '\'' || c == '"').to_string();
+    }
+
+    font
+}
+
+fn fill(chain: &[&Style]) -> Option<String> {
+    chain_attr(chain, |s| s.cell().attr("fo:background-color")).and_then(|v| argb(v))
+}
+
+fn border(chain: &[&Style]) -> XlsxBorder {
+    let side = |name: &str| {
+        let attr = format!("fo:border-{}", name);
+        chain
+            .iter()
+            .find_map(|s| s.cell().attr(&attr).or_else(|| s.cell().attr("fo:border")))
+            .and_then(|v| border_side(v))
+    };
+    XlsxBorder {
+        left: side("left"),
+        right: side("right"),
+        top: side("top"),
+        bottom: side("bottom"),
+    }
+}
+
+/// Converts "0.74pt solid #000000".
+fn border_side(border: &str) -> Option<(&'static str, String)> {
+    let mut width = 0.75;
+    let mut style = "solid";
+    let mut color = "FF000000".to_string();
+    for token in border.split_whitespace() {
+        if let Some(v) = argb(token) {
+            color = v;
+        } else if let Ok(v) = token.parse::<Length>() {
+            width = v.to_pt();
+        } else {
+            style = match token {
+                "thin" => {
+                    width = 0.75;
+                    "solid"
+                }
+                "medium" => {
+                    width = 1.5;
+                    "solid"
+                }
+                "thick" => {
+                    width = 2.5;
+                    "solid"
+                }
+                "none" => "none",
+                "hidden" => "none",
+                "dotted" => "dotted",
+                "dashed" => "dashed",
+                "double" => "double",
+                _ => "solid",
+            };
+        }
+    }
+
+    let style = match style {
+        "none" => return None,
+        "dotted" => "dotted",
+        "dashed" if width > 1f64 => "mediumDashed",
+        "dashed" => "dashed",
+        "double" => "double",
+        _ if width > 2f64 => "thick",
+        _ if width > 1f64 => "medium",
+        _ => "thin",
+    };
+    Some((style, color))
+}
+
+fn align(chain: &[&Style]) -> XlsxAlign {
+    let horizontal = match chain_attr(chain, |s| s.paragraph().attr("fo:text-align")) {
+        Some(v) => match v.as_str() {
+            "start" | "left" => Some("left"),
+            "end" | "right" => Some("right"),
+            "center" => Some("center"),
+            "justify" => Some("justify"),
+            _ => None,
+        },
+        None => None,
+    };
+    let vertical = match chain_attr(chain, |s| s.cell().attr("style:vertical-align")) {
+        Some(v) => match v.as_str() {
+            "top" => Some("top"),
+            "middle" => Some("center"),
+            "bottom" => Some("bottom"),
+            _ => None,
+        },
+        None => None,
+    };
+    let wrap = chain_attr(chain, |s| s.cell().attr("fo:wrap-option"))
+        .map(|v| v == "wrap")
+        .unwrap_or(false);
+
+    XlsxAlign {
+        horizontal,
+        vertical,
+        wrap,
+    }
+}
+
+/// Excel format code for the value format. None if the format has
+/// nothing that Excel wouldn't show anyway.
+fn format_code(format: &ValueFormat) -> Option<String> {
+    let mut code = String::new();
+    let mut elapsed = format.value_type() == ValueType::TimeDuration;
+
+    for part in format.parts() {
+        let long = part.attr_def("number:style", "") == "long";
+        match part.part_type() {
+            FormatPartType::Number => {
+                let int_digits = part
+                    .attr_def("number:min-integer-digits", "1")
+                    .parse::<usize>()
+                    .unwrap_or(1);
+                let decimals = part
+                    .attr_def("number:decimal-places", "0")
+                    .parse::<usize>()
+                    .unwrap_or(0);
+                let min_decimals = part
+                    .attr("loext:min-decimal-places")
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(decimals)
+                    .min(decimals);
+                let grouping = part.attr_def("number:grouping", "") == "true";
+
+                let mut int = "0".repeat(int_digits);
+                if grouping {
+                    while int.len() < 4 {
+                        int.insert(0, '#');
+                    }
+                    int.insert(int.len() - 3, ',');
+                } else if int.is_empty() {
+                    int.push('#');
+                }
+                code.push_str(&int);
+                if decimals > 0 {
+                    code.push('.');
+                    code.push_str(&"0".repeat(min_decimals));
+                    code.push_str(&"#".repeat(decimals - min_decimals));
+                }
+            }
+            FormatPartType::Fraction => {
+                if part.attr_def("number:min-integer-digits", "0") != "0" {
+                    code.push_str("# ");
+                }
+                let num_digits = part
+                    .attr_def("number:min-numerator-digits", "1")
+                    .parse::<usize>()
+                    .unwrap_or(1)
+                    .max(1);
+                code.push_str(&"?".repeat(num_digits));
+                code.push('/');
+                match part.attr("number:denominator-value") {
+                    Some(v) => code.push_str(v),
+                    None => {
+                        let den_digits = part
+                            .attr_def("number:min-denominator-digits", "1")
+                            .parse::<usize>()
+                            .unwrap_or(1)
+                            .max(1);
+                        code.push_str(&"?".repeat(den_digits));
+                    }
+                }
+            }
+            FormatPartType::Scientific => {
+                let decimals = part
+                    .attr_def("number:decimal-places", "0")
+                    .parse::<usize>()
+                    .unwrap_or(0);
+                code.push('0');
+                if decimals > 0 {
+                    code.push('.');
+                    code.push_str(&"0".repeat(decimals));
+                }
+                code.push_str("E+00");
+            }
+            FormatPartType::CurrencySymbol | FormatPartType::Text => {
+                if let Some(content) = part.content() {
+                    push_literal(&mut code, content);
+                }
+            }
+            FormatPartType::Day => code.push_str(if long { "dd" } else { "d" }),
+            FormatPartType::Month => {
+                let textual = part.attr_def("number:textual", "") == "true";
+                code.push_str(match (textual, long) {
+                    (true, true) => "mmmm",
+                    (true, false) => "mmm",
+                    (false, true) => "mm",
+                    (false, false) => "m",
+                });
+            }
+            FormatPartType::Year => code.push_str(if long { "yyyy" } else { "yy" }),
+            FormatPartType::DayOfWeek => code.push_str(if long { "dddd" } else { "ddd" }),
+            FormatPartType::Hours => {
+                let hours = if long { "hh" } else { "h" };
+                if elapsed {
+                    code.push('[');
+                    code.push_str(hours);
+                    code.push(']');
+                    elapsed = false;
+                } else {
+                    code.push_str(hours);
+                }
+            }
+            FormatPartType::Minutes => code.push_str(if long { "mm" } else { "m" }),
+            FormatPartType::Seconds => {
+                code.push_str(if long { "ss" } else { "s" });
+                let decimals = part
+                    .attr_def("number:decimal-places", "0")
+                    .parse::<usize>()
+                    .unwrap_or(0);
+                if decimals > 0 {
+                    code.push('.');
+                    code.push_str(&"0".repeat(decimals));
+                }
+            }
+            FormatPartType::AmPm => code.push_str("AM/PM"),
+            FormatPartType::TextContent => code.push('@'),
+            FormatPartType::Boolean
+            | FormatPartType::Era
+            | FormatPartType::WeekOfYear
+            | FormatPartType::Quarter
+            | FormatPartType::EmbeddedText => {}
+        }
+    }
+
+    if code.is_empty() {
+        None
+    } else {
+        Some(code)
+    }
+}
+
+/// Literal text in a format code. '%' stays unquoted because it scales
+/// the value.
+fn push_literal(code: &mut String, text: &str) {
+    let mut quoted = false;
+    for c in text.chars() {
+        let plain = " %-+/:()$".contains(c);
+        if plain && quoted {
+            code.push('"');
+            quoted = false;
+        }
+        if c == '"' {
+            if quoted {
+                code.push('"');
+                quoted = false;
+            }
+            code.push_str("\\\"");
+        } else if plain {
+            code.push(c);
+        } else {
+            if !quoted {
+                code.push('"');
+                quoted = true;
+            }
+            code.push(c);
+        }
+    }
+    if quoted {
+        code.push('"');
+    }
+}
@@ -0,0 +1,311 @@
+//!
+//! Iterators over the cells of a sheet.
+//!
+//! All of them only visit cells that exist, and use range queries
+//! on the underlying map instead of looking up every position. Each
+//! one has a mutable variant. The map hands out mutable references
+//! only through a single iterator, so those can't skip ahead and
+//! visit the cells in between.
+//!
+//! ```
+//! use spreadsheet_ods::{CellRange, Sheet};
+//!
+//! let mut sheet = Sheet::new();
+//! sheet.set_value(0, 0, "a");
+//! sheet.set_value(0, 3, "b");
+//! sheet.set_value(5, 1, 17);
+//!
+//! for (row, cells) in sheet.rows() {
+//!     for (col, cell) in cells {
+//!         println!("{} {} {:?}", row, col, cell.value());
+//!     }
+//! }
+//!
+//! for (cellref, cell) in sheet.range(&CellRange::local(0, 0, 5, 1)) {
+//!     println!("{} {:?}", cellref, cell.value());
+//! }
+//! ```
+//!
+
+use std::collections::btree_map;
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+use std::vec;
+
+use crate::{ucell, CellRange, CellRef, SCell};
+
+/// Iterates all cells in row-major order.
+pub struct CellIter<'a> {
+    pub(crate) it: btree_map::Iter<'a, (ucell, ucell), SCell>,
+}
+
+impl<'a> Iterator for CellIter<'a> {
+    type Item = ((ucell, ucell), &'a SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.it.next().map(|(k, v)| (*k, v))
+    }
+}
+
+/// Iterates all cells in row-major order.
+pub struct CellIterMut<'a> {
+    pub(crate) it: btree_map::IterMut<'a, (ucell, ucell), SCell>,
+}
+
+impl<'a> Iterator for CellIterMut<'a> {
+    type Item = ((ucell, ucell), &'a mut SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.it.next().map(|(k, v)| (*k, v))
+    }
+}
+
+/// Iterates the cells of one row.
+pub struct RowCells<'a> {
+    it: btree_map::Range<'a, (ucell, ucell), SCell>,
+}
+
+impl<'a> RowCells<'a> {
+    pub(crate) fn new(data: &'a BTreeMap<(ucell, ucell), SCell>, row: ucell) -> Self {
+        Self {
+            it: data.range((row, 0)..=(row, ucell::MAX)),
+        }
+    }
+}
+
+impl<'a> Iterator for RowCells<'a> {
+    type Item = (ucell, &'a SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.it.next().map(|((_, col), v)| (*col, v))
+    }
+}
+
+/// Iterates the cells of one row.
+pub struct RowCellsMut<'a> {
+    it: btree_map::RangeMut<'a, (ucell, ucell), SCell>,
+}
+
+impl<'a> RowCellsMut<'a> {
+    pub(crate) fn new(data: &'a mut BTreeMap<(ucell, ucell), SCell>, row: ucell) -> Self {
+        Self {
+            it: data.range_mut((row, 0)..=(row, ucell::MAX)),
+        }
+    }
+}
+
+impl<'a> Iterator for RowCellsMut<'a> {
+    type Item = (ucell, &'a mut SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.it.next().map(|((_, col), v)| (*col, v))
+    }
+}
+
+/// Iterates all non-empty rows.
+pub struct RowIter<'a> {
+    data: &'a BTreeMap<(ucell, ucell), SCell>,
+    row: Option<ucell>,
+}
+
+impl<'a> RowIter<'a> {
+    pub(crate) fn new(data: &'a BTreeMap<(ucell, ucell), SCell>) -> Self {
+        Self { data, row: Some(0) }
+    }
+}
+
+impl<'a> Iterator for RowIter<'a> {
+    type Item = (ucell, RowCells<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.row?;
+        let ((row, _), _) = self.data.range((row, 0)..).next()?;
+        self.row = row.checked_add(1);
+        Some((*row, RowCells::new(self.data, *row)))
+    }
+}
+
+/// Iterates the cells of one column.
+pub struct ColIter<'a> {
+    data: &'a BTreeMap<(ucell, ucell), SCell>,
+    col: ucell,
+    row: Option<ucell>,
+}
+
+impl<'a> ColIter<'a> {
+    pub(crate) fn new(data: &'a BTreeMap<(ucell, ucell), SCell>, col: ucell) -> Self {
+        Self {
+            data,
+            col,
+            row: Some(0),
+        }
+    }
+}
+
+impl<'a> Iterator for ColIter<'a> {
+    type Item = (ucell, &'a SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let row = self.row?;
+            let ((cur_row, cur_col), cell) = self.data.range((row, self.col)..).next()?;
+            if *cur_row == row && *cur_col == self.col {
+                self.row = row.checked_add(1);
+                return Some((row, cell));
+            } else if *cur_row == row {
+                // Nothing in this row, continue with the next one.
+                self.row = row.checked_add(1);
+            } else {
+                // Continue in the row where the next cell is.
+                self.row = Some(*cur_row);
+            }
+        }
+    }
+}
+
+/// Iterates the cells within a range.
+pub struct RangeIter<'a> {
+    data: &'a BTreeMap<(ucell, ucell), SCell>,
+    col: ucell,
+    to_col: ucell,
+    to_row: ucell,
+    row: ucell,
+    it: Option<btree_map::Range<'a, (ucell, ucell), SCell>>,
+}
+
+impl<'a> RangeIter<'a> {
+    pub(crate) fn new(data: &'a BTreeMap<(ucell, ucell), SCell>, range: &CellRange) -> Self {
+        let it = if range.row() <= range.to_row() && range.col() <= range.to_col() {
+            Some(data.range((range.row(), range.col())..=(range.row(), range.to_col())))
+        } else {
+            None
+        };
+        Self {
+            data,
+            col: range.col(),
+            to_col: range.to_col(),
+            to_row: range.to_row(),
+            row: range.row(),
+            it,
+        }
+    }
+}
+
+impl<'a> Iterator for RangeIter<'a> {
+    type Item = (CellRef, &'a SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(((row, col), cell)) = self.it.as_mut()?.next() {
+                return Some((CellRef::local(*row, *col), cell));
+            }
+
+            // Skip to the next row that contains any cell.
+            self.it = None;
+            let next_row = self.row.checked_add(1)?;
+            if next_row > self.to_row {
+                return None;
+            }
+            let ((row, _), _) = self.data.range((next_row, 0)..).next()?;
+            if *row > self.to_row {
+                return None;
+            }
+            self.row = *row;
+            self.it = Some(
+                self.data
+                    .range((self.row, self.col)..=(self.row, self.to_col)),
+            );
+        }
+    }
+}
+
+/// Iterates the cells within a range.
+///
+/// Unlike RangeIter this can't skip to the next row, it visits all
+/// cells of the rows in the range and filters the columns.
+pub struct RangeIterMut<'a> {
+    it: Option<btree_map::RangeMut<'a, (ucell, ucell), SCell>>,
+    col: ucell,
+    to_col: ucell,
+}
+
+impl<'a> RangeIterMut<'a> {
+    pub(crate) fn new(data: &'a mut BTreeMap<(ucell, ucell), SCell>, range: &CellRange) -> Self {
+        let it = if range.row() <= range.to_row() && range.col() <= range.to_col() {
+            Some(data.range_mut((range.row(), range.col())..=(range.to_row(), range.to_col())))
+        } else {
+            None
+        };
+        Self {
+            it,
+            col: range.col(),
+            to_col: range.to_col(),
+        }
+    }
+}
+
+impl<'a> Iterator for RangeIterMut<'a> {
+    type Item = (CellRef, &'a mut SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (col, to_col) = (self.col, self.to_col);
+        self.it
+            .as_mut()?
+            .find(|((_, c), _)| *c >= col && *c <= to_col)
+            .map(|((row, col), cell)| (CellRef::local(*row, *col), cell))
+    }
+}
+
+/// Iterates all non-empty rows. Each row is an iterator over its cells.
+pub struct RowIterMut<'a> {
+    it: Peekable<btree_map::IterMut<'a, (ucell, ucell), SCell>>,
+}
+
+impl<'a> RowIterMut<'a> {
+    pub(crate) fn new(data: &'a mut BTreeMap<(ucell, ucell), SCell>) -> Self {
+        Self {
+            it: data.iter_mut().peekable(),
+        }
+    }
+}
+
+impl<'a> Iterator for RowIterMut<'a> {
+    type Item = (ucell, vec::IntoIter<(ucell, &'a mut SCell)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ((row, col), cell) = self.it.next()?;
+        let mut cells = vec![(*col, cell)];
+        while let Some(((_, col), cell)) = self.it.next_if(|((r, _), _)| r == row) {
+            cells.push((*col, cell));
+        }
+        Some((*row, cells.into_iter()))
+    }
+}
+
+/// Iterates the cells of one column.
+///
+/// Unlike ColIter this visits all cells right of the column and filters.
+pub struct ColIterMut<'a> {
+    it: btree_map::RangeMut<'a, (ucell, ucell), SCell>,
+    col: ucell,
+}
+
+impl<'a> ColIterMut<'a> {
+    pub(crate) fn new(data: &'a mut BTreeMap<(ucell, ucell), SCell>, col: ucell) -> Self {
+        Self {
+            it: data.range_mut((0, col)..),
+            col,
+        }
+    }
+}
+
+impl<'a> Iterator for ColIterMut<'a> {
+    type Item = (ucell, &'a mut SCell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let col = self.col;
+        self.it
+            .find(|((_, c), _)| *c == col)
+            .map(|((row, _), cell)| (*row, cell))
+    }
+}
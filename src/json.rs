@@ -0,0 +1,463 @@
+//!
+//! JSON representation of the workbook data.
+//!
+//! Needs the feature "json". Only the cell data is written: the
+//! sheets, and for each sheet the rows with their used cells. Each cell
+//! has a type tag and the value, the formula, the style name, the
+//! direct formatting and the spans. Styles and value formats are
+//! referenced by name only. The direct formatting is written as the
+//! attributes of the groups "cell", "paragraph" and "text", tab stops
+//! are not included.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "sheets": [{
+//!     "name": "Sheet1",
+//!     "rows": [{
+//!       "row": 0,
+//!       "cells": [
+//!         { "col": 0, "type": "text", "value": "Name", "style": "head", "col_span": 2 },
+//!         { "col": 2, "type": "date", "value": "2021-03-01T00:00:00" },
+//!         { "col": 3, "type": "currency", "value": 12.5, "currency": "EUR" },
+//!         { "col": 4, "type": "number", "value": 3, "formula": "of:=[.A2]+1" },
+//!         { "col": 5, "type": "text", "value": "sum", "format": { "text": { "fo:font-weight": "bold" } } }
+//!       ]
+//!     }]
+//!   }]
+//! }
+//! ```
+//!
+//! The type tags are "empty", "boolean", "number", "percentage",
+//! "currency", "text", "date" and "duration". Dates are written as
+//! "%Y-%m-%dT%H:%M:%S%.f", durations as milliseconds. Formatted text is
+//! written with its text content as "text".
+//!
+//! ```
+//! use spreadsheet_ods::json::{apply_json, to_json};
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//!
+//! let mut wb = WorkBook::new();
+//! let mut sheet = Sheet::new_with_name("Data");
+//! sheet.set_value(0, 0, "rent");
+//! wb.push_sheet(sheet);
+//!
+//! let json = to_json(&wb);
+//!
+//! // ... edited by the frontend ...
+//! let edit = r#"{ "sheets": [{ "name": "Data", "rows": [
+//!     { "row": 0, "cells": [{ "col": 1, "type": "number", "value": 800 }] }
+//! ]}]}"#;
+//! apply_json(&mut wb, edit.as_bytes()).unwrap();
+//! assert_eq!(wb.sheet(0).value(0, 1).as_f64_or(0.0), 800.0);
+//! ```
+//!
+
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+use chrono::NaiveDateTime;
+use serde_json::{json, Map};
+use time::Duration;
+
+use crate::attrmap::{AttrMap, AttrMapIter, AttrMapType};
+use crate::style::CellFormat;
+use crate::{ucell, OdsError, SCell, Sheet, Value, WorkBook};
+
+/// Version of the JSON format.
+const VERSION: u64 = 1;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+type JsonValue = serde_json::Value;
+
+impl From<serde_json::Error> for OdsError {
+    fn from(err: serde_json::Error) -> OdsError {
+        if err.is_io() {
+            OdsError::Io(err.into())
+        } else {
+            OdsError::Ods(format!("Json {}", err))
+        }
+    }
+}
+
+/// The workbook data as JSON.
+pub fn to_json(book: &WorkBook) -> String {
+    book_to_json(book).to_string()
+}
+
+/// Writes the workbook data as JSON.
+pub fn write_json<W: Write>(book: &WorkBook, write: W) -> Result<(), OdsError> {
+    serde_json::to_writer(write, &book_to_json(book))?;
+    Ok(())
+}
+
+/// Reads the JSON into a new workbook. The styles named by the cells
+/// must be added before writing.
+pub fn read_json<R: Read>(read: R) -> Result<WorkBook, OdsError> {
+    let mut book = WorkBook::new();
+    apply_json(&mut book, read)?;
+    Ok(book)
+}
+
+/// Applies the JSON to the workbook. The sheets are found by name,
+/// unknown sheets are appended. Each cell in the JSON changes the cell
+/// in the sheet, cells that are not listed are left as they are.
+///
+/// Members of a cell that are missing keep the current value, formula,
+/// style, format or span. A null removes the formula, style or format
+/// and resets the span. A cell that ends up empty without style,
+/// formula, format or spans is removed.
+///
+/// Formatted text stays unchanged if the text content is the same.
+///
+/// The whole JSON is checked first, if it contains an error the
+/// workbook is not changed.
+pub fn apply_json<R: Read>(book: &mut WorkBook, read: R) -> Result<(), OdsError> {
+    let json: JsonValue = serde_json::from_reader(read)?;
+
+    if let Some(version) = json.get("version") {
+        if version.as_u64() != Some(VERSION) {
+            return Err(OdsError::Ods(format!(
+                "Json version {} is not supported",
+                version
+            )));
+        }
+    }
+
+    let mut sheets = Vec::new();
+    for sheet_json in array(&json, "sheets")? {
+        let name = sheet_json
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| OdsError::Ods("Json sheet without name".to_string()))?;
+
+        let mut cells = Vec::new();
+        for row_json in array(sheet_json, "rows")? {
+            let row = index(row_json, "row")?;
+            for cell_json in array(row_json, "cells")? {
+                let col = index(cell_json, "col")?;
+                cells.push(read_cell(row, col, cell_json)?);
+            }
+        }
+        sheets.push((name, cells));
+    }
+
+    for (name, cells) in sheets {
+        let idx = match (0..book.num_sheets()).find(|idx| book.sheet(*idx).name() == name) {
+            Some(idx) => idx,
+            None => {
+                book.push_sheet(Sheet::new_with_name(name));
+                book.num_sheets() - 1
+            }
+        };
+        let sheet = book.sheet_mut(idx);
+        for cell in cells {
+            apply_cell(sheet, cell);
+        }
+    }
+
+    Ok(())
+}
+
+fn book_to_json(book: &WorkBook) -> JsonValue {
+    let sheets: Vec<JsonValue> = (0..book.num_sheets())
+        .map(|idx| sheet_to_json(book.sheet(idx)))
+        .collect();
+
+    json!({
+        "version": VERSION,
+        "sheets": sheets,
+    })
+}
+
+fn sheet_to_json(sheet: &Sheet) -> JsonValue {
+    let mut rows = Vec::new();
+    let mut current: Option<(ucell, Vec<JsonValue>)> = None;
+
+    for ((row, col), cell) in sheet.iter() {
+        match &mut current {
+            Some((current_row, cells)) if *current_row == row => {
+                cells.push(cell_to_json(col, cell));
+            }
+            _ => {
+                if let Some((current_row, cells)) = current.take() {
+                    rows.push(json!({ "row": current_row, "cells": cells }));
+                }
+                current = Some((row, vec![cell_to_json(col, cell)]));
+            }
+        }
+    }
+    if let Some((current_row, cells)) = current {
+        rows.push(json!({ "row": current_row, "cells": cells }));
+    }
+
+    json!({
+        "name": sheet.name(),
+        "rows": rows,
+    })
+}
+
+fn cell_to_json(col: ucell, cell: &SCell) -> JsonValue {
+    let mut map = Map::new();
+    map.insert("col".to_string(), col.into());
+
+    let (value_type, value) = match cell.value() {
+        Value::Empty => ("empty", None),
+        Value::Boolean(v) => ("boolean", Some(JsonValue::from(*v))),
+        Value::Number(v) => ("number", Some(JsonValue::from(*v))),
+        Value::Percentage(v) => ("percentage", Some(JsonValue::from(*v))),
+        Value::Currency(c, v) => {
+            map.insert("currency".to_string(), c.as_str().into());
+            ("currency", Some(JsonValue::from(*v)))
+        }
+        Value::Text(v) => ("text", Some(JsonValue::from(v.as_str()))),
+        Value::TextXml(v) => ("text", Some(JsonValue::from(v.text_content()))),
+        Value::DateTime(v) => (
+            "date",
+            Some(JsonValue::from(v.format(DATETIME_FORMAT).to_string())),
+        ),
+        Value::TimeDuration(v) => ("duration", Some(JsonValue::from(v.num_milliseconds()))),
+    };
+    map.insert("type".to_string(), value_type.into());
+    if let Some(value) = value {
+        map.insert("value".to_string(), value);
+    }
+
+    if let Some(formula) = cell.formula() {
+        map.insert("formula".to_string(), formula.as_str().into());
+    }
+    if let Some(style) = cell.style() {
+        map.insert("style".to_string(), style.as_str().into());
+    }
+    if let Some(format) = cell.cell_format() {
+        map.insert("format".to_string(), format_to_json(format));
+    }
+    if cell.row_span() > 1 {
+        map.insert("row_span".to_string(), cell.row_span().into());
+    }
+    if cell.col_span() > 1 {
+        map.insert("col_span".to_string(), cell.col_span().into());
+    }
+
+    JsonValue::Object(map)
+}
+
+// Changes for one cell. None keeps the current setting.
+struct CellEdit {
+    row: ucell,
+    col: ucell,
+    value: Option<Value>,
+    formula: Option<Option<String>>,
+    style: Option<Option<String>>,
+    format: Option<Option<CellFormat>>,
+    row_span: Option<ucell>,
+    col_span: Option<ucell>,
+}
+
+fn read_cell(row: ucell, col: ucell, json: &JsonValue) -> Result<CellEdit, OdsError> {
+    let value = match json.get("type") {
+        None => None,
+        Some(value_type) => Some(read_value(
+            row,
+            col,
+            value_type.as_str().unwrap_or_default(),
+            json,
+        )?),
+    };
+
+    let invalid =
+        |name: &str| OdsError::Ods(format!("Json cell {},{} has an invalid {}", row, col, name));
+    let string = |name: &str| -> Result<Option<Option<String>>, OdsError> {
+        match json.get(name) {
+            None => Ok(None),
+            Some(JsonValue::Null) => Ok(Some(None)),
+            Some(JsonValue::String(v)) => Ok(Some(Some(v.clone()))),
+            Some(_) => Err(invalid(name)),
+        }
+    };
+    let span = |name: &str| -> Result<Option<ucell>, OdsError> {
+        match json.get(name) {
+            None => Ok(None),
+            Some(JsonValue::Null) => Ok(Some(1)),
+            Some(_) => Ok(Some(index(json, name)?.max(1))),
+        }
+    };
+
+    Ok(CellEdit {
+        row,
+        col,
+        value,
+        formula: string("formula")?,
+        style: string("style")?,
+        format: match json.get("format") {
+            None => None,
+            Some(JsonValue::Null) => Some(None),
+            Some(v) => Some(Some(format_from_json(v, row, col)?)),
+        },
+        row_span: span("row_span")?,
+        col_span: span("col_span")?,
+    })
+}
+
+fn read_value(
+    row: ucell,
+    col: ucell,
+    value_type: &str,
+    json: &JsonValue,
+) -> Result<Value, OdsError> {
+    let value = match value_type {
+        "empty" => Value::Empty,
+        "boolean" => Value::Boolean(
+            json.get("value")
+                .and_then(|v| v.as_bool())
+                .ok_or_else(|| invalid_value(row, col, value_type))?,
+        ),
+        "number" => Value::Number(number(json, row, col, value_type)?),
+        "percentage" => Value::Percentage(number(json, row, col, value_type)?),
+        "currency" => Value::Currency(
+            json.get("currency")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            number(json, row, col, value_type)?,
+        ),
+        "text" => Value::Text(
+            json.get("value")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| invalid_value(row, col, value_type))?
+                .to_string(),
+        ),
+        "date" => {
+            let text = json
+                .get("value")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| invalid_value(row, col, value_type))?;
+            Value::DateTime(NaiveDateTime::parse_from_str(text, DATETIME_FORMAT)?)
+        }
+        "duration" => Value::TimeDuration(Duration::milliseconds(
+            json.get("value")
+                .and_then(|v| v.as_i64())
+                .ok_or_else(|| invalid_value(row, col, value_type))?,
+        )),
+        _ => {
+            return Err(OdsError::Ods(format!(
+                "Json cell {},{} has an unknown type {}",
+                row, col, value_type
+            )))
+        }
+    };
+    Ok(value)
+}
+
+fn apply_cell(sheet: &mut Sheet, edit: CellEdit) {
+    let (row, col) = (edit.row, edit.col);
+    let mut cell = sheet.remove_cell(row, col).unwrap_or_default();
+
+    if let Some(value) = edit.value {
+        cell.value = match (value, &cell.value) {
+            (Value::Text(text), Value::TextXml(v)) if v.text_content() == text => {
+                Value::TextXml(v.clone())
+            }
+            (value, _) => value,
+        };
+    }
+    if let Some(formula) = edit.formula {
+        cell.formula = formula;
+    }
+    if let Some(style) = edit.style {
+        cell.style = style;
+    }
+    if let Some(format) = edit.format {
+        cell.format = format.map(Box::new);
+    }
+    if let Some(row_span) = edit.row_span {
+        cell.span.0 = row_span;
+    }
+    if let Some(col_span) = edit.col_span {
+        cell.span.1 = col_span;
+    }
+
+    let unused = matches!(cell.value, Value::Empty)
+        && cell.formula.is_none()
+        && cell.style.is_none()
+        && cell.format.is_none()
+        && cell.span == (1, 1);
+    if !unused {
+        sheet.add_cell(row, col, cell);
+    }
+}
+
+/// Direct formatting as the attributes of each group.
+fn format_to_json(format: &CellFormat) -> JsonValue {
+    fn attr_to_json(attr: &AttrMapType) -> JsonValue {
+        AttrMapIter::from(attr)
+            .map(|(k, v)| (k.to_string(), JsonValue::from(v.as_str())))
+            .collect::<Map<_, _>>()
+            .into()
+    }
+
+    let mut map = Map::new();
+    if format.cell().attr_map().is_some() {
+        map.insert("cell".to_string(), attr_to_json(format.cell().attr_map()));
+    }
+    if format.paragraph().attr_map().is_some() {
+        map.insert(
+            "paragraph".to_string(),
+            attr_to_json(format.paragraph().attr_map()),
+        );
+    }
+    if format.text().attr_map().is_some() {
+        map.insert("text".to_string(), attr_to_json(format.text().attr_map()));
+    }
+    JsonValue::Object(map)
+}
+
+fn format_from_json(json: &JsonValue, row: ucell, col: ucell) -> Result<CellFormat, OdsError> {
+    let invalid_format =
+        || OdsError::Ods(format!("Json cell {},{} has an invalid format", row, col));
+
+    let mut format = CellFormat::new();
+    for (group, attr) in json.as_object().ok_or_else(invalid_format)? {
+        for (name, value) in attr.as_object().ok_or_else(invalid_format)? {
+            let value = value.as_str().ok_or_else(invalid_format)?.to_string();
+            match group.as_str() {
+                "cell" => format.cell_mut().set_attr(name, value),
+                "paragraph" => format.paragraph_mut().set_attr(name, value),
+                "text" => format.text_mut().set_attr(name, value),
+                _ => return Err(invalid_format()),
+            }
+        }
+    }
+    Ok(format)
+}
+
+/// Array member, missing arrays are empty.
+fn array<'a>(json: &'a JsonValue, name: &str) -> Result<&'a [JsonValue], OdsError> {
+    match json.get(name) {
+        Some(JsonValue::Array(v)) => Ok(v.as_slice()),
+        Some(_) => Err(OdsError::Ods(format!("Json {} is not an array", name))),
+        None => Ok(&[]),
+    }
+}
+
+/// Row, column or span.
+fn index(json: &JsonValue, name: &str) -> Result<ucell, OdsError> {
+    json.get(name)
+        .and_then(|v| v.as_u64())
+        .and_then(|v| ucell::try_from(v).ok())
+        .ok_or_else(|| OdsError::Ods(format!("Json {} is not a valid index", name)))
+}
+
+fn number(json: &JsonValue, row: ucell, col: ucell, value_type: &str) -> Result<f64, OdsError> {
+    json.get("value")
+        .and_then(|v| v.as_f64())
+        .ok_or_else(|| invalid_value(row, col, value_type))
+}
+
+fn invalid_value(row: ucell, col: ucell, value_type: &str) -> OdsError {
+    OdsError::Ods(format!(
+        "Json cell {},{} has no valid value for type {}",
+        row, col, value_type
+    ))
+}
@@ -71,7 +71,21 @@
 //!     * Only as strings, but support functions for cell/range references.
 //!   * Row/Column spans
 //!   * Header rows/columns, print ranges
+//!   * Iterators over cells, rows, columns and ranges.
 //!   * Formatted text as xml text.
+//!   * Sheet protection.
+//!   * Tracked changes can be read, and accepted or rejected as a whole.
+//!   * Charts (bar, line, pie, scatter, area) as embedded chart documents.
+//!   * Drawing shapes (rectangle, ellipse, line, connector, text box).
+//!   * Scenarios, and switching the active scenario.
+//!   * Rows to records and back with serde (feature "serde").
+//!   * CSV import and export (feature "csv").
+//!   * JSON export and import of the workbook data (feature "json").
+//!   * Compare two workbooks cell by cell.
+//!   * Copy sheets between workbooks with their styles.
+//!   * HTML export with the cell styles as CSS.
+//!   * Import and export of xlsx with values, formulas, merged cells and
+//!     cell styles.
 //!
 //! * Formulas
 //!   * Only as strings.
@@ -80,13 +94,22 @@
 //! * Styles
 //!   * Default styles per data type.
 //!   * Preserves all style attributes.
-//!   * Table, row, column, cell, paragraph and text styles.
+//!   * Table, row, column, cell, paragraph, text and graphic styles.
 //!   * Stylemaps (basic support)
 //!   * Support for *setting* most style attributes.
+//!   * Removal of unused and duplicate styles.
+//!   * Effective cell style after inheritance and defaults.
+//!   * Common styles and default-styles per family.
+//!   * Direct formatting of cells, written as shared automatic styles.
+//!   * Typed getters for style attributes.
+//!   * Font declarations for the fonts used by the styles.
+//!   * Parsing, unit conversion and arithmetic for Length and Angle.
+//!   * Estimated optimal column widths and row heights.
 //!
 //! * Value formatting
 //!   * The whole set is available.
 //!   * Utility functions for common formats.
+//!   * Parsing of format codes like `#,##0.00;[RED]-#,##0.00`.
 //!
 //! * Fonts
 //!   * Preserves all font attributes.
@@ -110,7 +133,7 @@
 //! some modification. But they are written back to the ods.
 //!
 //! Anyway those are:
-//! * tracked-changes
+//! * tracked-changes (the parsed form is read only)
 //! * variable-decls
 //! * sequence-decls
 //! * user-field-decls
@@ -126,9 +149,8 @@
 //! * table:desc
 //! * table-source
 //! * dde-source
-//! * scenario
 //! * forms
-//! * shapes
+//! * shapes (except charts and the basic drawing shapes)
 //! * calcext:conditional-formats
 //!
 //! When storing a previously read ODS file, all the contained files
@@ -140,11 +162,12 @@
 
 #![doc(html_root_url = "https://docs.rs/spreadsheet-ods/0.4.0")]
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::path::PathBuf;
 
 use chrono::{NaiveDate, NaiveDateTime};
+use color::Rgb;
 #[cfg(feature = "use_decimal")]
 use rust_decimal::prelude::*;
 #[cfg(feature = "use_decimal")]
@@ -153,26 +176,59 @@ use time::Duration;
 
 pub use error::OdsError;
 pub use format::ValueFormat;
-pub use io::{read_ods, write_ods};
+pub use io::{
+    read_ods, read_xlsx, write_ods, write_ods_with_options, write_xlsx, MissingFonts,
+    OdsWriteOptions,
+};
 pub use refs::{CellRange, CellRef, ColRange, RowRange};
 pub use style::{Angle, Length, Style};
 
-use crate::attrmap::{AttrTableCol, AttrTableRow};
-use crate::style::{FontFaceDecl, PageLayout};
+use crate::attrmap::{AttrStyleWritingMode, AttrTable, AttrTableCol, AttrTableRow, WritingMode};
+use crate::chart::Chart;
+use crate::draw::Shape;
+use crate::iter::{
+    CellIter, CellIterMut, ColIter, ColIterMut, RangeIter, RangeIterMut, RowCells, RowCellsMut,
+    RowIter, RowIterMut,
+};
+use crate::protection::{ProtectionOptions, SheetProtection};
+use crate::scenario::Scenario;
+use crate::style::{
+    CellFormat, FontFaceDecl, PageLayout, ResolvedStyle, StyleFor, StyleOrigin, StyleUse,
+};
 use crate::text::TextTag;
+use crate::trackedchanges::{ChangeState, ChangeType, TrackedChange};
 use crate::xmltree::XmlTag;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 mod attrmap;
+mod autofit;
+pub mod chart;
+#[cfg(feature = "csv")]
+pub mod csv;
 pub mod defaultstyles;
+pub mod diff;
+pub mod draw;
 pub mod error;
+mod fonts;
 pub mod format;
+mod formatcode;
 pub mod formula;
+pub mod html;
 mod io;
+pub mod iter;
+#[cfg(feature = "json")]
+pub mod json;
+mod merge;
+pub mod protection;
 pub mod refs;
+pub mod scenario;
+#[cfg(feature = "serde")]
+pub mod serde_rows;
 pub mod style;
+mod stylepool;
 pub mod text;
+pub mod trackedchanges;
 pub mod xmltree;
 
 pub(crate) mod sealed {
@@ -212,6 +268,9 @@ pub struct WorkBook {
     /// files except content.xml
     file: Option<PathBuf>,
 
+    /// Parsed table:tracked-changes. The original xml is kept in extra.
+    tracked_changes: Vec<TrackedChange>,
+
     /// other stuff ...
     extra: Vec<XmlTag>,
 }
@@ -236,6 +295,9 @@ impl fmt::Debug for WorkBook {
         for s in self.page_layouts.values() {
             writeln!(f, "{:?}", s)?;
         }
+        for s in &self.tracked_changes {
+            writeln!(f, "{:?}", s)?;
+        }
         for xtr in &self.extra {
             writeln!(f, "extras {:?}", xtr)?;
         }
@@ -254,6 +316,7 @@ impl WorkBook {
             def_styles: Default::default(),
             page_layouts: Default::default(),
             file: None,
+            tracked_changes: vec![],
             extra: vec![],
         }
     }
@@ -296,6 +359,82 @@ impl WorkBook {
         self.sheets.remove(n)
     }
 
+    /// Copies a sheet of another workbook and appends it. All styles,
+    /// value formats, fonts and page layouts the sheet uses are copied
+    /// too. If this workbook already has a different definition with
+    /// the same name, the copy is renamed with a suffix "_2", "_3", ...
+    /// and all references are changed. Equal definitions are shared.
+    ///
+    /// Returns the index of the new sheet.
+    ///
+    /// Panics
+    ///
+    /// Panics if the sheet n does not exist in the other workbook.
+    pub fn import_sheet(&mut self, other: &WorkBook, n: usize) -> usize {
+        merge::import_sheet(self, other, n)
+    }
+
+    /// Removes the automatic styles and value formats that are not
+    /// referenced by a cell, row, column, table, shape, formatted text,
+    /// style map, parent style or default style. Font declarations and
+    /// page layouts that are not referenced by a remaining style are
+    /// removed too, except for the page layout of the "Default" master
+    /// page. Named and default styles are always kept.
+    ///
+    /// Before that automatic styles and value formats that are equal
+    /// apart from the name are merged, the same for font declarations.
+    /// The references are changed to the first name in sort order.
+    pub fn remove_unused_styles(&mut self) {
+        merge::remove_unused_styles(self)
+    }
+
+    /// Formatting of a cell as a spreadsheet application shows it. The
+    /// cell style is the style of the cell, or the default style for the
+    /// value type, or the cell style of the row, or the cell style of
+    /// the column. This style is merged with its parents and the
+    /// table-cell default-style. The direct formatting of the cell comes
+    /// on top.
+    ///
+    /// Panics
+    ///
+    /// Panics if the sheet n does not exist.
+    pub fn effective_cell_style(&self, n: usize, row: ucell, col: ucell) -> ResolvedStyle<'_> {
+        self.sheet_cell_style(&self.sheets[n], row, col)
+    }
+
+    /// Same as effective_cell_style() for a sheet that need not be part
+    /// of the workbook.
+    pub(crate) fn sheet_cell_style(
+        &self,
+        sheet: &Sheet,
+        row: ucell,
+        col: ucell,
+    ) -> ResolvedStyle<'_> {
+        let style = match sheet.data.get(&(row, col)) {
+            Some(cell) => cell
+                .style
+                .as_ref()
+                .or_else(|| self.def_style(cell.value.value_type())),
+            None => None,
+        }
+        .or_else(|| sheet.row_cell_style(row))
+        .or_else(|| sheet.column_cell_style(col));
+
+        let mut resolved = ResolvedStyle::resolve(self, style.map(|v| v.as_str()));
+        if let Some(format) = sheet.cell_format(row, col) {
+            resolved.apply_format(format);
+        }
+        resolved
+    }
+
+    /// Replaces the direct formatting of all cells with automatic
+    /// styles. Cells with the same style and the same format share one
+    /// new style "ce1", "ce2", ... This is done when writing, the
+    /// workbook itself is not changed then.
+    pub fn apply_cell_formats(&mut self) {
+        stylepool::apply_cell_formats(self)
+    }
+
     /// Adds a default-style for all new values.
     /// This information is only used when writing the data to the ODS file.
     pub fn add_def_style(&mut self, value_type: ValueType, style: &str) {
@@ -340,6 +479,37 @@ impl WorkBook {
         self.fonts.get_mut(name)
     }
 
+    /// Returns the name of the font declaration for the font family,
+    /// to be used with set_font_name(). If there is none a new one is
+    /// added, with svg:font-family quoted as needed.
+    ///
+    /// ```
+    /// use spreadsheet_ods::style::{AttrFontDecl, AttrText};
+    /// use spreadsheet_ods::{Style, WorkBook};
+    ///
+    /// let mut book = WorkBook::new();
+    /// let font = book.font_for("Liberation Serif");
+    /// assert_eq!(book.font(&font).unwrap().font_family().unwrap(), "'Liberation Serif'");
+    ///
+    /// let mut style = Style::new_cell_style("serif", "");
+    /// style.text_mut().set_font_name(font);
+    /// ```
+    pub fn font_for(&mut self, family: &str) -> String {
+        fonts::font_for(self, family)
+    }
+
+    /// Font names used by some style, value format or cell format that
+    /// have no font declaration.
+    pub fn missing_fonts(&self) -> Vec<String> {
+        fonts::missing_fonts(self)
+    }
+
+    /// Adds a font declaration for every missing font. The font name is
+    /// used as the font family.
+    pub fn create_missing_fonts(&mut self) {
+        fonts::create_missing_fonts(self)
+    }
+
     /// Adds a style.
     pub fn add_style(&mut self, style: Style) {
         self.styles.insert(style.name().to_string(), style);
@@ -350,6 +520,48 @@ impl WorkBook {
         self.styles.remove(name)
     }
 
+    /// Adds a common style. It is written to styles.xml and shows up in
+    /// the list of styles of the application. The display name defaults
+    /// to the name.
+    ///
+    /// The value format of a common style should be a common one too,
+    /// see ValueFormat::set_styleuse(). Otherwise it is copied to the
+    /// automatic styles of styles.xml.
+    pub fn add_common_style(&mut self, mut style: Style) {
+        style.set_origin(StyleOrigin::Styles);
+        style.set_styleuse(StyleUse::Named);
+        if style.display_name().is_none() {
+            let name = style.name().clone();
+            style.set_display_name(name);
+        }
+        self.add_style(style);
+    }
+
+    /// Sets the default-style for the family of the style. It defines
+    /// the attributes that no other style sets. Replaces the existing
+    /// default-style of this family. The name of the style is not used.
+    pub fn set_default_style(&mut self, mut style: Style) {
+        style.set_origin(StyleOrigin::Styles);
+        style.set_styleuse(StyleUse::Default);
+        style.set_name(style::default_style_name(style.family()));
+        self.add_style(style);
+    }
+
+    /// Returns the default-style for the family.
+    pub fn default_style(&self, family: StyleFor) -> Option<&Style> {
+        self.styles.get(&style::default_style_name(family))
+    }
+
+    /// Returns the default-style for the family.
+    pub fn default_style_mut(&mut self, family: StyleFor) -> Option<&mut Style> {
+        self.styles.get_mut(&style::default_style_name(family))
+    }
+
+    /// Removes the default-style for the family.
+    pub fn remove_default_style(&mut self, family: StyleFor) -> Option<Style> {
+        self.styles.remove(&style::default_style_name(family))
+    }
+
     /// Returns the style.
     pub fn style(&self, name: &str) -> Option<&Style> {
         self.styles.get(name)
@@ -399,6 +611,223 @@ impl WorkBook {
     pub fn pagelayout_mut(&mut self, name: &str) -> Option<&mut PageLayout> {
         self.page_layouts.get_mut(name)
     }
+
+    /// Index of the sheet a scenario belongs to. This is the nearest
+    /// sheet before the scenario that is no scenario itself.
+    pub fn scenario_target(&self, idx: usize) -> Option<usize> {
+        if !self.sheets.get(idx)?.is_scenario() {
+            return None;
+        }
+        (0..idx).rev().find(|i| !self.sheets[*i].is_scenario())
+    }
+
+    /// Indices of all the scenarios of a sheet.
+    pub fn scenarios(&self, idx: usize) -> Vec<usize> {
+        self.sheets
+            .iter()
+            .enumerate()
+            .skip(idx + 1)
+            .take_while(|(_, sheet)| sheet.is_scenario())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Adds a scenario for the sheet at idx. The scenario sheet is
+    /// inserted after the sheet and its existing scenarios and gets
+    /// the current content of the scenario ranges.
+    ///
+    /// Returns the index of the new sheet.
+    pub fn add_scenario<S: Into<String>>(
+        &mut self,
+        idx: usize,
+        name: S,
+        scenario: Scenario,
+    ) -> Result<usize, OdsError> {
+        match self.sheets.get(idx) {
+            None => return Err(OdsError::Ods(format!("Sheet {} does not exist", idx))),
+            Some(sheet) if sheet.is_scenario() => {
+                return Err(OdsError::Ods(format!("Sheet {} is a scenario", idx)))
+            }
+            _ => {}
+        }
+
+        let mut sheet = Sheet::new_with_name(name);
+        sheet.copy_ranges_from(&self.sheets[idx], scenario.ranges(), true, true);
+        sheet.set_scenario(scenario);
+
+        let pos = idx + 1 + self.scenarios(idx).len();
+        self.sheets.insert(pos, sheet);
+
+        Ok(pos)
+    }
+
+    /// Activates the scenario at idx.
+    ///
+    /// If the currently active scenario has copy_back set, the content
+    /// of its ranges is copied from the target sheet back to the scenario
+    /// first. Then the content of the new scenario is copied to the
+    /// target sheet.
+    pub fn activate_scenario(&mut self, idx: usize) -> Result<(), OdsError> {
+        let target = if let Some(target) = self.scenario_target(idx) {
+            target
+        } else {
+            return Err(OdsError::Ods(format!("Sheet {} is not a scenario", idx)));
+        };
+
+        for other in self.scenarios(target) {
+            if other == idx {
+                continue;
+            }
+            let (active, copy_back) = match self.sheets[other].scenario() {
+                Some(s) => (s.active(), s.copy_back()),
+                None => (false, false),
+            };
+            if active && copy_back {
+                // scenarios always come after the target.
+                let (head, tail) = self.sheets.split_at_mut(other);
+                let sheet = &mut tail[0];
+                if let Some(scenario) = sheet.scenario.clone() {
+                    sheet.copy_ranges_from(
+                        &head[target],
+                        scenario.ranges(),
+                        scenario.copy_styles(),
+                        scenario.copy_formulas(),
+                    );
+                }
+            }
+            if let Some(scenario) = self.sheets[other].scenario_mut() {
+                scenario.set_active(false);
+            }
+        }
+
+        let (head, tail) = self.sheets.split_at_mut(idx);
+        let sheet = &tail[0];
+        if let Some(scenario) = sheet.scenario() {
+            head[target].copy_ranges_from(
+                sheet,
+                scenario.ranges(),
+                scenario.copy_styles(),
+                scenario.copy_formulas(),
+            );
+        }
+        if let Some(scenario) = self.sheets[idx].scenario_mut() {
+            scenario.set_active(true);
+        }
+
+        Ok(())
+    }
+
+    /// Tracked changes as read from the file.
+    pub fn tracked_changes(&self) -> &Vec<TrackedChange> {
+        &self.tracked_changes
+    }
+
+    /// Accepts all changes. The current content stays as it is and
+    /// the change tracking is removed from the workbook.
+    pub fn accept_all_changes(&mut self) {
+        self.tracked_changes.clear();
+        self.extra.retain(|v| v.name() != "table:tracked-changes");
+    }
+
+    /// Rejects all pending changes and removes the change tracking
+    /// from the workbook.
+    ///
+    /// Changed cells get their previous value, inserted rows, columns
+    /// and tables are removed, deleted rows and columns are inserted
+    /// again with the recorded cell contents and moved ranges are moved
+    /// back.
+    ///
+    /// A deleted table is inserted again at its old position with the
+    /// recorded cell contents. Its original name can't be restored:
+    /// table:deletion only records the position, so the table is named
+    /// "SheetN" like a new table in LibreOffice, with N the position
+    /// counted from 1, or the next number that isn't used by another
+    /// sheet. Styles and other settings of the deleted table are lost too.
+    pub fn reject_all_changes(&mut self) {
+        let changes = std::mem::take(&mut self.tracked_changes);
+        self.extra.retain(|v| v.name() != "table:tracked-changes");
+
+        for change in changes.iter().rev() {
+            if change.state() != ChangeState::Pending {
+                continue;
+            }
+
+            let range = change.range();
+            match change.change_type() {
+                ChangeType::CellContent => {
+                    if let Some(sheet) = self.sheets.get_mut(change.table()) {
+                        let cell = sheet.cell_mut(range.row(), range.col());
+                        cell.value = change.previous().cloned().unwrap_or_default();
+                        cell.formula = change.previous_formula().cloned();
+                    }
+                }
+                ChangeType::InsertRows => {
+                    if let Some(sheet) = self.sheets.get_mut(change.table()) {
+                        sheet.remove_rows_at(
+                            range.row(),
+                            range.to_row().saturating_sub(range.row()) + 1,
+                        );
+                    }
+                }
+                ChangeType::InsertColumns => {
+                    if let Some(sheet) = self.sheets.get_mut(change.table()) {
+                        sheet.remove_cols_at(
+                            range.col(),
+                            range.to_col().saturating_sub(range.col()) + 1,
+                        );
+                    }
+                }
+                ChangeType::InsertTable => {
+                    if change.table() < self.sheets.len() {
+                        self.sheets.remove(change.table());
+                    }
+                }
+                ChangeType::DeleteRows => {
+                    if let Some(sheet) = self.sheets.get_mut(change.table()) {
+                        sheet.insert_rows_at(
+                            range.row(),
+                            range.to_row().saturating_sub(range.row()) + 1,
+                        );
+                        for (cellref, value) in change.deleted() {
+                            sheet.set_value(cellref.row(), cellref.col(), value.clone());
+                        }
+                    }
+                }
+                ChangeType::DeleteColumns => {
+                    if let Some(sheet) = self.sheets.get_mut(change.table()) {
+                        sheet.insert_cols_at(
+                            range.col(),
+                            range.to_col().saturating_sub(range.col()) + 1,
+                        );
+                        for (cellref, value) in change.deleted() {
+                            sheet.set_value(cellref.row(), cellref.col(), value.clone());
+                        }
+                    }
+                }
+                ChangeType::DeleteTable => {
+                    let idx = change.table().min(self.sheets.len());
+                    let mut name = format!("Sheet{}", idx + 1);
+                    let mut n = idx + 1;
+                    while self.sheets.iter().any(|v| *v.name() == name) {
+                        n += 1;
+                        name = format!("Sheet{}", n);
+                    }
+                    let mut sheet = Sheet::new_with_name(name);
+                    for (cellref, value) in change.deleted() {
+                        sheet.set_value(cellref.row(), cellref.col(), value.clone());
+                    }
+                    self.sheets.insert(idx, sheet);
+                }
+                ChangeType::Movement => {
+                    if let (Some(sheet), Some(source)) =
+                        (self.sheets.get_mut(change.table()), change.source())
+                    {
+                        sheet.move_range(range, source.row(), source.col());
+                    }
+                }
+            }
+        }
+    }
 }
 
 /// Visibility of a column or row.
@@ -447,6 +876,9 @@ struct RowColHeader {
     style: Option<String>,
     cell_style: Option<String>,
     visible: Visibility,
+    /// Column width or row height, written as an automatic style.
+    size: Option<Length>,
+    use_optimal_size: bool,
 }
 
 impl RowColHeader {
@@ -455,9 +887,29 @@ impl RowColHeader {
             style: None,
             cell_style: None,
             visible: Default::default(),
+            size: None,
+            use_optimal_size: false,
         }
     }
 
+    pub fn set_size(&mut self, size: Length, use_optimal_size: bool) {
+        self.size = Some(size);
+        self.use_optimal_size = use_optimal_size;
+    }
+
+    pub fn clear_size(&mut self) {
+        self.size = None;
+        self.use_optimal_size = false;
+    }
+
+    pub fn size(&self) -> Option<Length> {
+        self.size
+    }
+
+    pub fn use_optimal_size(&self) -> bool {
+        self.use_optimal_size
+    }
+
     pub fn set_style<S: Into<String>>(&mut self, style: S) {
         self.style = Some(style.into());
     }
@@ -513,6 +965,13 @@ pub struct Sheet {
     header_cols: Option<ColRange>,
     print_ranges: Option<Vec<CellRange>>,
 
+    protection: Option<SheetProtection>,
+
+    scenario: Option<Scenario>,
+
+    charts: Vec<Chart>,
+    shapes: Vec<Shape>,
+
     extra: Vec<XmlTag>,
 }
 
@@ -534,6 +993,18 @@ impl fmt::Debug for Sheet {
         if let Some(header_cols) = &self.header_cols {
             writeln!(f, "header cols {:?}", header_cols)?;
         }
+        if let Some(protection) = &self.protection {
+            writeln!(f, "protection {:?}", protection)?;
+        }
+        if let Some(scenario) = &self.scenario {
+            writeln!(f, "scenario {:?}", scenario)?;
+        }
+        for chart in &self.charts {
+            writeln!(f, "chart {:?}", chart)?;
+        }
+        for shape in &self.shapes {
+            writeln!(f, "shape {:?}", shape)?;
+        }
         for xtr in &self.extra {
             writeln!(f, "extras {:?}", xtr)?;
         }
@@ -552,6 +1023,10 @@ impl Sheet {
             header_rows: None,
             header_cols: None,
             print_ranges: None,
+            protection: None,
+            scenario: None,
+            charts: vec![],
+            shapes: vec![],
             extra: vec![],
             row_header: Default::default(),
             display: true,
@@ -569,6 +1044,10 @@ impl Sheet {
             header_rows: None,
             header_cols: None,
             print_ranges: None,
+            protection: None,
+            scenario: None,
+            charts: vec![],
+            shapes: vec![],
             extra: vec![],
             row_header: Default::default(),
             display: true,
@@ -663,20 +1142,53 @@ impl Sheet {
         }
     }
 
-    /// Creates a col style and sets the col width.
-    pub fn set_col_width(&mut self, workbook: &mut WorkBook, col: ucell, width: Length) {
-        let style_name = format!("co{}", col);
+    /// Sets the column width. When writing, columns with the same width
+    /// and column style share an automatic style "co1", "co2", ...
+    pub fn set_col_width(&mut self, col: ucell, width: Length) {
+        self.set_col_size(col, width, false);
+    }
 
-        let mut col_style = if let Some(style) = workbook.remove_style(&style_name) {
-            style
-        } else {
-            Style::new_col_style(&style_name, "")
-        };
-        col_style.col_mut().set_col_width(width);
-        col_style.col_mut().set_use_optimal_col_width(false);
-        workbook.add_style(col_style);
+    /// Removes the column width.
+    pub fn clear_col_width(&mut self, col: ucell) {
+        if let Some(col_header) = self.col_header.get_mut(&col) {
+            col_header.clear_size();
+        }
+    }
+
+    /// The column width as set with set_col_width(), or else the width
+    /// from the column style.
+    pub fn col_width(&self, workbook: &WorkBook, col: ucell) -> Option<Length> {
+        let col_header = self.col_header.get(&col)?;
+        col_header.size().or_else(|| {
+            workbook
+                .style(col_header.style()?)?
+                .col()
+                .col_width()
+                .ok()
+                .flatten()
+        })
+    }
 
-        self.set_column_style(col, &style_name);
+    /// Sets the column widths to an estimate of the optimal width for the
+    /// content, as with set_col_width(). Columns without content are not
+    /// changed.
+    ///
+    /// The text is formatted with the value format and measured with
+    /// average glyph widths for the font family and size of the cell
+    /// style. Wrapped text only needs the width of its longest word,
+    /// rotated text is measured along the rotation. Cells that span
+    /// several columns are not included.
+    pub fn autofit_columns<I: IntoIterator<Item = ucell>>(&mut self, workbook: &WorkBook, cols: I) {
+        for (col, width) in autofit::column_widths(workbook, self, cols) {
+            self.set_col_size(col, width, true);
+        }
+    }
+
+    fn set_col_size(&mut self, col: ucell, width: Length, optimal: bool) {
+        self.col_header
+            .entry(col)
+            .or_default()
+            .set_size(width, optimal);
     }
 
     /// Row style.
@@ -746,20 +1258,117 @@ impl Sheet {
         }
     }
 
-    /// Creates a row-style and sets the row height.
-    pub fn set_row_height(&mut self, workbook: &mut WorkBook, row: ucell, height: Length) {
-        let style_name = format!("ro{}", row);
+    /// Sets the row height. When writing, rows with the same height and
+    /// row style share an automatic style "ro1", "ro2", ...
+    pub fn set_row_height(&mut self, row: ucell, height: Length) {
+        self.set_row_size(row, height, false);
+    }
+
+    /// Removes the row height.
+    pub fn clear_row_height(&mut self, row: ucell) {
+        if let Some(row_header) = self.row_header.get_mut(&row) {
+            row_header.clear_size();
+        }
+    }
 
-        let mut row_style = if let Some(style) = workbook.remove_style(&style_name) {
-            style
+    /// The row height as set with set_row_height(), or else the height
+    /// from the row style.
+    pub fn row_height(&self, workbook: &WorkBook, row: ucell) -> Option<Length> {
+        let row_header = self.row_header.get(&row)?;
+        row_header.size().or_else(|| {
+            workbook
+                .style(row_header.style()?)?
+                .row()
+                .row_height()
+                .ok()
+                .flatten()
+        })
+    }
+
+    /// Sets the row heights to an estimate of the optimal height for the
+    /// content, as with set_row_height(). Rows without content are not
+    /// changed.
+    ///
+    /// Wrapped text is broken into lines at the width of its column,
+    /// so autofit_columns() should come first. Cells that span several
+    /// rows are not included.
+    pub fn autofit_rows<I: IntoIterator<Item = ucell>>(&mut self, workbook: &WorkBook, rows: I) {
+        for (row, height) in autofit::row_heights(workbook, self, rows) {
+            self.set_row_size(row, height, true);
+        }
+    }
+
+    fn set_row_size(&mut self, row: ucell, height: Length, optimal: bool) {
+        self.row_header
+            .entry(row)
+            .or_default()
+            .set_size(height, optimal);
+    }
+
+    /// Sets the color of the sheet tab.
+    ///
+    /// The sheet gets its own table style for this, a style shared with
+    /// other sheets is copied first.
+    pub fn set_tab_color(&mut self, workbook: &mut WorkBook, color: Rgb<u8>) {
+        self.own_table_style(workbook)
+            .table_mut()
+            .set_tab_color(color);
+    }
+
+    /// Right-to-left layout of the sheet, column A is on the right side.
+    ///
+    /// The sheet gets its own table style for this, a style shared with
+    /// other sheets is copied first.
+    pub fn set_right_to_left(&mut self, workbook: &mut WorkBook, rtl: bool) {
+        let writing_mode = if rtl {
+            WritingMode::RlTb
         } else {
-            Style::new_row_style(&style_name, "")
+            WritingMode::LrTb
+        };
+        self.own_table_style(workbook)
+            .table_mut()
+            .set_writing_mode(writing_mode);
+    }
+
+    // The table style of this sheet. Styles named "ta_N" are created here
+    // and can be changed, unless a sheet of the workbook uses it too. That
+    // happens with a cloned sheet. Anything else is copied to a new style.
+    fn own_table_style<'a>(&mut self, workbook: &'a mut WorkBook) -> &'a mut Style {
+        let style_name = match &self.style {
+            Some(name)
+                if name.starts_with("ta_")
+                    && workbook.style(name).is_some()
+                    && !workbook
+                        .sheets
+                        .iter()
+                        .any(|v| v.style.as_ref() == Some(name)) =>
+            {
+                name.clone()
+            }
+            _ => {
+                let mut n = 1;
+                while workbook.style(&format!("ta_{}", n)).is_some() {
+                    n += 1;
+                }
+                let style_name = format!("ta_{}", n);
+
+                let mut table_style =
+                    if let Some(style) = self.style.as_ref().and_then(|v| workbook.style(v)) {
+                        style.clone()
+                    } else {
+                        Style::new_table_style(&style_name, "")
+                    };
+                table_style.set_name(&style_name);
+                table_style.set_origin(StyleOrigin::Content);
+                table_style.set_styleuse(StyleUse::Automatic);
+                workbook.add_style(table_style);
+
+                self.style = Some(style_name.clone());
+                style_name
+            }
         };
-        row_style.row_mut().set_row_height(height);
-        row_style.row_mut().set_use_optimal_row_height(false);
-        workbook.add_style(row_style);
 
-        self.set_row_style(row, &style_name);
+        workbook.style_mut(&style_name).unwrap()
     }
 
     /// Returns a tuple of (max(row)+1, max(col)+1)
@@ -793,6 +1402,91 @@ impl Sheet {
         self.print
     }
 
+    /// Iterates all cells in row-major order.
+    pub fn iter(&self) -> CellIter<'_> {
+        CellIter {
+            it: self.data.iter(),
+        }
+    }
+
+    /// Iterates all cells in row-major order.
+    pub fn iter_mut(&mut self) -> CellIterMut<'_> {
+        CellIterMut {
+            it: self.data.iter_mut(),
+        }
+    }
+
+    /// Iterates all rows that contain any cells. Each row is an iterator
+    /// over its cells.
+    pub fn rows(&self) -> RowIter<'_> {
+        RowIter::new(&self.data)
+    }
+
+    /// Iterates all rows that contain any cells. Each row is an iterator
+    /// over its cells.
+    pub fn rows_mut(&mut self) -> RowIterMut<'_> {
+        RowIterMut::new(&mut self.data)
+    }
+
+    /// Iterates the cells of one row.
+    pub fn row_cells(&self, row: ucell) -> RowCells<'_> {
+        RowCells::new(&self.data, row)
+    }
+
+    /// Iterates the cells of one row.
+    pub fn row_cells_mut(&mut self, row: ucell) -> RowCellsMut<'_> {
+        RowCellsMut::new(&mut self.data, row)
+    }
+
+    /// Iterates the cells of one column.
+    pub fn col_cells(&self, col: ucell) -> ColIter<'_> {
+        ColIter::new(&self.data, col)
+    }
+
+    /// Iterates the cells of one column.
+    pub fn col_cells_mut(&mut self, col: ucell) -> ColIterMut<'_> {
+        ColIterMut::new(&mut self.data, col)
+    }
+
+    /// Writes the records starting with the first row, one record per
+    /// row and one field per column. With header the field names are
+    /// written to the first row and the records follow after that.
+    ///
+    /// Only structs can be written. See serde_rows for dates, durations
+    /// and decimals.
+    #[cfg(feature = "serde")]
+    pub fn serialize_rows<T: serde::Serialize>(
+        &mut self,
+        records: &[T],
+        header: bool,
+    ) -> Result<(), OdsError> {
+        serde_rows::serialize_rows(self, records, header)
+    }
+
+    /// Reads one record for each row below the header row. The fields
+    /// are matched with the names in the header row, columns without a
+    /// name are ignored. Errors contain the reference of the cell that
+    /// failed.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_rows<T: serde::de::DeserializeOwned>(
+        &self,
+        header_row: ucell,
+    ) -> Result<Vec<T>, OdsError> {
+        serde_rows::deserialize_rows(self, header_row)
+    }
+
+    /// Iterates the cells within the range. The table name of the
+    /// range is ignored.
+    pub fn range(&self, range: &CellRange) -> RangeIter<'_> {
+        RangeIter::new(&self.data, range)
+    }
+
+    /// Iterates the cells within the range. The table name of the
+    /// range is ignored.
+    pub fn range_mut(&mut self, range: &CellRange) -> RangeIterMut<'_> {
+        RangeIterMut::new(&mut self.data, range)
+    }
+
     /// Returns true if there is no SCell at the given position.
     pub fn is_empty(&self, row: ucell, col: ucell) -> bool {
         self.data.get(&(row, col)).is_none()
@@ -877,6 +1571,25 @@ impl Sheet {
         }
     }
 
+    /// Direct formatting of the cell on top of the cell style. Creates
+    /// the cell if necessary. When writing, equal formats become shared
+    /// automatic styles.
+    pub fn cell_format_mut(&mut self, row: ucell, col: ucell) -> &mut CellFormat {
+        self.cell_mut(row, col).cell_format_mut()
+    }
+
+    /// Returns the direct formatting of the cell.
+    pub fn cell_format(&self, row: ucell, col: ucell) -> Option<&CellFormat> {
+        self.data.get(&(row, col)).and_then(|v| v.cell_format())
+    }
+
+    /// Removes the direct formatting of the cell.
+    pub fn clear_cell_format(&mut self, row: ucell, col: ucell) {
+        if let Some(cell) = self.data.get_mut(&(row, col)) {
+            cell.clear_cell_format();
+        }
+    }
+
     /// Sets the rowspan of the cell. Must be greater than 0.
     pub fn set_row_span(&mut self, row: ucell, col: ucell, span: ucell) {
         let mut cell = self.data.entry((row, col)).or_insert_with(SCell::new);
@@ -952,20 +1665,300 @@ impl Sheet {
     pub fn print_ranges(&self) -> Option<&Vec<CellRange>> {
         self.print_ranges.as_ref()
     }
+
+    // Inserts empty rows and moves everything below down.
+    pub(crate) fn insert_rows_at(&mut self, row: ucell, count: ucell) {
+        self.data = std::mem::take(&mut self.data)
+            .into_iter()
+            .map(|((r, c), v)| {
+                if r >= row {
+                    ((r.saturating_add(count), c), v)
+                } else {
+                    ((r, c), v)
+                }
+            })
+            .collect();
+        self.row_header = std::mem::take(&mut self.row_header)
+            .into_iter()
+            .map(|(r, v)| {
+                if r >= row {
+                    (r.saturating_add(count), v)
+                } else {
+                    (r, v)
+                }
+            })
+            .collect();
+    }
+
+    // Removes rows and moves everything below up.
+    pub(crate) fn remove_rows_at(&mut self, row: ucell, count: ucell) {
+        let end = row.saturating_add(count);
+        self.data = std::mem::take(&mut self.data)
+            .into_iter()
+            .filter_map(|((r, c), v)| {
+                if r < row {
+                    Some(((r, c), v))
+                } else if r < end {
+                    None
+                } else {
+                    Some(((r - count, c), v))
+                }
+            })
+            .collect();
+        self.row_header = std::mem::take(&mut self.row_header)
+            .into_iter()
+            .filter_map(|(r, v)| {
+                if r < row {
+                    Some((r, v))
+                } else if r < end {
+                    None
+                } else {
+                    Some((r - count, v))
+                }
+            })
+            .collect();
+    }
+
+    // Inserts empty columns and moves everything right of it.
+    pub(crate) fn insert_cols_at(&mut self, col: ucell, count: ucell) {
+        self.data = std::mem::take(&mut self.data)
+            .into_iter()
+            .map(|((r, c), v)| {
+                if c >= col {
+                    ((r, c.saturating_add(count)), v)
+                } else {
+                    ((r, c), v)
+                }
+            })
+            .collect();
+        self.col_header = std::mem::take(&mut self.col_header)
+            .into_iter()
+            .map(|(c, v)| {
+                if c >= col {
+                    (c.saturating_add(count), v)
+                } else {
+                    (c, v)
+                }
+            })
+            .collect();
+    }
+
+    // Removes columns and moves everything right of it.
+    pub(crate) fn remove_cols_at(&mut self, col: ucell, count: ucell) {
+        let end = col.saturating_add(count);
+        self.data = std::mem::take(&mut self.data)
+            .into_iter()
+            .filter_map(|((r, c), v)| {
+                if c < col {
+                    Some(((r, c), v))
+                } else if c < end {
+                    None
+                } else {
+                    Some(((r, c - count), v))
+                }
+            })
+            .collect();
+        self.col_header = std::mem::take(&mut self.col_header)
+            .into_iter()
+            .filter_map(|(c, v)| {
+                if c < col {
+                    Some((c, v))
+                } else if c < end {
+                    None
+                } else {
+                    Some((c - count, v))
+                }
+            })
+            .collect();
+    }
+
+    // Moves the cells of the range to a new origin. Overwrites any cells
+    // at the destination.
+    pub(crate) fn move_range(&mut self, range: &CellRange, row: ucell, col: ucell) {
+        let keys: Vec<(ucell, ucell)> = self
+            .data
+            .keys()
+            .filter(|(r, c)| range.contains(*r, *c))
+            .cloned()
+            .collect();
+        let mut moved = Vec::new();
+        for (r, c) in keys {
+            if let Some(cell) = self.data.remove(&(r, c)) {
+                moved.push(((r - range.row() + row, c - range.col() + col), cell));
+            }
+        }
+        self.data.extend(moved);
+    }
+
+    /// Adds a chart to the sheet.
+    pub fn add_chart(&mut self, chart: Chart) {
+        self.charts.push(chart);
+    }
+
+    /// Removes a chart.
+    pub fn remove_chart(&mut self, idx: usize) -> Chart {
+        self.charts.remove(idx)
+    }
+
+    /// Charts of this sheet.
+    pub fn charts(&self) -> &Vec<Chart> {
+        &self.charts
+    }
+
+    /// Charts of this sheet.
+    pub fn charts_mut(&mut self) -> &mut Vec<Chart> {
+        &mut self.charts
+    }
+
+    /// Adds a drawing shape. If the shape is anchored to a cell, an
+    /// empty cell is created there if necessary.
+    pub fn add_shape(&mut self, shape: Shape) {
+        if let Some((row, col)) = shape.anchor_cell() {
+            if self.is_empty(row, col) {
+                self.add_cell(row, col, SCell::new());
+            }
+        }
+        self.shapes.push(shape);
+    }
+
+    /// Removes a drawing shape.
+    pub fn remove_shape(&mut self, idx: usize) -> Shape {
+        self.shapes.remove(idx)
+    }
+
+    /// Drawing shapes of this sheet.
+    pub fn shapes(&self) -> &Vec<Shape> {
+        &self.shapes
+    }
+
+    /// Drawing shapes of this sheet.
+    ///
+    /// A shape whose anchor cell doesn't exist is written
+    /// as anchored to the sheet.
+    pub fn shapes_mut(&mut self) -> &mut Vec<Shape> {
+        &mut self.shapes
+    }
+
+    /// Protects the sheet with a password. The password is stored
+    /// as a digest as given in the options.
+    ///
+    /// Which cells are locked is defined by the cell styles,
+    /// see AttrTableCell::set_cell_protect().
+    pub fn protect(&mut self, password: &str, options: ProtectionOptions) {
+        self.protection = Some(SheetProtection::new(password, options));
+    }
+
+    /// Sets the protection directly.
+    pub fn set_protection(&mut self, protection: SheetProtection) {
+        self.protection = Some(protection);
+    }
+
+    /// Removes the protection.
+    pub fn unprotect(&mut self) {
+        self.protection = None;
+    }
+
+    /// Sheet is protected.
+    pub fn is_protected(&self) -> bool {
+        self.protection.is_some()
+    }
+
+    /// Returns the protection state.
+    pub fn protection(&self) -> Option<&SheetProtection> {
+        self.protection.as_ref()
+    }
+
+    /// Returns the protection state.
+    pub fn protection_mut(&mut self) -> Option<&mut SheetProtection> {
+        self.protection.as_mut()
+    }
+
+    /// Makes this sheet a scenario for the sheet before it.
+    /// See WorkBook::add_scenario() too.
+    pub fn set_scenario(&mut self, scenario: Scenario) {
+        self.scenario = Some(scenario);
+    }
+
+    /// This is no longer a scenario sheet.
+    pub fn clear_scenario(&mut self) {
+        self.scenario = None;
+    }
+
+    /// Is this a scenario sheet.
+    pub fn is_scenario(&self) -> bool {
+        self.scenario.is_some()
+    }
+
+    /// Scenario settings.
+    pub fn scenario(&self) -> Option<&Scenario> {
+        self.scenario.as_ref()
+    }
+
+    /// Scenario settings.
+    pub fn scenario_mut(&mut self) -> Option<&mut Scenario> {
+        self.scenario.as_mut()
+    }
+
+    /// Copies the cells of the ranges from the other sheet.
+    /// Without copy_formulas only the value is copied, without
+    /// copy_styles the cell style and cell format stay as they are.
+    pub(crate) fn copy_ranges_from(
+        &mut self,
+        from: &Sheet,
+        ranges: &[CellRange],
+        copy_styles: bool,
+        copy_formulas: bool,
+    ) {
+        for range in ranges {
+            // Only positions with a cell in either sheet.
+            let positions: BTreeSet<(ucell, ucell)> = self
+                .range(range)
+                .chain(from.range(range))
+                .map(|(cellref, _)| (cellref.row(), cellref.col()))
+                .collect();
+
+            for (row, col) in positions {
+                let src = from.cell(row, col).cloned().unwrap_or_default();
+                let dst = self.cell_mut(row, col);
+                dst.value = src.value;
+                dst.formula = if copy_formulas { src.formula } else { None };
+                if copy_styles {
+                    dst.style = src.style;
+                    dst.format = src.format;
+                }
+                if matches!(dst.value, Value::Empty)
+                    && dst.formula.is_none()
+                    && dst.style.is_none()
+                    && dst.format.is_none()
+                    && dst.span == (1, 1)
+                {
+                    self.remove_cell(row, col);
+                }
+            }
+        }
+    }
 }
 
 /// One Cell of the spreadsheet.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct SCell {
     value: Value,
     // Unparsed formula string.
     formula: Option<String>,
     // Cell style name.
     style: Option<String>,
+    // Direct formatting, becomes an automatic style when writing.
+    format: Option<Box<CellFormat>>,
     // Row/Column span.
     span: (ucell, ucell),
 }
 
+impl Default for SCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SCell {
     /// New, empty.
     pub fn new() -> Self {
@@ -973,6 +1966,7 @@ impl SCell {
             value: Value::Empty,
             formula: None,
             style: None,
+            format: None,
             span: (1, 1),
         }
     }
@@ -1007,6 +2001,21 @@ impl SCell {
         self.style = Some(style.into());
     }
 
+    /// Returns the direct formatting.
+    pub fn cell_format(&self) -> Option<&CellFormat> {
+        self.format.as_deref()
+    }
+
+    /// Direct formatting on top of the cell style.
+    pub fn cell_format_mut(&mut self) -> &mut CellFormat {
+        self.format.get_or_insert_with(Default::default)
+    }
+
+    /// Removes the direct formatting.
+    pub fn clear_cell_format(&mut self) {
+        self.format = None;
+    }
+
     /// Sets the row span of this cell.
     /// Cells below with values will be lost when writing.
     pub fn set_row_span(&mut self, rows: ucell) {
@@ -0,0 +1,582 @@
+//!
+//! Copies a sheet with all the definitions it uses into another
+//! workbook, and removes unused definitions from a workbook.
+//!
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::attrmap::AttrMap;
+use crate::fonts::FONT_ATTRS;
+use crate::format::ValueFormat;
+use crate::style::{PageLayout, Style, StyleOrigin, StyleUse, TextAttr};
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{Sheet, Value, WorkBook};
+
+/// Definitions used by the sheet.
+#[derive(Default)]
+struct Used {
+    styles: BTreeSet<String>,
+    formats: BTreeSet<String>,
+    fonts: BTreeSet<String>,
+    /// By name of the page layout.
+    page_layouts: BTreeSet<String>,
+}
+
+/// New names for clashing definitions.
+#[derive(Default)]
+struct Renames {
+    styles: HashMap<String, String>,
+    formats: HashMap<String, String>,
+    fonts: HashMap<String, String>,
+    page_layouts: HashMap<String, String>,
+    /// Master pages are renamed with their page layout.
+    master_pages: HashMap<String, String>,
+}
+
+/// Copies the sheet, the definitions it references and appends the
+/// sheet. Returns the index of the new sheet.
+pub(crate) fn import_sheet(book: &mut WorkBook, other: &WorkBook, idx: usize) -> usize {
+    let mut sheet = other.sheets[idx].clone();
+
+    let used = collect_used(other, std::iter::once(&sheet), Vec::new(), Vec::new());
+    let renames = assign_names(book, other, &used);
+
+    for name in &used.fonts {
+        if let Some(font) = other.fonts.get(name) {
+            let new_name = renames.fonts.get(name).unwrap_or(name);
+            if !book.fonts.contains_key(new_name) {
+                let mut font = font.clone();
+                font.set_name(new_name.as_str());
+                book.fonts.insert(new_name.clone(), font);
+            }
+        }
+    }
+    for name in &used.page_layouts {
+        if let Some(layout) = other.page_layouts.get(name) {
+            let layout = rewrite_page_layout(layout, &renames);
+            if !book.page_layouts.contains_key(layout.name()) {
+                book.page_layouts.insert(layout.name().clone(), layout);
+            }
+        }
+    }
+    for name in &used.formats {
+        if let Some(format) = other.formats.get(name) {
+            let format = rewrite_format(format, &renames);
+            if !book.formats.contains_key(format.name()) {
+                book.formats.insert(format.name().clone(), format);
+            }
+        }
+    }
+    for name in &used.styles {
+        if let Some(style) = other.styles.get(name) {
+            let style = rewrite_style(style, &renames);
+            if !book.styles.contains_key(style.name()) {
+                book.styles.insert(style.name().clone(), style);
+            }
+        }
+    }
+
+    rewrite_sheet(&mut sheet, &renames);
+    book.sheets.push(sheet);
+    book.sheets.len() - 1
+}
+
+/// Follows all references starting with the sheets and the given
+/// styles and formats.
+fn collect_used<'a, I: Iterator<Item = &'a Sheet>>(
+    other: &WorkBook,
+    sheets: I,
+    mut styles: Vec<String>,
+    mut formats: Vec<String>,
+) -> Used {
+    let mut used = Used::default();
+
+    for sheet in sheets {
+        styles.extend(sheet.style.iter().cloned());
+        for header in sheet.col_header.values().chain(sheet.row_header.values()) {
+            styles.extend(header.style.iter().cloned());
+            styles.extend(header.cell_style.iter().cloned());
+        }
+        for cell in sheet.data.values() {
+            styles.extend(cell.style.iter().cloned());
+            if let Some(format) = &cell.format {
+                push_fonts(&mut used.fonts, format.text());
+            }
+            if let Value::TextXml(text) = &cell.value {
+                push_xml_styles(&mut styles, &mut formats, text);
+            }
+        }
+        for shape in &sheet.shapes {
+            styles.extend(shape.style().cloned());
+            styles.extend(shape.text_style().cloned());
+        }
+        for frame in sheet.charts.iter().filter_map(|v| v.frame.as_ref()) {
+            push_xml_styles(&mut styles, &mut formats, frame);
+        }
+        for tag in &sheet.extra {
+            push_xml_styles(&mut styles, &mut formats, tag);
+        }
+    }
+
+    while let Some(name) = styles.pop() {
+        if used.styles.contains(&name) {
+            continue;
+        }
+        if let Some(style) = other.styles.get(&name) {
+            styles.extend(style.parent().cloned());
+            if let Some(stylemaps) = style.stylemaps() {
+                styles.extend(stylemaps.iter().map(|v| v.applied_style().clone()));
+            }
+            formats.extend(style.value_format().cloned());
+            if let Some(master_page) = style.master_page_name() {
+                used.page_layouts.extend(
+                    other
+                        .page_layouts
+                        .values()
+                        .filter(|v| v.master_page_name() == master_page)
+                        .map(|v| v.name().clone()),
+                );
+            }
+            push_fonts(&mut used.fonts, style.text());
+        }
+        used.styles.insert(name);
+    }
+
+    while let Some(name) = formats.pop() {
+        if used.formats.contains(&name) {
+            continue;
+        }
+        if let Some(format) = other.formats.get(&name) {
+            if let Some(stylemaps) = format.stylemaps() {
+                formats.extend(stylemaps.iter().map(|v| v.applied_style().clone()));
+            }
+            push_fonts(&mut used.fonts, format.text());
+        }
+        used.formats.insert(name);
+    }
+
+    used
+}
+
+/// Styles referenced in passed-through XML, like the text:style-name of
+/// spans and paragraphs or the draw:style-name of frames. Attributes
+/// ending in data-style-name reference formats.
+fn push_xml_styles(styles: &mut Vec<String>, formats: &mut Vec<String>, tag: &XmlTag) {
+    for (name, value) in tag.attr_iter() {
+        if name.ends_with("data-style-name") {
+            formats.push(value.clone());
+        } else if name.ends_with("style-name") {
+            styles.push(value.clone());
+        }
+    }
+    for content in tag.content() {
+        if let XmlContent::Tag(tag) = content {
+            push_xml_styles(styles, formats, tag);
+        }
+    }
+}
+
+fn push_fonts(fonts: &mut BTreeSet<String>, text: &TextAttr) {
+    for attr in FONT_ATTRS.iter() {
+        if let Some(font) = text.attr(attr) {
+            fonts.insert(font.clone());
+        }
+    }
+}
+
+/// A definition keeps its name if the name is free in the workbook or
+/// the workbook has the same definition. Otherwise it gets a new name,
+/// or the name of an equal copy from an earlier import. Renaming a
+/// definition changes everything that references it, so this repeats
+/// until all names are settled.
+fn assign_names(book: &WorkBook, other: &WorkBook, used: &Used) -> Renames {
+    let mut renames = Renames::default();
+
+    for name in &used.fonts {
+        if let Some(font) = other.fonts.get(name) {
+            let new_name = pick_name(name, name, &book.fonts, &other.fonts, &renames.fonts, |v| {
+                let mut font = font.clone();
+                font.set_name(v);
+                font
+            });
+            if let Some(new_name) = new_name {
+                renames.fonts.insert(name.clone(), new_name);
+            }
+        }
+    }
+
+    for name in &used.page_layouts {
+        if let Some(layout) = other.page_layouts.get(name) {
+            let master_page = layout.master_page_name();
+            let clash = book
+                .page_layouts
+                .values()
+                .any(|v| (v.name() == name || v.master_page_name() == master_page) && v != layout);
+            if clash {
+                let new_name = unique_name(name, |v| {
+                    book.page_layouts.contains_key(v) || other.page_layouts.contains_key(v)
+                });
+                let new_master_page = unique_name(master_page, |v| {
+                    book.page_layouts
+                        .values()
+                        .chain(other.page_layouts.values())
+                        .any(|l| l.master_page_name() == v)
+                });
+                renames.page_layouts.insert(name.clone(), new_name);
+                renames
+                    .master_pages
+                    .insert(master_page.clone(), new_master_page);
+            }
+        }
+    }
+
+    loop {
+        let mut changed = false;
+
+        for name in &used.formats {
+            if let Some(format) = other.formats.get(name) {
+                let current = renames.formats.get(name).unwrap_or(name);
+                let new_name = pick_name(
+                    name,
+                    current,
+                    &book.formats,
+                    &other.formats,
+                    &renames.formats,
+                    |v| {
+                        let mut format = rewrite_format(format, &renames);
+                        format.set_name(v);
+                        format
+                    },
+                );
+                if let Some(new_name) = new_name {
+                    renames.formats.insert(name.clone(), new_name);
+                    changed = true;
+                }
+            }
+        }
+
+        for name in &used.styles {
+            if let Some(style) = other.styles.get(name) {
+                let current = renames.styles.get(name).unwrap_or(name);
+                let new_name = pick_name(
+                    name,
+                    current,
+                    &book.styles,
+                    &other.styles,
+                    &renames.styles,
+                    |v| {
+                        let mut style = rewrite_style(style, &renames);
+                        style.set_name(v);
+                        style
+                    },
+                );
+                if let Some(new_name) = new_name {
+                    renames.styles.insert(name.clone(), new_name);
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    renames
+}
+
+/// Checks the current name of the definition and returns a new name
+/// if it can't be used. A name can be used if the workbook has an equal
+/// definition, or if it is free and no other definition uses it.
+fn pick_name<T: PartialEq, F: Fn(&str) -> T>(
+    name: &str,
+    current: &str,
+    book: &HashMap<String, T>,
+    other: &HashMap<String, T>,
+    renames: &HashMap<String, String>,
+    definition: F,
+) -> Option<String> {
+    let usable = |v: &str| match book.get(v) {
+        Some(existing) => &definition(v) == existing,
+        None => {
+            v == name
+                || (!other.contains_key(v) && !renames.iter().any(|(k, r)| k != name && r == v))
+        }
+    };
+
+    if usable(current) {
+        None
+    } else {
+        Some(unique_name(name, |v| !usable(v)))
+    }
+}
+
+/// Appends _2, _3, ... until the name is not taken.
+fn unique_name<F: Fn(&str) -> bool>(name: &str, taken: F) -> String {
+    (2..)
+        .map(|n| format!("{}_{}", name, n))
+        .find(|v| !taken(v))
+        .unwrap()
+}
+
+fn rename(name: &mut Option<String>, renames: &HashMap<String, String>) {
+    if let Some(new_name) = name.as_ref().and_then(|v| renames.get(v)) {
+        *name = Some(new_name.clone());
+    }
+}
+
+fn rewrite_fonts(text: &mut TextAttr, renames: &Renames) {
+    for attr in FONT_ATTRS.iter() {
+        if let Some(new_name) = text.attr(attr).and_then(|v| renames.fonts.get(v)) {
+            let new_name = new_name.clone();
+            text.set_attr(attr, new_name);
+        }
+    }
+}
+
+fn rewrite_style(style: &Style, renames: &Renames) -> Style {
+    let mut style = style.clone();
+    if let Some(new_name) = renames.styles.get(style.name()) {
+        style.set_name(new_name.as_str());
+    }
+    if let Some(parent) = style.parent().and_then(|v| renames.styles.get(v)) {
+        style.set_parent(parent.as_str());
+    }
+    if let Some(format) = style.value_format().and_then(|v| renames.formats.get(v)) {
+        style.set_value_format(format.as_str());
+    }
+    if let Some(master_page) = style
+        .master_page_name()
+        .and_then(|v| renames.master_pages.get(v))
+    {
+        style.set_master_page_name(master_page.as_str());
+    }
+    if style.stylemaps().is_some() {
+        for stylemap in style.stylemaps_mut() {
+            if let Some(applied) = renames.styles.get(stylemap.applied_style()) {
+                stylemap.set_applied_style(applied.as_str());
+            }
+        }
+    }
+    rewrite_fonts(style.text_mut(), renames);
+    style
+}
+
+fn rewrite_format(format: &ValueFormat, renames: &Renames) -> ValueFormat {
+    let mut format = format.clone();
+    if let Some(new_name) = renames.formats.get(format.name()) {
+        format.set_name(new_name.as_str());
+    }
+    if format.stylemaps().is_some() {
+        for stylemap in format.stylemaps_mut() {
+            if let Some(applied) = renames.formats.get(stylemap.applied_style()) {
+                stylemap.set_applied_style(applied.as_str());
+            }
+        }
+    }
+    rewrite_fonts(format.text_mut(), renames);
+    format
+}
+
+fn rewrite_page_layout(layout: &PageLayout, renames: &Renames) -> PageLayout {
+    let mut layout = layout.clone();
+    if let Some(new_name) = renames.page_layouts.get(layout.name()) {
+        layout.set_name(new_name.clone());
+    }
+    if let Some(master_page) = renames.master_pages.get(layout.master_page_name()) {
+        layout.set_master_page_name(master_page.clone());
+    }
+    layout
+}
+
+fn rewrite_sheet(sheet: &mut Sheet, renames: &Renames) {
+    rename(&mut sheet.style, &renames.styles);
+    for header in sheet
+        .col_header
+        .values_mut()
+        .chain(sheet.row_header.values_mut())
+    {
+        rename(&mut header.style, &renames.styles);
+        rename(&mut header.cell_style, &renames.styles);
+    }
+    for cell in sheet.data.values_mut() {
+        rename(&mut cell.style, &renames.styles);
+        if let Some(format) = &mut cell.format {
+            rewrite_fonts(format.text_mut(), renames);
+        }
+        if let Value::TextXml(text) = &mut cell.value {
+            rewrite_xml_styles(text, renames);
+        }
+    }
+    for shape in sheet.shapes.iter_mut() {
+        if let Some(style) = shape.style().and_then(|v| renames.styles.get(v)) {
+            let style = style.clone();
+            shape.set_style(style);
+        }
+        if let Some(style) = shape.text_style().and_then(|v| renames.styles.get(v)) {
+            let style = style.clone();
+            shape.set_text_style(style);
+        }
+    }
+    for frame in sheet.charts.iter_mut().filter_map(|v| v.frame.as_mut()) {
+        rewrite_xml_styles(frame, renames);
+    }
+    for tag in sheet.extra.iter_mut() {
+        rewrite_xml_styles(tag, renames);
+    }
+}
+
+fn rewrite_xml_styles(tag: &mut XmlTag, renames: &Renames) {
+    if let Some(attr) = tag.attr_map_mut() {
+        for (name, value) in attr.iter_mut() {
+            let new_name = if name.ends_with("data-style-name") {
+                renames.formats.get(value)
+            } else if name.ends_with("style-name") {
+                renames.styles.get(value)
+            } else {
+                None
+            };
+            if let Some(new_name) = new_name {
+                *value = new_name.clone();
+            }
+        }
+    }
+    for content in tag.content_mut() {
+        if let XmlContent::Tag(tag) = content {
+            rewrite_xml_styles(tag, renames);
+        }
+    }
+}
+
+/// Automatic styles and formats of content.xml belong to the data and
+/// can be removed. All others are kept.
+fn removable(origin: StyleOrigin, styleuse: StyleUse) -> bool {
+    origin == StyleOrigin::Content && styleuse == StyleUse::Automatic
+}
+
+/// Merges equal definitions and removes everything that is not
+/// referenced.
+pub(crate) fn remove_unused_styles(book: &mut WorkBook) {
+    merge_duplicates(book);
+
+    let mut styles: Vec<String> = book
+        .styles
+        .values()
+        .filter(|v| !removable(v.origin(), v.styleuse()))
+        .map(|v| v.name().clone())
+        .chain(book.def_styles.values().cloned())
+        .collect();
+    let mut formats: Vec<String> = book
+        .formats
+        .values()
+        .filter(|v| !removable(v.origin(), v.styleuse()))
+        .map(|v| v.name().clone())
+        .collect();
+    for tag in &book.extra {
+        push_xml_styles(&mut styles, &mut formats, tag);
+    }
+    let used = collect_used(book, book.sheets.iter(), styles, formats);
+
+    book.styles
+        .retain(|k, v| !removable(v.origin(), v.styleuse()) || used.styles.contains(k));
+    book.formats
+        .retain(|k, v| !removable(v.origin(), v.styleuse()) || used.formats.contains(k));
+    book.fonts.retain(|k, _| used.fonts.contains(k));
+    book.page_layouts
+        .retain(|k, v| used.page_layouts.contains(k) || v.master_page_name() == "Default");
+}
+
+/// Definitions that are equal apart from the name are merged into the
+/// first one by name. Fonts without a font family are identified by
+/// their name and are never merged. Merging can make more definitions
+/// equal, so this repeats until nothing changes.
+fn merge_duplicates(book: &mut WorkBook) {
+    loop {
+        let fonts = duplicates(
+            &book.fonts,
+            |v| v.attr("svg:font-family").is_some(),
+            |v| {
+                let mut font = v.clone();
+                font.set_name("");
+                font
+            },
+        );
+        let mut renames = Renames {
+            fonts,
+            ..Default::default()
+        };
+        let formats = duplicates(
+            &book.formats,
+            |v| removable(v.origin(), v.styleuse()),
+            |v| {
+                let mut format = rewrite_format(v, &renames);
+                format.set_name("");
+                format
+            },
+        );
+        renames.formats = formats;
+        let styles = duplicates(
+            &book.styles,
+            |v| removable(v.origin(), v.styleuse()),
+            |v| {
+                let mut style = rewrite_style(v, &renames);
+                style.set_name("");
+                style
+            },
+        );
+        renames.styles = styles;
+
+        if renames.fonts.is_empty() && renames.formats.is_empty() && renames.styles.is_empty() {
+            break;
+        }
+
+        book.fonts.retain(|k, _| !renames.fonts.contains_key(k));
+        book.formats.retain(|k, _| !renames.formats.contains_key(k));
+        book.styles.retain(|k, _| !renames.styles.contains_key(k));
+
+        for format in book.formats.values_mut() {
+            *format = rewrite_format(format, &renames);
+        }
+        for style in book.styles.values_mut() {
+            *style = rewrite_style(style, &renames);
+        }
+        for sheet in book.sheets.iter_mut() {
+            rewrite_sheet(sheet, &renames);
+        }
+        for tag in book.extra.iter_mut() {
+            rewrite_xml_styles(tag, &renames);
+        }
+        for style in book.def_styles.values_mut() {
+            if let Some(new_name) = renames.styles.get(style) {
+                *style = new_name.clone();
+            }
+        }
+    }
+}
+
+/// Maps each duplicate to the first equal definition.
+fn duplicates<T: PartialEq, P: Fn(&T) -> bool, F: Fn(&T) -> T>(
+    map: &HashMap<String, T>,
+    mergeable: P,
+    unnamed: F,
+) -> HashMap<String, String> {
+    let mut names: Vec<&String> = map
+        .iter()
+        .filter(|(_, v)| mergeable(v))
+        .map(|(k, _)| k)
+        .collect();
+    names.sort();
+
+    let mut kept: Vec<(&String, T)> = Vec::new();
+    let mut renames = HashMap::new();
+    for name in names {
+        let definition = unnamed(&map[name]);
+        match kept.iter().find(|(_, v)| *v == definition) {
+            Some((first, _)) => {
+                renames.insert(name.clone(), (*first).clone());
+            }
+            None => kept.push((name, definition)),
+        }
+    }
+    renames
+}
@@ -0,0 +1,227 @@
+//!
+//! Sheet protection.
+//!
+//! A protected sheet stores a digest of the password, never the
+//! password itself. Which cells are locked is defined by the
+//! cell-style attribute style:cell-protect.
+//!
+//! ```
+//! use spreadsheet_ods::Sheet;
+//! use spreadsheet_ods::protection::{ProtectionOptions, ProtectionDigest};
+//!
+//! let mut sheet = Sheet::new();
+//! let mut options = ProtectionOptions::new();
+//! options.set_select_protected_cells(false);
+//! options.set_digest(ProtectionDigest::Sha256);
+//! sheet.protect("secret", options);
+//!
+//! assert!(sheet.is_protected());
+//! assert!(sheet.protection().unwrap().check_password("secret"));
+//! ```
+//!
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::OdsError;
+
+/// Digest algorithm for the protection key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ProtectionDigest {
+    /// SHA-256, the default for current LibreOffice versions.
+    #[default]
+    Sha256,
+    /// SHA-1. The default if no algorithm is given in the file.
+    Sha1,
+}
+
+impl FromStr for ProtectionDigest {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "http://www.w3.org/2000/09/xmldsig#sha256" => Ok(ProtectionDigest::Sha256),
+            "http://www.w3.org/2000/09/xmldsig#sha1" => Ok(ProtectionDigest::Sha1),
+            _ => Err(OdsError::Ods(format!(
+                "Unknown value for table:protection-key-digest-algorithm {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for ProtectionDigest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ProtectionDigest::Sha256 => write!(f, "http://www.w3.org/2000/09/xmldsig#sha256"),
+            ProtectionDigest::Sha1 => write!(f, "http://www.w3.org/2000/09/xmldsig#sha1"),
+        }
+    }
+}
+
+impl ProtectionDigest {
+    /// Calculates the base64 encoded digest of the password.
+    pub fn key(&self, password: &str) -> String {
+        match self {
+            ProtectionDigest::Sha256 => base64::encode(Sha256::digest(password.as_bytes())),
+            ProtectionDigest::Sha1 => base64::encode(Sha1::digest(password.as_bytes())),
+        }
+    }
+}
+
+/// Options for a protected sheet. These define what the user
+/// is still allowed to do.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ProtectionOptions {
+    digest: ProtectionDigest,
+    select_protected_cells: bool,
+    select_unprotected_cells: bool,
+    insert_columns: bool,
+    insert_rows: bool,
+    delete_columns: bool,
+    delete_rows: bool,
+}
+
+impl Default for ProtectionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProtectionOptions {
+    /// Defaults as used by LibreOffice. Selecting cells is allowed,
+    /// inserting and deleting is not.
+    pub fn new() -> Self {
+        Self {
+            digest: ProtectionDigest::Sha256,
+            select_protected_cells: true,
+            select_unprotected_cells: true,
+            insert_columns: false,
+            insert_rows: false,
+            delete_columns: false,
+            delete_rows: false,
+        }
+    }
+
+    /// Digest algorithm for the password.
+    pub fn set_digest(&mut self, digest: ProtectionDigest) {
+        self.digest = digest;
+    }
+
+    /// Digest algorithm for the password.
+    pub fn digest(&self) -> ProtectionDigest {
+        self.digest
+    }
+
+    /// Allows selecting protected cells.
+    pub fn set_select_protected_cells(&mut self, select: bool) {
+        self.select_protected_cells = select;
+    }
+
+    /// Allows selecting protected cells.
+    pub fn select_protected_cells(&self) -> bool {
+        self.select_protected_cells
+    }
+
+    /// Allows selecting unprotected cells.
+    pub fn set_select_unprotected_cells(&mut self, select: bool) {
+        self.select_unprotected_cells = select;
+    }
+
+    /// Allows selecting unprotected cells.
+    pub fn select_unprotected_cells(&self) -> bool {
+        self.select_unprotected_cells
+    }
+
+    /// Allows inserting columns.
+    pub fn set_insert_columns(&mut self, insert: bool) {
+        self.insert_columns = insert;
+    }
+
+    /// Allows inserting columns.
+    pub fn insert_columns(&self) -> bool {
+        self.insert_columns
+    }
+
+    /// Allows inserting rows.
+    pub fn set_insert_rows(&mut self, insert: bool) {
+        self.insert_rows = insert;
+    }
+
+    /// Allows inserting rows.
+    pub fn insert_rows(&self) -> bool {
+        self.insert_rows
+    }
+
+    /// Allows deleting columns.
+    pub fn set_delete_columns(&mut self, delete: bool) {
+        self.delete_columns = delete;
+    }
+
+    /// Allows deleting columns.
+    pub fn delete_columns(&self) -> bool {
+        self.delete_columns
+    }
+
+    /// Allows deleting rows.
+    pub fn set_delete_rows(&mut self, delete: bool) {
+        self.delete_rows = delete;
+    }
+
+    /// Allows deleting rows.
+    pub fn delete_rows(&self) -> bool {
+        self.delete_rows
+    }
+}
+
+/// Protection state of a sheet.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SheetProtection {
+    key: Option<String>,
+    options: ProtectionOptions,
+}
+
+impl SheetProtection {
+    /// Protection with the given password.
+    pub fn new(password: &str, options: ProtectionOptions) -> Self {
+        Self {
+            key: Some(options.digest.key(password)),
+            options,
+        }
+    }
+
+    /// Protection with an already calculated key.
+    pub fn new_with_key<S: Into<String>>(key: Option<S>, options: ProtectionOptions) -> Self {
+        Self {
+            key: key.map(|v| v.into()),
+            options,
+        }
+    }
+
+    /// The base64 encoded password digest.
+    /// A sheet can be protected without a password.
+    pub fn key(&self) -> Option<&String> {
+        self.key.as_ref()
+    }
+
+    /// Protection options.
+    pub fn options(&self) -> &ProtectionOptions {
+        &self.options
+    }
+
+    /// Protection options.
+    pub fn options_mut(&mut self) -> &mut ProtectionOptions {
+        &mut self.options
+    }
+
+    /// Compares the password with the stored key.
+    pub fn check_password(&self, password: &str) -> bool {
+        match &self.key {
+            None => password.is_empty(),
+            Some(key) => *key == self.options.digest.key(password),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//!
+//! Scenarios.
+//!
+//! A scenario is a sheet of its own that directly follows the sheet it
+//! belongs to. It holds alternative values for some ranges of the
+//! target sheet. Exactly one scenario per target is active, its values
+//! are the ones visible in the target sheet.
+//!
+//! ```
+//! use spreadsheet_ods::{CellRange, Sheet, WorkBook};
+//! use spreadsheet_ods::scenario::Scenario;
+//!
+//! let mut wb = WorkBook::new();
+//! let mut sheet = Sheet::new_with_name("Plan");
+//! sheet.set_value(0, 0, "Growth");
+//! sheet.set_value(0, 1, 0.02);
+//! wb.push_sheet(sheet);
+//!
+//! let mut scenario = Scenario::new();
+//! scenario.add_range(CellRange::local(0, 1, 0, 1));
+//! scenario.set_comment("Worst case");
+//! let worst = wb.add_scenario(0, "Worst", scenario).unwrap();
+//! wb.sheet_mut(worst).set_value(0, 1, -0.05);
+//!
+//! wb.activate_scenario(worst).unwrap();
+//! assert_eq!(wb.sheet(0).value(0, 1).as_f64_or(0.0), -0.05);
+//! ```
+//!
+
+use color::Rgb;
+
+use crate::CellRange;
+
+/// Settings of a scenario sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    ranges: Vec<CellRange>,
+    active: bool,
+    display_border: bool,
+    border_color: Option<Rgb<u8>>,
+    copy_back: bool,
+    copy_styles: bool,
+    copy_formulas: bool,
+    comment: Option<String>,
+    protected: bool,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    /// Inactive scenario without ranges. The defaults are the same as
+    /// in LibreOffice: display the border, copy back, copy styles and
+    /// formulas.
+    pub fn new() -> Self {
+        Self {
+            ranges: vec![],
+            active: false,
+            display_border: true,
+            border_color: None,
+            copy_back: true,
+            copy_styles: true,
+            copy_formulas: true,
+            comment: None,
+            protected: false,
+        }
+    }
+
+    /// Adds a range of the scenario. The table name is ignored, the
+    /// range always refers to the same cells in the scenario sheet and
+    /// in the target sheet.
+    pub fn add_range(&mut self, range: CellRange) {
+        self.ranges.push(range);
+    }
+
+    /// Ranges of the scenario.
+    pub fn set_ranges(&mut self, ranges: Vec<CellRange>) {
+        self.ranges = ranges;
+    }
+
+    /// Ranges of the scenario.
+    pub fn ranges(&self) -> &Vec<CellRange> {
+        &self.ranges
+    }
+
+    /// Active scenario. Use WorkBook::activate_scenario() to switch
+    /// scenarios, this only sets the flag.
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    /// Active scenario.
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    /// Shows a border around the scenario ranges.
+    pub fn set_display_border(&mut self, display_border: bool) {
+        self.display_border = display_border;
+    }
+
+    /// Shows a border around the scenario ranges.
+    pub fn display_border(&self) -> bool {
+        self.display_border
+    }
+
+    /// Color of the border.
+    pub fn set_border_color(&mut self, color: Rgb<u8>) {
+        self.border_color = Some(color);
+    }
+
+    /// Color of the border.
+    pub fn border_color(&self) -> Option<Rgb<u8>> {
+        self.border_color
+    }
+
+    /// Changes in the target sheet are copied back to the scenario
+    /// when another scenario is activated.
+    pub fn set_copy_back(&mut self, copy_back: bool) {
+        self.copy_back = copy_back;
+    }
+
+    /// Changes in the target sheet are copied back to the scenario.
+    pub fn copy_back(&self) -> bool {
+        self.copy_back
+    }
+
+    /// Cell styles are copied together with the values.
+    pub fn set_copy_styles(&mut self, copy_styles: bool) {
+        self.copy_styles = copy_styles;
+    }
+
+    /// Cell styles are copied together with the values.
+    pub fn copy_styles(&self) -> bool {
+        self.copy_styles
+    }
+
+    /// Formulas are copied. Otherwise only the values are copied.
+    pub fn set_copy_formulas(&mut self, copy_formulas: bool) {
+        self.copy_formulas = copy_formulas;
+    }
+
+    /// Formulas are copied.
+    pub fn copy_formulas(&self) -> bool {
+        self.copy_formulas
+    }
+
+    /// Comment.
+    pub fn set_comment<S: Into<String>>(&mut self, comment: S) {
+        self.comment = Some(comment.into());
+    }
+
+    /// Comment.
+    pub fn comment(&self) -> Option<&String> {
+        self.comment.as_ref()
+    }
+
+    /// Scenario is protected against changes.
+    pub fn set_protected(&mut self, protected: bool) {
+        self.protected = protected;
+    }
+
+    /// Scenario is protected against changes.
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
+}
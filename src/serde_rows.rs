@@ -0,0 +1,793 @@
+//!
+//! Serde support for mapping the rows of a sheet to records.
+//!
+//! Needs the feature "serde". Each record is one row, the fields are
+//! the columns. Sheet::serialize_rows() writes the field names as a
+//! header row, Sheet::deserialize_rows() finds the columns by the
+//! names in the header row.
+//!
+//! Chrono and time types have no serde implementation of their own,
+//! use the helper modules here with `#[serde(with = "...")]`. They
+//! map to Value::DateTime and Value::TimeDuration in the sheet and to
+//! strings or milliseconds in any other format.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use serde::{Deserialize, Serialize};
+//! use spreadsheet_ods::Sheet;
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Record {
+//!     name: String,
+//!     amount: f64,
+//!     #[serde(with = "spreadsheet_ods::serde_rows::date")]
+//!     due: NaiveDate,
+//!     note: Option<String>,
+//! }
+//!
+//! let records = vec![Record {
+//!     name: "rent".to_string(),
+//!     amount: 800.0,
+//!     due: NaiveDate::from_ymd(2021, 1, 1),
+//!     note: None,
+//! }];
+//!
+//! let mut sheet = Sheet::new();
+//! sheet.serialize_rows(&records, true).unwrap();
+//! assert_eq!(sheet.value(0, 0).as_str_or(""), "name");
+//!
+//! let read: Vec<Record> = sheet.deserialize_rows(0).unwrap();
+//! assert_eq!(read, records);
+//! ```
+//!
+
+use std::fmt::Display;
+
+use chrono::NaiveDateTime;
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::ser::{Impossible, SerializeStruct};
+use serde::{de, ser, Deserializer, Serialize, Serializer};
+use time::Duration;
+
+use crate::{ucell, CellRef, OdsError, Sheet, Value};
+
+const DATETIME: &str = "$spreadsheet_ods::DateTime";
+const DATE: &str = "$spreadsheet_ods::Date";
+const DURATION: &str = "$spreadsheet_ods::Duration";
+const DECIMAL: &str = "$spreadsheet_ods::Decimal";
+
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+impl ser::Error for OdsError {
+    fn custom<T: Display>(msg: T) -> Self {
+        OdsError::Ods(msg.to_string())
+    }
+}
+
+impl de::Error for OdsError {
+    fn custom<T: Display>(msg: T) -> Self {
+        OdsError::Ods(msg.to_string())
+    }
+}
+
+/// Accepts the string form of the helper types.
+struct StringVisitor;
+
+impl<'de> Visitor<'de> for StringVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        d.deserialize_str(self)
+    }
+}
+
+/// Accepts the millisecond form of durations.
+struct MillisVisitor;
+
+impl<'de> Visitor<'de> for MillisVisitor {
+    type Value = i64;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a duration in milliseconds")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v as i64)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        d.deserialize_i64(self)
+    }
+}
+
+/// Use with `#[serde(with = "spreadsheet_ods::serde_rows::datetime")]`
+/// for a chrono::NaiveDateTime.
+pub mod datetime {
+    use chrono::NaiveDateTime;
+    use serde::{de, Deserializer, Serializer};
+
+    use super::{StringVisitor, DATETIME, DATETIME_FORMAT};
+
+    /// Serializes as Value::DateTime, or as ISO string.
+    pub fn serialize<S: Serializer>(v: &NaiveDateTime, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_newtype_struct(DATETIME, &v.format(DATETIME_FORMAT).to_string())
+    }
+
+    /// Deserializes from Value::DateTime, or from an ISO string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDateTime, D::Error> {
+        let s = d.deserialize_newtype_struct(DATETIME, StringVisitor)?;
+        NaiveDateTime::parse_from_str(&s, DATETIME_FORMAT).map_err(de::Error::custom)
+    }
+}
+
+/// Use with `#[serde(with = "spreadsheet_ods::serde_rows::date")]`
+/// for a chrono::NaiveDate.
+pub mod date {
+    use chrono::NaiveDate;
+    use serde::{de, Deserializer, Serializer};
+
+    use super::{StringVisitor, DATE, DATE_FORMAT};
+
+    /// Serializes as Value::DateTime, or as ISO string.
+    pub fn serialize<S: Serializer>(v: &NaiveDate, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_newtype_struct(DATE, &v.format(DATE_FORMAT).to_string())
+    }
+
+    /// Deserializes from Value::DateTime, or from an ISO string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveDate, D::Error> {
+        let s = d.deserialize_newtype_struct(DATE, StringVisitor)?;
+        NaiveDate::parse_from_str(&s, DATE_FORMAT).map_err(de::Error::custom)
+    }
+}
+
+/// Use with `#[serde(with = "spreadsheet_ods::serde_rows::duration")]`
+/// for a time::Duration.
+pub mod duration {
+    use serde::{Deserializer, Serializer};
+    use time::Duration;
+
+    use super::{MillisVisitor, DURATION};
+
+    /// Serializes as Value::TimeDuration, or as milliseconds.
+    pub fn serialize<S: Serializer>(v: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_newtype_struct(DURATION, &v.num_milliseconds())
+    }
+
+    /// Deserializes from Value::TimeDuration, or from milliseconds.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        let ms = d.deserialize_newtype_struct(DURATION, MillisVisitor)?;
+        Ok(Duration::milliseconds(ms))
+    }
+}
+
+/// Use with `#[serde(with = "spreadsheet_ods::serde_rows::decimal")]`
+/// for a rust_decimal::Decimal.
+#[cfg(feature = "use_decimal")]
+pub mod decimal {
+    use std::str::FromStr;
+
+    use rust_decimal::Decimal;
+    use serde::{de, Deserializer, Serializer};
+
+    use super::{StringVisitor, DECIMAL};
+
+    /// Serializes as Value::Number, or as string.
+    pub fn serialize<S: Serializer>(v: &Decimal, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_newtype_struct(DECIMAL, &v.to_string())
+    }
+
+    /// Deserializes from Value::Number, or from a string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Decimal, D::Error> {
+        let s = d.deserialize_newtype_struct(DECIMAL, StringVisitor)?;
+        Decimal::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+/// Writes the records to the sheet, starting at the first row.
+pub(crate) fn serialize_rows<T: Serialize>(
+    sheet: &mut Sheet,
+    records: &[T],
+    header: bool,
+) -> Result<(), OdsError> {
+    let first = if header { 1 } else { 0 };
+    let mut names: Vec<&'static str> = Vec::new();
+    for (idx, record) in records.iter().enumerate() {
+        let row = first + idx as ucell;
+        let fields = record.serialize(RowSerializer { row })?;
+
+        for (col, (name, value)) in fields.into_iter().enumerate() {
+            if col >= names.len() {
+                names.push(name);
+            }
+            if !matches!(value, Value::Empty) {
+                sheet.set_value(row, col as ucell, value);
+            }
+        }
+    }
+    if header {
+        for (col, name) in names.into_iter().enumerate() {
+            sheet.set_value(0, col as ucell, name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads all rows below the header row.
+pub(crate) fn deserialize_rows<T: DeserializeOwned>(
+    sheet: &Sheet,
+    header_row: ucell,
+) -> Result<Vec<T>, OdsError> {
+    let columns: Vec<(ucell, &str)> = sheet
+        .row_cells(header_row)
+        .filter_map(|(col, cell)| match cell.value().as_str_opt() {
+            Some(name) if !name.is_empty() => Some((col, name)),
+            _ => None,
+        })
+        .collect();
+
+    let mut records = Vec::new();
+    for (row, _) in sheet.rows() {
+        if row <= header_row {
+            continue;
+        }
+        // Rows with only styled cells don't count.
+        if columns
+            .iter()
+            .all(|(col, _)| matches!(sheet.value(row, *col), Value::Empty))
+        {
+            continue;
+        }
+
+        let mut rd = RowDeserializer {
+            sheet,
+            row,
+            columns: &columns,
+            next: 0,
+            cell_error: false,
+        };
+        let record = match T::deserialize(&mut rd) {
+            Ok(record) => record,
+            // Errors for a cell already contain the reference.
+            Err(e) if rd.cell_error => return Err(e),
+            Err(e) => return Err(OdsError::Ods(format!("{} {}", CellRef::local(row, 0), e))),
+        };
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn unsupported(what: &str) -> OdsError {
+    OdsError::Ods(format!("Unsupported type for a cell: {}", what))
+}
+
+/// Serializes a struct into (field, value) pairs.
+struct RowSerializer {
+    row: ucell,
+}
+
+struct RowStructSerializer {
+    row: ucell,
+    fields: Vec<(&'static str, Value)>,
+}
+
+impl SerializeStruct for RowStructSerializer {
+    type Ok = Vec<(&'static str, Value)>;
+    type Error = OdsError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        let value = value.serialize(ValueSerializer).map_err(|e| {
+            let cellref = CellRef::local(self.row, self.fields.len() as ucell);
+            OdsError::Ods(format!("{} field {}: {}", cellref, key, e))
+        })?;
+        self.fields.push((key, value));
+        Ok(())
+    }
+
+    /// Keeps the column of a skipped field.
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.fields.push((key, Value::Empty));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.fields)
+    }
+}
+
+impl RowSerializer {
+    fn no_struct(&self) -> OdsError {
+        OdsError::Ods(format!(
+            "{} Only structs can be written as rows",
+            CellRef::local(self.row, 0)
+        ))
+    }
+}
+
+macro_rules! no_row {
+    ($($f:ident($($t:ty),*);)*) => {
+        $(
+            fn $f(self, $(_: $t),*) -> Result<Self::Ok, Self::Error> {
+                Err(self.no_struct())
+            }
+        )*
+    };
+}
+
+impl Serializer for RowSerializer {
+    type Ok = Vec<(&'static str, Value)>;
+    type Error = OdsError;
+    type SerializeSeq = Impossible<Self::Ok, OdsError>;
+    type SerializeTuple = Impossible<Self::Ok, OdsError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, OdsError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, OdsError>;
+    type SerializeMap = Impossible<Self::Ok, OdsError>;
+    type SerializeStruct = RowStructSerializer;
+    type SerializeStructVariant = Impossible<Self::Ok, OdsError>;
+
+    no_row! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(self.no_struct())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(self.no_struct())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(self.no_struct())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(self.no_struct())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(self.no_struct())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(self.no_struct())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(RowStructSerializer {
+            row: self.row,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(self.no_struct())
+    }
+}
+
+/// Serializes a single field into a Value.
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = OdsError;
+    type SerializeSeq = Impossible<Value, OdsError>;
+    type SerializeTuple = Impossible<Value, OdsError>;
+    type SerializeTupleStruct = Impossible<Value, OdsError>;
+    type SerializeTupleVariant = Impossible<Value, OdsError>;
+    type SerializeMap = Impossible<Value, OdsError>;
+    type SerializeStruct = Impossible<Value, OdsError>;
+    type SerializeStructVariant = Impossible<Value, OdsError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, OdsError> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, OdsError> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, OdsError> {
+        Ok(Value::Number(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, OdsError> {
+        Ok(Value::Text(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, OdsError> {
+        Ok(Value::Text(v.to_string()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Value, OdsError> {
+        Err(unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<Value, OdsError> {
+        Ok(Value::Empty)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, OdsError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, OdsError> {
+        Ok(Value::Empty)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, OdsError> {
+        Ok(Value::Empty)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, OdsError> {
+        Ok(Value::Text(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Value, OdsError> {
+        let value = value.serialize(self)?;
+        match (name, value) {
+            (DATETIME, Value::Text(v)) => Ok(Value::DateTime(NaiveDateTime::parse_from_str(
+                &v,
+                DATETIME_FORMAT,
+            )?)),
+            (DATE, Value::Text(v)) => Ok(Value::DateTime(NaiveDateTime::parse_from_str(
+                &format!("{}T00:00:00", v),
+                DATETIME_FORMAT,
+            )?)),
+            (DURATION, Value::Number(v)) => {
+                Ok(Value::TimeDuration(Duration::milliseconds(v as i64)))
+            }
+            (DECIMAL, Value::Text(v)) => Ok(Value::Number(v.parse()?)),
+            (_, value) => Ok(value),
+        }
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Value, OdsError> {
+        Err(unsupported("enum variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, OdsError> {
+        Err(unsupported("sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, OdsError> {
+        Err(unsupported("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, OdsError> {
+        Err(unsupported("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, OdsError> {
+        Err(unsupported("enum variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, OdsError> {
+        Err(unsupported("map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, OdsError> {
+        Err(unsupported("struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, OdsError> {
+        Err(unsupported("enum variant"))
+    }
+}
+
+/// Deserializes one row as a map from header names to cells.
+struct RowDeserializer<'a> {
+    sheet: &'a Sheet,
+    row: ucell,
+    columns: &'a [(ucell, &'a str)],
+    next: usize,
+    cell_error: bool,
+}
+
+impl<'de, 'a> Deserializer<'de> for &mut RowDeserializer<'a> {
+    type Error = OdsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OdsError> {
+        visitor.visit_map(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, 'a> MapAccess<'de> for RowDeserializer<'a> {
+    type Error = OdsError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, OdsError> {
+        match self.columns.get(self.next) {
+            Some((_, name)) => {
+                let name: StrDeserializer<'_, OdsError> = name.into_deserializer();
+                seed.deserialize(name).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, OdsError> {
+        let (col, _) = self.columns[self.next];
+        self.next += 1;
+
+        let cell = ValueDeserializer {
+            value: self.sheet.value(self.row, col),
+        };
+        seed.deserialize(cell).map_err(|e| {
+            self.cell_error = true;
+            OdsError::Ods(format!("{} {}", CellRef::local(self.row, col), e))
+        })
+    }
+}
+
+/// Deserializes a single cell.
+struct ValueDeserializer<'a> {
+    value: &'a Value,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    fn invalid<E: de::Expected>(&self, exp: &E) -> OdsError {
+        let unexp = match self.value {
+            Value::Empty => de::Unexpected::Unit,
+            Value::Boolean(v) => de::Unexpected::Bool(*v),
+            Value::Number(v) | Value::Percentage(v) | Value::Currency(_, v) => {
+                de::Unexpected::Float(*v)
+            }
+            Value::Text(v) => de::Unexpected::Str(v),
+            Value::TextXml(_) => de::Unexpected::Other("formatted text"),
+            Value::DateTime(_) => de::Unexpected::Other("date"),
+            Value::TimeDuration(_) => de::Unexpected::Other("duration"),
+        };
+        de::Error::invalid_type(unexp, exp)
+    }
+
+    fn integer(&self) -> Option<f64> {
+        match self.value {
+            Value::Number(v) | Value::Percentage(v) | Value::Currency(_, v) if v.fract() == 0.0 => {
+                Some(*v)
+            }
+            _ => None,
+        }
+    }
+}
+
+macro_rules! deserialize_int {
+    ($($f:ident)*) => {
+        $(
+            fn $f<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OdsError> {
+                match self.integer() {
+                    Some(v) if v < 0.0 => visitor.visit_i64(v as i64),
+                    Some(v) => visitor.visit_u64(v as u64),
+                    None => Err(self.invalid(&visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = OdsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OdsError> {
+        match self.value {
+            Value::Empty => visitor.visit_unit(),
+            Value::Boolean(v) => visitor.visit_bool(*v),
+            Value::Number(v) | Value::Percentage(v) | Value::Currency(_, v) => {
+                visitor.visit_f64(*v)
+            }
+            Value::Text(v) => visitor.visit_str(v),
+            Value::TextXml(_) => Err(self.invalid(&visitor)),
+            Value::DateTime(v) => visitor.visit_string(v.format(DATETIME_FORMAT).to_string()),
+            Value::TimeDuration(v) => visitor.visit_i64(v.num_milliseconds()),
+        }
+    }
+
+    deserialize_int! {
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OdsError> {
+        match self.value {
+            Value::Empty => visitor.visit_str(""),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OdsError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, OdsError> {
+        match self.value {
+            Value::Empty => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, OdsError> {
+        match (name, self.value) {
+            (DATETIME, Value::DateTime(v)) => {
+                visitor.visit_string(v.format(DATETIME_FORMAT).to_string())
+            }
+            (DATE, Value::DateTime(v)) => visitor.visit_string(v.format(DATE_FORMAT).to_string()),
+            (DURATION, Value::TimeDuration(v)) => visitor.visit_i64(v.num_milliseconds()),
+            (DECIMAL, Value::Number(v)) => visitor.visit_string(v.to_string()),
+            (DATETIME, _) | (DATE, _) | (DURATION, _) | (DECIMAL, _) => Err(self.invalid(&visitor)),
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OdsError> {
+        match self.value {
+            Value::Text(v) => {
+                let v: StrDeserializer<'_, OdsError> = v.as_str().into_deserializer();
+                visitor.visit_enum(v)
+            }
+            _ => Err(self.invalid(&visitor)),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
@@ -6,8 +6,9 @@
 
 use string_cache::DefaultAtom;
 
+use crate::format::ValueFormat;
 use crate::text::TextTag;
-use crate::CellRef;
+use crate::{CellRef, WorkBook};
 
 pub use crate::attrmap::*;
 use crate::sealed::Sealed;
@@ -61,6 +62,25 @@ impl Default for StyleFor {
     }
 }
 
+/// Value of the style:family attribute.
+pub(crate) fn family_name(family: StyleFor) -> &'static str {
+    match family {
+        StyleFor::Table => "table",
+        StyleFor::TableColumn => "table-column",
+        StyleFor::TableRow => "table-row",
+        StyleFor::TableCell => "table-cell",
+        StyleFor::Paragraph => "paragraph",
+        StyleFor::Graphic => "graphic",
+        StyleFor::None => "",
+    }
+}
+
+/// A default-style has no name in the file. It is stored with this
+/// name, which can't clash with a valid style name.
+pub(crate) fn default_style_name(family: StyleFor) -> String {
+    format!("default-style:{}", family_name(family))
+}
+
 /// Page layout.
 /// Contains all header and footer information.
 ///
@@ -91,7 +111,7 @@ impl Default for StyleFor {
 /// write_ods(&wb, "test_out/hf0.ods").unwrap();
 /// ```
 ///
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct PageLayout {
     name: String,
     master_page_name: String,
@@ -189,6 +209,59 @@ impl PageLayout {
         AttrMapIter::from(self.attr_map())
     }
 
+    /// Page size.
+    pub fn set_page_size(&mut self, width: Length, height: Length) {
+        self.set_attr("fo:page-width", width.to_string());
+        self.set_attr("fo:page-height", height.to_string());
+    }
+
+    /// Page width.
+    pub fn page_width(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:page-width")
+    }
+
+    /// Page height.
+    pub fn page_height(&self) -> Result<Option<Length>, AttrParseError> {
+        parse_attr(self, "fo:page-height")
+    }
+
+    /// Width within the page margins. Percentages are relative to the
+    /// page width. None if there is no page width.
+    pub fn content_width(&self) -> Result<Option<Length>, AttrParseError> {
+        match self.page_width()? {
+            Some(width) => Ok(Some(
+                width
+                    - self.page_margin("fo:margin-left", width)?
+                    - self.page_margin("fo:margin-right", width)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Height within the page margins, the header and footer are placed
+    /// in there too. Percentages are relative to the page width. None if
+    /// there is no page size.
+    pub fn content_height(&self) -> Result<Option<Length>, AttrParseError> {
+        match (self.page_width()?, self.page_height()?) {
+            (Some(width), Some(height)) => Ok(Some(
+                height
+                    - self.page_margin("fo:margin-top", width)?
+                    - self.page_margin("fo:margin-bottom", width)?,
+            )),
+            _ => Ok(None),
+        }
+    }
+
+    /// One side of the margin, fo:margin if it is not set.
+    fn page_margin(&self, name: &str, width: Length) -> Result<Length, AttrParseError> {
+        let side = parse_attr_percent_of(self, name, width)?;
+        let margin = match side {
+            Some(side) => Some(side),
+            None => parse_attr_percent_of(self, "fo:margin", width)?,
+        };
+        Ok(margin.unwrap_or(Length::Pt(0.0)))
+    }
+
     /// Left side header.
     pub fn set_header(&mut self, header: HeaderFooter) {
         self.header = header;
@@ -270,7 +343,7 @@ impl PageLayout {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct HeaderFooterAttr {
     attr: AttrMapType,
 }
@@ -316,7 +389,7 @@ impl<'a> IntoIterator for &'a HeaderFooterAttr {
 /// Can be seen as three regions left/center/right or as one region.
 /// In the first case region* contains the data, in the second it's content.
 /// Each is a TextTag of parsed XML-tags.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct HeaderFooter {
     display: bool,
 
@@ -451,7 +524,7 @@ impl HeaderFooter {
 }
 
 /// Font declarations.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct FontFaceDecl {
     name: String,
     /// From where did we get this style.
@@ -553,7 +626,7 @@ impl FontFaceDecl {
 ///
 /// Styles can also link to a parent style and to a pagelayout.
 ///
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Style {
     /// Style name.
     name: String,
@@ -636,6 +709,14 @@ impl Style {
         Style::new_with_name(StyleFor::Table, name, value_style)
     }
 
+    /// Creates a new graphic style for drawing shapes.
+    pub fn new_graphic_style<S: Into<String>>(name: S) -> Self {
+        let mut style = Style::new();
+        style.set_name(name);
+        style.set_family(StyleFor::Graphic);
+        style
+    }
+
     /// New, with name.
     /// value_style references a ValueFormat.
     pub fn new_with_name<S: Into<String>, T: Into<String>>(
@@ -869,6 +950,209 @@ impl Style {
     }
 }
 
+/// Formatting of a cell after following the style inheritance.
+///
+/// The attributes of the table-cell default-style, the parent styles and
+/// the style itself are merged, the nearest style wins. Setting a
+/// shorthand like "fo:border" replaces the single sides set by a parent.
+/// Stylemaps are not evaluated.
+#[derive(Clone, Debug, Default)]
+pub struct ResolvedStyle<'a> {
+    name: Option<String>,
+    cell: TableCellAttr,
+    paragraph: ParagraphAttr,
+    text: TextAttr,
+    value_format: Option<&'a ValueFormat>,
+}
+
+impl<'a> ResolvedStyle<'a> {
+    /// Resolves the named cell style. Without a name only the
+    /// default-style applies.
+    pub(crate) fn resolve(book: &'a WorkBook, name: Option<&str>) -> Self {
+        let mut chain: Vec<&Style> = Vec::new();
+        let mut next = name;
+        while let Some(name) = next {
+            match book.style(name) {
+                Some(style) if !chain.iter().any(|s| s.name() == style.name()) => {
+                    chain.push(style);
+                    next = style.parent().map(|v| v.as_str());
+                }
+                _ => break,
+            }
+        }
+        chain.extend(book.default_style(StyleFor::TableCell));
+
+        let mut resolved = ResolvedStyle {
+            name: name.filter(|v| book.style(v).is_some()).map(String::from),
+            ..Default::default()
+        };
+        for style in chain.iter().rev() {
+            if style.family == StyleFor::TableCell {
+                merge_attr(&mut resolved.cell, &style.table_cell_attr);
+            }
+            merge_paragraph(&mut resolved.paragraph, &style.paragraph_attr);
+            merge_attr(&mut resolved.text, &style.text_attr);
+        }
+        resolved.value_format = chain
+            .iter()
+            .filter_map(|v| v.value_format())
+            .find_map(|v| book.format(v));
+
+        resolved
+    }
+
+    /// Sets the direct formatting of the cell on top.
+    pub(crate) fn apply_format(&mut self, format: &CellFormat) {
+        merge_attr(&mut self.cell, &format.cell);
+        merge_paragraph(&mut self.paragraph, &format.paragraph);
+        merge_attr(&mut self.text, &format.text);
+    }
+
+    /// The name of the cell style, if it exists.
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    /// Merged table-cell attributes.
+    pub fn cell(&self) -> &TableCellAttr {
+        &self.cell
+    }
+
+    /// Merged paragraph attributes.
+    pub fn paragraph(&self) -> &ParagraphAttr {
+        &self.paragraph
+    }
+
+    /// Merged text attributes.
+    pub fn text(&self) -> &TextAttr {
+        &self.text
+    }
+
+    /// The value format of the nearest style that has one.
+    pub fn value_format(&self) -> Option<&'a ValueFormat> {
+        self.value_format
+    }
+}
+
+/// Formatting set directly on a cell, without a named style.
+///
+/// When writing, all cells with the same style and the same CellFormat
+/// share one generated automatic style "ce1", "ce2", ...
+///
+/// ```
+/// use spreadsheet_ods::{Sheet, WorkBook};
+/// use spreadsheet_ods::style::AttrText;
+///
+/// let mut sheet = Sheet::new();
+/// sheet.set_value(0, 0, "Total");
+/// sheet.cell_format_mut(0, 0).text_mut().set_font_bold();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CellFormat {
+    cell: TableCellAttr,
+    paragraph: ParagraphAttr,
+    text: TextAttr,
+}
+
+impl CellFormat {
+    /// New, without attributes.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Table-cell attributes.
+    pub fn cell(&self) -> &TableCellAttr {
+        &self.cell
+    }
+
+    /// Table-cell attributes.
+    pub fn cell_mut(&mut self) -> &mut TableCellAttr {
+        &mut self.cell
+    }
+
+    /// Paragraph attributes.
+    pub fn paragraph(&self) -> &ParagraphAttr {
+        &self.paragraph
+    }
+
+    /// Paragraph attributes.
+    pub fn paragraph_mut(&mut self) -> &mut ParagraphAttr {
+        &mut self.paragraph
+    }
+
+    /// Text attributes.
+    pub fn text(&self) -> &TextAttr {
+        &self.text
+    }
+
+    /// Text attributes.
+    pub fn text_mut(&mut self) -> &mut TextAttr {
+        &mut self.text
+    }
+
+    /// Sets the attributes on top of the style.
+    pub(crate) fn apply_to(&self, style: &mut Style) {
+        merge_attr(&mut style.table_cell_attr, &self.cell);
+        merge_paragraph(&mut style.paragraph_attr, &self.paragraph);
+        merge_attr(&mut style.text_attr, &self.text);
+    }
+}
+
+/// Shorthand attributes and the attributes they replace.
+const SHORTHANDS: [(&str, [&str; 4]); 3] = [
+    (
+        "fo:border",
+        [
+            "fo:border-top",
+            "fo:border-right",
+            "fo:border-bottom",
+            "fo:border-left",
+        ],
+    ),
+    (
+        "fo:padding",
+        [
+            "fo:padding-top",
+            "fo:padding-right",
+            "fo:padding-bottom",
+            "fo:padding-left",
+        ],
+    ),
+    (
+        "fo:margin",
+        [
+            "fo:margin-top",
+            "fo:margin-right",
+            "fo:margin-bottom",
+            "fo:margin-left",
+        ],
+    ),
+];
+
+/// Copies the attributes and the tabstops, if there are any.
+fn merge_paragraph(target: &mut ParagraphAttr, source: &ParagraphAttr) {
+    merge_attr(target, source);
+    if let Some(tabstops) = &source.tabstops {
+        target.tabstops = Some(tabstops.clone());
+    }
+}
+
+/// Copies all attributes, a shorthand clears the attributes it covers.
+fn merge_attr<T: AttrMap>(target: &mut T, source: &T) {
+    if let Some(attr) = source.attr_map() {
+        for (shorthand, sides) in SHORTHANDS.iter() {
+            if attr.contains_key(&DefaultAtom::from(*shorthand)) {
+                for side in sides.iter() {
+                    target.clear_attr(side);
+                }
+            }
+        }
+        for (name, value) in attr.iter() {
+            target.set_attr(name.as_ref(), value.clone());
+        }
+    }
+}
+
 /// One style mapping.
 ///
 /// The rules for this are not very clear. It writes the necessary data fine,
@@ -879,7 +1163,7 @@ impl Style {
 ///
 /// TODO: clarify all of this.
 ///
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct StyleMap {
     condition: String,
     applied_style: String,
@@ -925,7 +1209,7 @@ impl StyleMap {
 }
 
 /// Style for the whole table.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct TableAttr {
     attr: AttrMapType,
 }
@@ -963,8 +1247,10 @@ impl AttrStyleShadow for TableAttr {}
 
 impl AttrStyleWritingMode for TableAttr {}
 
+impl AttrTable for TableAttr {}
+
 /// Styles for table rows.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct TableRowAttr {
     attr: AttrMapType,
 }
@@ -999,7 +1285,7 @@ impl AttrFoKeepTogether for TableRowAttr {}
 impl AttrTableRow for TableRowAttr {}
 
 /// Styles for table columns.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct TableColAttr {
     attr: AttrMapType,
 }
@@ -1030,7 +1316,7 @@ impl AttrFoBreak for TableColAttr {}
 impl AttrTableCol for TableColAttr {}
 
 /// Styles for table cells.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct TableCellAttr {
     attr: AttrMapType,
 }
@@ -1094,7 +1380,7 @@ impl Default for TabStopType {
 }
 
 /// Tabstops are part of a paragraph style.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct TabStop {
     attr: AttrMapType,
 }
@@ -1174,7 +1460,7 @@ impl<'a> IntoIterator for &'a TabStop {
 }
 
 /// Paragraph style.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ParagraphAttr {
     attr: AttrMapType,
     tabstops: Option<Vec<TabStop>>,
@@ -1235,7 +1521,7 @@ impl AttrStyleWritingMode for ParagraphAttr {}
 impl AttrParagraph for ParagraphAttr {}
 
 /// Text styles.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct TextAttr {
     attr: AttrMapType,
 }
@@ -1265,7 +1551,8 @@ impl AttrFoBackgroundColor for TextAttr {}
 
 impl AttrText for TextAttr {}
 
-#[derive(Clone, Debug, Default)]
+/// Graphic styles for drawing shapes.
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct GraphicAttr {
     attr: AttrMapType,
 }
@@ -1290,3 +1577,7 @@ impl<'a> IntoIterator for &'a GraphicAttr {
         AttrMapIter::from(self.attr_map())
     }
 }
+
+impl AttrFoPadding for GraphicAttr {}
+
+impl AttrGraphic for GraphicAttr {}
@@ -0,0 +1,211 @@
+//!
+//! Automatic styles for the direct formatting of cells and for the
+//! column widths and row heights of the sheets.
+//!
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::attrmap::{AttrTableCol, AttrTableRow};
+use crate::style::{CellFormat, Style, StyleFor, StyleUse};
+use crate::{ucell, RowColHeader, WorkBook};
+
+/// Creates one automatic style for each distinct pair of base style and
+/// format, and sets it as the cell style.
+///
+/// Automatic styles can't be parents, so an automatic base style is
+/// copied. Otherwise the new style inherits from the base style, or from
+/// "Default" if there is none.
+pub(crate) fn apply_cell_formats(book: &mut WorkBook) {
+    let mut pool: Vec<(Option<String>, CellFormat, String)> = Vec::new();
+    let mut next = 1;
+
+    let styles = &mut book.styles;
+    let def_styles = &book.def_styles;
+    for sheet in book.sheets.iter_mut() {
+        for cell in sheet.data.values_mut() {
+            let format = match cell.format.take() {
+                Some(format) => *format,
+                None => continue,
+            };
+            let base = cell
+                .style
+                .clone()
+                .or_else(|| def_styles.get(&cell.value.value_type()).cloned());
+
+            let pooled = pool
+                .iter()
+                .find(|(b, f, _)| *b == base && *f == format)
+                .map(|(_, _, name)| name.clone());
+            let name = match pooled {
+                Some(name) => name,
+                None => {
+                    let name = loop {
+                        let name = format!("ce{}", next);
+                        next += 1;
+                        if !styles.contains_key(&name) {
+                            break name;
+                        }
+                    };
+
+                    let mut style = match base.as_ref().and_then(|v| styles.get(v)) {
+                        Some(base) if base.styleuse() == StyleUse::Automatic => base.clone(),
+                        Some(base) => {
+                            let mut style = Style::new();
+                            style.set_family(StyleFor::TableCell);
+                            style.set_parent(base.name().as_str());
+                            if let Some(value_format) = base.value_format() {
+                                style.set_value_format(value_format.as_str());
+                            }
+                            style
+                        }
+                        None => {
+                            let mut style = Style::new();
+                            style.set_family(StyleFor::TableCell);
+                            style.set_parent("Default");
+                            style
+                        }
+                    };
+                    style.set_name(name.as_str());
+                    style.set_origin(Default::default());
+                    style.set_styleuse(StyleUse::Automatic);
+                    format.apply_to(&mut style);
+                    styles.insert(name.clone(), style);
+
+                    pool.push((base, format, name.clone()));
+                    name
+                }
+            };
+            cell.style = Some(name);
+        }
+    }
+}
+
+/// Key for the styles of apply_dimensions().
+#[derive(PartialEq)]
+struct DimensionKey {
+    family: StyleFor,
+    base: Option<String>,
+    size: String,
+    optimal: bool,
+}
+
+/// Creates one automatic style for each distinct combination of base
+/// style, size and use-optimal flag, and sets it as the column or row
+/// style.
+pub(crate) fn apply_dimensions(book: &mut WorkBook) {
+    let mut pool: Vec<(DimensionKey, String)> = Vec::new();
+    let mut next_col = 1;
+    let mut next_row = 1;
+
+    let styles = &mut book.styles;
+    for sheet in book.sheets.iter_mut() {
+        dimension_styles(
+            styles,
+            &mut pool,
+            StyleFor::TableColumn,
+            &mut sheet.col_header,
+            &mut next_col,
+        );
+        dimension_styles(
+            styles,
+            &mut pool,
+            StyleFor::TableRow,
+            &mut sheet.row_header,
+            &mut next_row,
+        );
+    }
+}
+
+fn dimension_styles(
+    styles: &mut HashMap<String, Style>,
+    pool: &mut Vec<(DimensionKey, String)>,
+    family: StyleFor,
+    headers: &mut BTreeMap<ucell, RowColHeader>,
+    next: &mut u32,
+) {
+    for header in headers.values_mut() {
+        let size = match header.size() {
+            Some(size) => size,
+            None => continue,
+        };
+        let optimal = header.use_optimal_size();
+        let key = DimensionKey {
+            family,
+            base: header.style().cloned(),
+            size: size.to_string(),
+            optimal,
+        };
+
+        let pooled = pool
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, name)| name.clone());
+        let name = match pooled {
+            Some(name) => name,
+            None => {
+                let prefix = if family == StyleFor::TableColumn {
+                    "co"
+                } else {
+                    "ro"
+                };
+                let name = loop {
+                    let name = format!("{}{}", prefix, next);
+                    *next += 1;
+                    if !styles.contains_key(&name) {
+                        break name;
+                    }
+                };
+
+                let mut style = match key.base.as_ref().and_then(|v| styles.get(v)) {
+                    Some(base) if base.family() == family => base.clone(),
+                    _ => {
+                        let mut style = Style::new();
+                        style.set_family(family);
+                        style
+                    }
+                };
+                style.set_name(name.as_str());
+                style.set_origin(Default::default());
+                style.set_styleuse(StyleUse::Automatic);
+                if family == StyleFor::TableColumn {
+                    style.col_mut().set_col_width(size);
+                    style.col_mut().set_use_optimal_col_width(optimal);
+                } else {
+                    style.row_mut().set_row_height(size);
+                    style.row_mut().set_use_optimal_row_height(optimal);
+                }
+                styles.insert(name.clone(), style);
+
+                pool.push((key, name.clone()));
+                name
+            }
+        };
+        header.set_style(name);
+        header.clear_size();
+    }
+}
+
+/// The workbook with automatic styles instead of direct formatting and
+/// column and row sizes. Only clones if there is something to do.
+pub(crate) fn with_automatic_styles(book: &WorkBook) -> Cow<'_, WorkBook> {
+    let has_formats = book
+        .sheets
+        .iter()
+        .any(|sheet| sheet.data.values().any(|cell| cell.format.is_some()));
+    let has_sizes = book.sheets.iter().any(|sheet| {
+        sheet
+            .col_header
+            .values()
+            .chain(sheet.row_header.values())
+            .any(|header| header.size().is_some())
+    });
+    if has_formats || has_sizes {
+        let mut book = book.clone();
+        apply_cell_formats(&mut book);
+        apply_dimensions(&mut book);
+        Cow::Owned(book)
+    } else {
+        Cow::Borrowed(book)
+    }
+}
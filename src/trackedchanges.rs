@@ -0,0 +1,182 @@
+//!
+//! Tracked changes.
+//!
+//! When a spreadsheet is read, the table:tracked-changes are parsed into
+//! a list of TrackedChange. The original XML is still preserved and
+//! written back unchanged, unless the changes are accepted or rejected
+//! via WorkBook::accept_all_changes() or WorkBook::reject_all_changes().
+//!
+//! ```no_run
+//! use spreadsheet_ods::read_ods;
+//!
+//! let mut wb = read_ods("tests/tracked.ods").unwrap();
+//! for change in wb.tracked_changes() {
+//!     println!("{} {:?} {:?} {}", change.id(), change.change_type(), change.author(), change.range());
+//! }
+//! wb.accept_all_changes();
+//! ```
+//!
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+use crate::{CellRange, CellRef, OdsError, Value};
+
+/// Acceptance state of a change.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ChangeState {
+    #[default]
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+impl FromStr for ChangeState {
+    type Err = OdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ChangeState::Pending),
+            "accepted" => Ok(ChangeState::Accepted),
+            "rejected" => Ok(ChangeState::Rejected),
+            _ => Err(OdsError::Ods(format!(
+                "Unknown value for table:acceptance-state {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for ChangeState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            ChangeState::Pending => write!(f, "pending"),
+            ChangeState::Accepted => write!(f, "accepted"),
+            ChangeState::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// Kind of change.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ChangeType {
+    /// table:cell-content-change
+    CellContent,
+    /// table:insertion of rows.
+    InsertRows,
+    /// table:insertion of columns.
+    InsertColumns,
+    /// table:insertion of a table.
+    InsertTable,
+    /// table:deletion of rows.
+    DeleteRows,
+    /// table:deletion of columns.
+    DeleteColumns,
+    /// table:deletion of a table. Only the position and the cell
+    /// contents are recorded, the name of the table is lost.
+    DeleteTable,
+    /// table:movement
+    Movement,
+}
+
+/// One tracked change.
+///
+/// The affected range for row and column insertions/deletions spans
+/// the whole row/column, which means up to ucell::MAX.
+#[derive(Debug, Clone)]
+pub struct TrackedChange {
+    pub(crate) id: String,
+    pub(crate) change_type: ChangeType,
+    pub(crate) state: ChangeState,
+    pub(crate) author: Option<String>,
+    pub(crate) date: Option<NaiveDateTime>,
+    pub(crate) comment: Option<String>,
+    /// Index of the affected table.
+    pub(crate) table: usize,
+    pub(crate) range: CellRange,
+    pub(crate) source: Option<CellRange>,
+    pub(crate) previous: Option<Value>,
+    pub(crate) previous_formula: Option<String>,
+    pub(crate) deleted: Vec<(CellRef, Value)>,
+}
+
+impl TrackedChange {
+    pub(crate) fn new<S: Into<String>>(id: S, change_type: ChangeType) -> Self {
+        Self {
+            id: id.into(),
+            change_type,
+            state: Default::default(),
+            author: None,
+            date: None,
+            comment: None,
+            table: 0,
+            range: CellRange::new(),
+            source: None,
+            previous: None,
+            previous_formula: None,
+            deleted: vec![],
+        }
+    }
+
+    /// Change id.
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    /// Kind of change.
+    pub fn change_type(&self) -> ChangeType {
+        self.change_type
+    }
+
+    /// Acceptance state.
+    pub fn state(&self) -> ChangeState {
+        self.state
+    }
+
+    /// Author of the change.
+    pub fn author(&self) -> Option<&String> {
+        self.author.as_ref()
+    }
+
+    /// Timestamp of the change.
+    pub fn date(&self) -> Option<&NaiveDateTime> {
+        self.date.as_ref()
+    }
+
+    /// Comment for the change.
+    pub fn comment(&self) -> Option<&String> {
+        self.comment.as_ref()
+    }
+
+    /// Index of the affected table.
+    pub fn table(&self) -> usize {
+        self.table
+    }
+
+    /// Affected range. For a movement this is the target.
+    pub fn range(&self) -> &CellRange {
+        &self.range
+    }
+
+    /// Source range of a movement.
+    pub fn source(&self) -> Option<&CellRange> {
+        self.source.as_ref()
+    }
+
+    /// Value before a cell content change.
+    pub fn previous(&self) -> Option<&Value> {
+        self.previous.as_ref()
+    }
+
+    /// Formula before a cell content change.
+    pub fn previous_formula(&self) -> Option<&String> {
+        self.previous_formula.as_ref()
+    }
+
+    /// Cell contents that where removed by a deletion.
+    pub fn deleted(&self) -> &Vec<(CellRef, Value)> {
+        &self.deleted
+    }
+}
@@ -49,7 +49,7 @@ use std::collections::HashMap;
 use string_cache::DefaultAtom;
 
 /// Defines a XML tag and it's children.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct XmlTag {
     name: String,
     attr: AttrMapType,
@@ -135,10 +135,51 @@ impl XmlTag {
     pub fn content(&self) -> &Vec<XmlContent> {
         &self.content
     }
+
+    /// Returns the content vec.
+    pub fn content_mut(&mut self) -> &mut Vec<XmlContent> {
+        &mut self.content
+    }
+
+    /// Returns the plain text of this tag and all child elements.
+    /// Paragraphs and line-breaks are separated by a newline, text:s
+    /// and text:tab are replaced by spaces and tabs.
+    pub fn text_content(&self) -> String {
+        let mut buf = String::new();
+        self.push_text_content(&mut buf);
+        buf
+    }
+
+    fn push_text_content(&self, buf: &mut String) {
+        match self.name.as_str() {
+            "text:s" => {
+                let count = self
+                    .attr("text:c")
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(1);
+                buf.push_str(&" ".repeat(count));
+            }
+            "text:tab" => buf.push('\t'),
+            "text:line-break" => buf.push('\n'),
+            _ => {
+                for c in &self.content {
+                    match c {
+                        XmlContent::Text(t) => buf.push_str(t),
+                        XmlContent::Tag(t) => {
+                            if t.name == "text:p" && !buf.is_empty() {
+                                buf.push('\n');
+                            }
+                            t.push_text_content(buf);
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 /// Values of the content vec.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum XmlContent {
     Text(String),
     Tag(XmlTag),
@@ -0,0 +1,68 @@
+use color::Rgb;
+
+use spreadsheet_ods::style::{
+    Angle, AttrFoBackgroundColor, AttrFoBorder, AttrFoMargin, AttrMap, AttrStyleShadow,
+    AttrTableCell, AttrText, Border, CellAlignVertical, Length, TextWeight, WrapOption,
+};
+use spreadsheet_ods::{OdsError, Style};
+
+#[test]
+fn typed_getters() -> Result<(), OdsError> {
+    let mut st = Style::new_cell_style("c", "");
+    let cell = st.cell_mut();
+    cell.set_border(Length::Pt(0.5), Border::Solid, Rgb::new(255, 0, 0));
+    cell.set_background_color(Rgb::new(0, 128, 0));
+    cell.set_wrap_option(WrapOption::Wrap);
+    cell.set_rotation_angle(Angle::Deg(45.0));
+    cell.set_vertical_align(CellAlignVertical::Middle);
+    cell.set_shadow(Length::Mm(1.0), Length::Mm(2.0), None, Rgb::new(0, 0, 0));
+    st.paragraph_mut().set_margin(Length::Cm(1.5));
+    let text = st.text_mut();
+    text.set_font_bold();
+    text.set_font_size(Length::Pt(12.0));
+
+    let cell = st.cell();
+    assert_eq!(
+        cell.border()?,
+        Some((Length::Pt(0.5), Border::Solid, Rgb::new(255, 0, 0)))
+    );
+    assert_eq!(cell.border_bottom()?, None);
+    assert_eq!(cell.background_color()?, Some(Rgb::new(0, 128, 0)));
+    assert_eq!(cell.wrap_option()?, Some(WrapOption::Wrap));
+    assert_eq!(cell.rotation_angle()?, Some(Angle::Deg(45.0)));
+    assert_eq!(cell.vertical_align()?, Some(CellAlignVertical::Middle));
+    assert_eq!(
+        cell.shadow()?,
+        Some((Length::Mm(1.0), Length::Mm(2.0), None, Rgb::new(0, 0, 0)))
+    );
+
+    assert_eq!(st.paragraph().margin()?, Some(Length::Cm(1.5)));
+
+    let text = st.text();
+    assert_eq!(text.font_weight()?, Some(TextWeight::Bold));
+    assert_eq!(text.font_size()?, Some(Length::Pt(12.0)));
+    assert_eq!(text.font_size_percent()?, None);
+
+    Ok(())
+}
+
+#[test]
+fn typed_getters_invalid() {
+    let mut st = Style::new_cell_style("c", "");
+    st.cell_mut()
+        .set_attr("style:vertical-align", "sideways".to_string());
+    st.cell_mut().set_attr("fo:border", "thick".to_string());
+    st.cell_mut()
+        .set_attr("fo:background-color", "transparent".to_string());
+    st.text_mut().set_attr("fo:font-size", "12ex".to_string());
+
+    let err = st.cell().vertical_align().unwrap_err();
+    assert_eq!(err.attr(), "style:vertical-align");
+    assert_eq!(err.value(), "sideways");
+    assert!(st.cell().border().is_err());
+    assert_eq!(st.cell().background_color(), Ok(None));
+    assert!(st.text().font_size().is_err());
+
+    let err: OdsError = st.text().font_size().unwrap_err().into();
+    assert!(err.to_string().contains("fo:font-size"));
+}
@@ -6,7 +6,7 @@ use spreadsheet_ods::style::{
     AttrStyleDynamicSpacing, AttrStyleShadow, AttrSvgHeight, PageLayout,
 };
 use spreadsheet_ods::style::{
-    AttrFoBreak, AttrFoKeepWithNext, AttrFontDecl, AttrParagraph, AttrStyleWritingMode,
+    AttrFoBreak, AttrFoKeepWithNext, AttrFontDecl, AttrParagraph, AttrStyleWritingMode, AttrTable,
     AttrTableCell, AttrTableCol, AttrTableRow, AttrText, Border, CellAlignVertical, FontFaceDecl,
     FontPitch, PageBreak, ParaAlignVertical, RotationAlign, TextAlignSource, TextKeep,
     TextPosition, TextRelief, TextTransform, TextWeight, WrapOption, WritingMode,
@@ -106,6 +106,26 @@ fn test_attr3() {
         st.table().attr("style:writing-mode"),
         Some(&"tb-lr".to_string())
     );
+    assert_eq!(st.table().writing_mode(), Some(WritingMode::TbLr));
+
+    st.table_mut().set_tab_color(Rgb::new(255, 128, 0));
+    assert_eq!(
+        st.table().attr("tableooo:tab-color"),
+        Some(&"#ff8000".to_string())
+    );
+    assert_eq!(st.table().tab_color(), Some(Rgb::new(255, 128, 0)));
+
+    assert!(st.table().display());
+    st.table_mut().set_display(false);
+    assert!(!st.table().display());
+
+    assert!(st.table().may_break_between_rows());
+    st.table_mut().set_may_break_between_rows(false);
+    assert_eq!(
+        st.table().attr("style:may-break-between-rows"),
+        Some(&"false".to_string())
+    );
+    assert!(!st.table().may_break_between_rows());
 
     let mut st = Style::new_col_style("c01", "f00");
 
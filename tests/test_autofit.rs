@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+
+use spreadsheet_ods::format::create_date_dmy_format;
+use spreadsheet_ods::style::{AttrTableCell, AttrTableCol, AttrTableRow, AttrText, WrapOption};
+use spreadsheet_ods::{
+    deg, pt, read_ods, write_ods, Angle, Length, OdsError, Sheet, Style, WorkBook,
+};
+
+#[test]
+fn autofit_columns() {
+    let mut wb = WorkBook::new();
+    let mut bold = Style::new_cell_style("bold", "");
+    bold.text_mut().set_font_bold();
+    wb.add_style(bold);
+    let mut big = Style::new_cell_style("big", "");
+    big.text_mut().set_font_size(pt!(20));
+    wb.add_style(big);
+    let mut mono = Style::new_cell_style("mono", "");
+    let font = wb.font_for("Liberation Mono");
+    mono.text_mut().set_font_name(font);
+    wb.add_style(mono);
+    wb.add_format(create_date_dmy_format("dmy"));
+    wb.add_style(Style::new_cell_style("date", "dmy"));
+
+    let mut sh = Sheet::new();
+    sh.set_value(0, 0, "iiii");
+    sh.set_value(1, 0, "short");
+    sh.set_value(0, 1, "a much longer text");
+    sh.set_styled_value(0, 2, "a much longer text", "bold");
+    sh.set_styled_value(0, 3, "a much longer text", "big");
+    sh.set_styled_value(0, 4, "iiii", "mono");
+    sh.set_styled_value(0, 5, NaiveDate::from_ymd(2021, 3, 1), "date");
+    sh.set_value(0, 7, "spans over two columns");
+    sh.set_col_span(0, 7, 2);
+    sh.set_col_width(9, Length::Cm(5.0));
+
+    sh.autofit_columns(&wb, 0..10);
+
+    let w: Vec<Option<Length>> = (0..10).map(|c| sh.col_width(&wb, c)).collect();
+    assert!(w[1].unwrap() > w[0].unwrap());
+    assert!(w[2].unwrap() > w[1].unwrap());
+    assert!(w[3].unwrap() > w[2].unwrap());
+    assert!(w[4].unwrap() > w[0].unwrap());
+    // "01.03.2021"
+    assert!(w[5].unwrap() > Length::Cm(1.3) && w[5].unwrap() < Length::Cm(2.0));
+    assert_eq!(w[6], None);
+    assert_eq!(w[7], None);
+    assert_eq!(w[9], Some(Length::Cm(5.0)));
+}
+
+#[test]
+fn autofit_rows() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut wrap = Style::new_cell_style("wrap", "");
+    wrap.cell_mut().set_wrap_option(WrapOption::Wrap);
+    wb.add_style(wrap);
+    let mut rotated = Style::new_cell_style("rotated", "");
+    rotated.cell_mut().set_rotation_angle(deg!(90));
+    wb.add_style(rotated);
+
+    let mut sh = Sheet::new();
+    sh.set_value(0, 0, "one line");
+    sh.set_styled_value(1, 0, "some words that need a few lines to fit", "wrap");
+    sh.set_styled_value(2, 0, "rotated text", "rotated");
+    sh.set_value(3, 0, "two\nlines");
+
+    sh.autofit_columns(&wb, 0..1);
+    let width = sh.col_width(&wb, 0).unwrap();
+    // the longest word, not the whole text
+    assert!(width < Length::Cm(1.5));
+
+    sh.autofit_rows(&wb, 0..5);
+    let h: Vec<Option<Length>> = (0..5).map(|r| sh.row_height(&wb, r)).collect();
+    assert_eq!(h[0], Some(Length::Cm(0.452)));
+    assert!(h[1].unwrap() > Length::Cm(1.0));
+    assert!(h[2].unwrap() > Length::Cm(1.5));
+    assert!(h[3].unwrap() > h[0].unwrap());
+    assert_eq!(h[4], None);
+
+    wb.push_sheet(sh);
+    write_ods(&wb, "test_out/test_autofit.ods")?;
+    let wb = read_ods("test_out/test_autofit.ods")?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.row_height(&wb, 0), Some(Length::Cm(0.452)));
+    let style = wb.style(sh.row_style(0).unwrap()).unwrap();
+    assert_eq!(style.row().use_optimal_row_height(), Ok(Some(true)));
+    let style = wb.style(sh.column_style(0).unwrap()).unwrap();
+    assert_eq!(style.col().use_optimal_col_width(), Ok(Some(true)));
+
+    Ok(())
+}
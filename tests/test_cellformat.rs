@@ -0,0 +1,118 @@
+use chrono::NaiveDate;
+use color::Rgb;
+
+use spreadsheet_ods::format::create_date_dmy_format;
+use spreadsheet_ods::style::{
+    AttrFoBackgroundColor, AttrFontDecl, AttrMap, AttrText, FontFaceDecl, StyleUse,
+};
+use spreadsheet_ods::{read_ods, write_ods, OdsError, Sheet, Style, WorkBook};
+
+fn book() -> WorkBook {
+    let mut wb = WorkBook::new();
+
+    let mut style = Style::new_cell_style("Heading", "");
+    style.set_styleuse(StyleUse::Named);
+    style.text_mut().set_color(Rgb::new(0, 0, 128));
+    wb.add_common_style(style);
+    wb.add_format(create_date_dmy_format("date_dmy"));
+    wb.add_style(Style::new_cell_style("date", "date_dmy"));
+
+    let mut sh = Sheet::new_with_name("One");
+    sh.set_value(0, 0, "a");
+    sh.cell_format_mut(0, 0).text_mut().set_font_bold();
+    sh.set_value(0, 1, "b");
+    sh.cell_format_mut(0, 1).text_mut().set_font_bold();
+    sh.set_value(0, 2, "c");
+    sh.cell_format_mut(0, 2)
+        .cell_mut()
+        .set_background_color(Rgb::new(255, 255, 0));
+    sh.set_styled_value(1, 0, "Title", "Heading");
+    sh.cell_format_mut(1, 0).text_mut().set_font_bold();
+    sh.set_styled_value(2, 0, NaiveDate::from_ymd(2021, 3, 1), "date");
+    sh.cell_format_mut(2, 0).text_mut().set_font_bold();
+    wb.push_sheet(sh);
+
+    wb
+}
+
+#[test]
+fn cell_format() {
+    let wb = book();
+
+    let st = wb.effective_cell_style(0, 1, 0);
+    assert_eq!(st.name().unwrap(), "Heading");
+    assert_eq!(st.text().attr("fo:font-weight").unwrap(), "bold");
+    assert_eq!(st.text().attr("fo:color").unwrap(), "#000080");
+
+    let mut wb = wb;
+    wb.apply_cell_formats();
+    let sh = wb.sheet(0);
+    assert!(sh.cell_format(0, 0).is_none());
+    assert_eq!(sh.cell_style(0, 0), sh.cell_style(0, 1));
+    assert_ne!(sh.cell_style(0, 0), sh.cell_style(0, 2));
+    assert!(sh.cell_style(0, 0).unwrap().starts_with("ce"));
+
+    let st = wb.style(sh.cell_style(1, 0).unwrap()).unwrap();
+    assert_eq!(st.parent().unwrap(), "Heading");
+    assert_eq!(st.styleuse(), StyleUse::Automatic);
+
+    // an automatic style is copied, not used as parent
+    let st = wb.style(sh.cell_style(2, 0).unwrap()).unwrap();
+    assert_eq!(st.parent().unwrap(), "Default");
+    assert_eq!(st.value_format().unwrap(), "date_dmy");
+    assert_eq!(st.text().attr("fo:font-weight").unwrap(), "bold");
+}
+
+#[test]
+fn write_cell_format() -> Result<(), OdsError> {
+    let wb = book();
+    write_ods(&wb, "test_out/test_cellformat.ods")?;
+
+    // unchanged
+    assert!(wb.sheet(0).cell_format(0, 0).is_some());
+    assert!(wb.sheet(0).cell_style(0, 0).is_none());
+
+    let wb = read_ods("test_out/test_cellformat.ods")?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.cell_style(0, 0), sh.cell_style(0, 1));
+    let st = wb.style(sh.cell_style(0, 0).unwrap()).unwrap();
+    assert_eq!(st.text().attr("fo:font-weight").unwrap(), "bold");
+    let st = wb.style(sh.cell_style(0, 2).unwrap()).unwrap();
+    assert_eq!(st.cell().attr("fo:background-color").unwrap(), "#ffff00");
+
+    Ok(())
+}
+
+#[test]
+fn cell_format_fonts() {
+    let mut wb = WorkBook::new();
+    let font = wb.font_for("Liberation Serif");
+    let mut sh = Sheet::new_with_name("One");
+    sh.set_value(0, 0, "a");
+    sh.cell_format_mut(0, 0).text_mut().set_font_name(font);
+    sh.cell_format_mut(0, 1).text_mut().set_font_name("Missing");
+    wb.push_sheet(sh);
+
+    assert_eq!(wb.missing_fonts(), vec!["Missing".to_string()]);
+    wb.remove_unused_styles();
+    assert!(wb.font("Liberation Serif").is_some());
+
+    // a different font with the same name
+    let mut other = WorkBook::new();
+    let mut font = FontFaceDecl::new_with_name("Liberation Serif");
+    font.set_font_family("Times");
+    other.add_font(font);
+    let idx = other.import_sheet(&wb, 0);
+    let name = other
+        .sheet(idx)
+        .cell_format(0, 0)
+        .unwrap()
+        .text()
+        .attr("style:font-name")
+        .unwrap();
+    assert_eq!(name, "Liberation Serif_2");
+    assert_eq!(
+        other.font(name).unwrap().font_family().unwrap(),
+        "'Liberation Serif'"
+    );
+}
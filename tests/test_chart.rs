@@ -0,0 +1,107 @@
+use std::io::Read;
+
+use spreadsheet_ods::chart::{Chart, ChartType, LegendPosition, Series};
+use spreadsheet_ods::{
+    cm, read_ods, write_ods, CellRange, CellRef, Length, OdsError, Sheet, WorkBook,
+};
+
+fn manifest(path: &str) -> String {
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut buf = String::new();
+    zip.by_name("META-INF/manifest.xml")
+        .unwrap()
+        .read_to_string(&mut buf)
+        .unwrap();
+    buf
+}
+
+#[test]
+fn chart() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_value(0, 1, "Sales");
+    sh.set_value(0, 2, "Costs");
+    for i in 1..5 {
+        sh.set_value(i, 0, format!("Q{}", i));
+        sh.set_value(i, 1, 100 + i * 10);
+        sh.set_value(i, 2, 80 + i * 5);
+    }
+
+    let mut chart = Chart::new(ChartType::Bar);
+    chart.set_title("Sales per quarter");
+    chart.set_legend(LegendPosition::Bottom);
+    chart.set_x_axis_title("Quarter");
+    chart.set_y_axis_title("EUR");
+    chart.set_categories(CellRange::local(1, 0, 4, 0));
+    let mut series = Series::new(CellRange::local(1, 1, 4, 1));
+    series.set_label(CellRef::local(0, 1));
+    chart.add_series(series);
+    let mut series = Series::new(CellRange::local(1, 2, 4, 2));
+    series.set_label(CellRef::local(0, 2));
+    chart.add_series(series);
+    chart.set_position(cm!(5), cm!(1));
+    chart.set_size(cm!(12), cm!(7));
+    sh.add_chart(chart);
+
+    let mut chart = Chart::new(ChartType::Scatter);
+    let mut series = Series::new(CellRange::local(1, 2, 4, 2));
+    series.set_domain(CellRange::local(1, 1, 4, 1));
+    chart.add_series(series);
+    sh.add_chart(chart);
+
+    wb.push_sheet(sh);
+
+    write_ods(&wb, "test_out/chart.ods")?;
+    assert!(manifest("test_out/chart.ods").contains("Object 2/content.xml"));
+
+    let mut wb = read_ods("test_out/chart.ods")?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.charts().len(), 2);
+
+    let chart = &sh.charts()[0];
+    assert_eq!(chart.chart_type(), ChartType::Bar);
+    assert_eq!(chart.title().unwrap(), "Sales per quarter");
+    assert_eq!(chart.legend(), Some(LegendPosition::Bottom));
+    assert_eq!(chart.x_axis_title().unwrap(), "Quarter");
+    assert_eq!(chart.y_axis_title().unwrap(), "EUR");
+    assert_eq!(
+        *chart.categories().unwrap(),
+        CellRange::remote("Data", 1, 0, 4, 0)
+    );
+    assert_eq!(chart.series().len(), 2);
+    assert_eq!(
+        *chart.series()[1].values(),
+        CellRange::remote("Data", 1, 2, 4, 2)
+    );
+    assert_eq!(
+        *chart.series()[1].label().unwrap(),
+        CellRef::remote("Data", 0, 2)
+    );
+
+    let chart = &sh.charts()[1];
+    assert_eq!(chart.chart_type(), ChartType::Scatter);
+    assert_eq!(
+        *chart.series()[0].domain().unwrap(),
+        CellRange::remote("Data", 1, 1, 4, 1)
+    );
+
+    // The existing charts are copied, the new one gets a fresh object.
+    let mut chart = Chart::new(ChartType::Pie);
+    chart.add_series(Series::new(CellRange::local(1, 1, 4, 1)));
+    wb.sheet_mut(0).add_chart(chart);
+
+    write_ods(&wb, "test_out/chart2.ods")?;
+    let m = manifest("test_out/chart2.ods");
+    assert!(m.contains("Object 1/content.xml"));
+    assert!(m.contains("Object 3/content.xml"));
+
+    let wb = read_ods("test_out/chart2.ods")?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.charts().len(), 3);
+    assert_eq!(sh.charts()[0].chart_type(), ChartType::Bar);
+    assert_eq!(sh.charts()[2].chart_type(), ChartType::Pie);
+    assert_eq!(sh.charts()[2].href().unwrap(), "Object 3");
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "csv")]
+
+use chrono::NaiveDate;
+use time::Duration;
+
+use spreadsheet_ods::csv::{read_csv, write_csv, CsvOptions, CsvQuote};
+use spreadsheet_ods::{write_ods, OdsError, Sheet, Value, ValueType, WorkBook};
+
+#[test]
+fn csv_read() -> Result<(), OdsError> {
+    let data = "\
+name,count,share,due,booked,paid
+\"rent, flat\",1,25%,2021-01-01,2021-01-02T10:15:00,true
+salary,-2.5,,2021-01-31,2021-01-02 08:00:30,FALSE
+";
+
+    let mut wb = WorkBook::new();
+    let mut options = CsvOptions::new();
+    options.set_default_styles(true);
+    let idx = read_csv(&mut wb, "Import", data.as_bytes(), &options)?;
+
+    assert!(wb.def_style(ValueType::DateTime).is_some());
+
+    let sh = wb.sheet(idx);
+    assert_eq!(sh.name(), "Import");
+    assert_eq!(sh.value(0, 0).as_str_or(""), "name");
+    assert_eq!(sh.value(1, 0).as_str_or(""), "rent, flat");
+    assert_eq!(sh.value(1, 1).as_f64_or(0.0), 1.0);
+    assert!(matches!(sh.value(1, 2), Value::Percentage(v) if *v == 0.25));
+    assert_eq!(
+        sh.value(1, 3).as_datetime_opt(),
+        Some(NaiveDate::from_ymd(2021, 1, 1).and_hms(0, 0, 0))
+    );
+    assert_eq!(
+        sh.value(1, 4).as_datetime_opt(),
+        Some(NaiveDate::from_ymd(2021, 1, 2).and_hms(10, 15, 0))
+    );
+    assert!(matches!(sh.value(1, 5), Value::Boolean(true)));
+    assert_eq!(sh.value(2, 1).as_f64_or(0.0), -2.5);
+    assert!(sh.is_empty(2, 2));
+    assert!(matches!(sh.value(2, 4), Value::DateTime(_)));
+    assert!(matches!(sh.value(2, 5), Value::Boolean(false)));
+
+    write_ods(&wb, "test_out/csv.ods")?;
+
+    let mut options = CsvOptions::new();
+    options.set_infer_types(false);
+    let idx = read_csv(&mut wb, "Text", data.as_bytes(), &options)?;
+    assert!(matches!(wb.sheet(idx).value(1, 1), Value::Text(_)));
+
+    Ok(())
+}
+
+#[test]
+fn csv_write() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut sh = Sheet::new();
+    sh.set_value(0, 0, "name");
+    sh.set_value(0, 2, "due");
+    sh.set_value(1, 0, "a;b");
+    sh.set_value(1, 1, 1.5);
+    sh.set_value(1, 2, NaiveDate::from_ymd(2021, 3, 1));
+    sh.set_value(2, 1, true);
+    wb.push_sheet(sh);
+
+    let mut durations = Sheet::new();
+    durations.set_value(0, 0, Duration::milliseconds(1050));
+    durations.set_value(0, 1, Duration::milliseconds(-3_723_500));
+    wb.push_sheet(durations);
+    let mut buf = Vec::new();
+    write_csv(&wb, 1, &mut buf, &CsvOptions::new())?;
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "PT0H0M1.050S,-PT1H2M3.500S\n"
+    );
+
+    let mut options = CsvOptions::new();
+    options.set_delimiter(b';');
+    let mut buf = Vec::new();
+    write_csv(&wb, 0, &mut buf, &options)?;
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "name;;due\n\"a;b\";1.5;2021-03-01\n;true;\n"
+    );
+
+    options.set_quote_style(CsvQuote::NonNumeric);
+    let mut buf = Vec::new();
+    write_csv(&wb, 0, &mut buf, &options)?;
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "\"name\";\"\";\"due\"\n\"a;b\";1.5;\"2021-03-01\"\n\"\";\"true\";\"\"\n"
+    );
+
+    // The default styles format the date as d.m.y
+    spreadsheet_ods::defaultstyles::create_default_styles(&mut wb);
+    let mut options = CsvOptions::new();
+    options.set_display_text(true);
+    let mut buf = Vec::new();
+    write_csv(&wb, 0, &mut buf, &options)?;
+    let text = String::from_utf8(buf).unwrap();
+    assert!(text.contains("01.03.2021"), "{}", text);
+
+    // Round trip through csv keeps the values.
+    let options = CsvOptions::new();
+    let mut buf = Vec::new();
+    write_csv(&wb, 0, &mut buf, &options)?;
+    let idx = read_csv(&mut wb, "Back", buf.as_slice(), &options)?;
+    let sh = wb.sheet(idx);
+    assert_eq!(sh.value(1, 0).as_str_or(""), "a;b");
+    assert_eq!(sh.value(1, 1).as_f64_or(0.0), 1.5);
+    assert!(matches!(sh.value(1, 2), Value::DateTime(_)));
+    assert!(matches!(sh.value(2, 1), Value::Boolean(true)));
+
+    Ok(())
+}
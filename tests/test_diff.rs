@@ -0,0 +1,147 @@
+use spreadsheet_ods::diff::{diff, diff_with_options, CellChange, DefinitionChange, DiffOptions};
+use spreadsheet_ods::format::create_date_dmy_format;
+use spreadsheet_ods::style::AttrText;
+use spreadsheet_ods::{read_ods, OdsError, Sheet, Style, Value, WorkBook};
+
+fn report() -> WorkBook {
+    let mut wb = WorkBook::new();
+    wb.add_format(create_date_dmy_format("date_dmy"));
+    wb.add_style(Style::new_cell_style("plain", ""));
+    wb.add_style(Style::new_cell_style("old", ""));
+
+    let mut sh = Sheet::new_with_name("Summary");
+    sh.set_value(0, 0, "Total");
+    sh.set_value(0, 1, 100.0);
+    sh.set_formula(1, 1, "of:=[.B1]*2");
+    sh.set_value(1, 1, 200.0);
+    sh.set_styled_value(2, 0, "styled", "plain");
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("Q1");
+    sh.set_value(0, 0, 1);
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("Gone");
+    sh.set_value(0, 0, 1);
+    wb.push_sheet(sh);
+
+    wb
+}
+
+#[test]
+fn diff_equal() -> Result<(), OdsError> {
+    let wb = report();
+    assert!(diff(&wb, &wb.clone()).is_empty());
+
+    let wb = read_ods("tests/orders.ods")?;
+    let wb2 = read_ods("tests/orders.ods")?;
+    let d = diff(&wb, &wb2);
+    assert!(d.is_empty(), "{}", d);
+    Ok(())
+}
+
+#[test]
+fn diff_changes() {
+    let old = report();
+    let mut new = report();
+
+    new.sheet_mut(0).set_value(0, 1, 100.0001);
+    new.sheet_mut(0).set_formula(1, 1, "of:=[.B1]*3");
+    new.sheet_mut(0).set_cell_style(2, 0, "bold");
+    new.sheet_mut(0).set_col_span(0, 0, 2);
+    new.sheet_mut(0).set_value(5, 5, "new");
+    new.sheet_mut(1).set_name("Q1 2021");
+    new.remove_sheet(2);
+    new.push_sheet(Sheet::new_with_name("Added"));
+
+    new.remove_style("old");
+    let mut style = Style::new_cell_style("plain", "");
+    style.text_mut().set_font_bold();
+    new.add_style(style);
+    new.add_style(Style::new_cell_style("bold", ""));
+
+    let d = diff(&old, &new);
+    assert_eq!(d.added_sheets(), &["Added".to_string()]);
+    assert_eq!(d.removed_sheets(), &["Gone".to_string()]);
+    assert_eq!(
+        d.renamed_sheets(),
+        &[("Q1".to_string(), "Q1 2021".to_string())]
+    );
+
+    let sh = d.sheet("Summary").unwrap();
+    assert_eq!(sh.cells().len(), 5);
+    let a1 = sh.cell(0, 0).unwrap();
+    assert!(matches!(
+        a1.changes(),
+        [CellChange::Span {
+            old: (1, 1),
+            new: (1, 2)
+        }]
+    ));
+    let b1 = sh.cell(0, 1).unwrap();
+    assert_eq!(b1.cell().to_string(), "Summary.B1");
+    assert!(matches!(
+        b1.changes(),
+        [CellChange::Value {
+            old: Value::Number(_),
+            new: Value::Number(_)
+        }]
+    ));
+    assert!(matches!(
+        sh.cell(1, 1).unwrap().changes(),
+        [CellChange::Formula { .. }]
+    ));
+    assert!(matches!(
+        sh.cell(2, 0).unwrap().changes(),
+        [CellChange::Style {
+            old: Some(_),
+            new: Some(_)
+        }]
+    ));
+    assert!(matches!(
+        sh.cell(5, 5).unwrap().changes(),
+        [CellChange::Value {
+            old: Value::Empty,
+            ..
+        }]
+    ));
+    assert!(d.sheet("Q1 2021").is_none());
+
+    assert_eq!(
+        d.styles(),
+        &[
+            DefinitionChange::Added("bold".to_string()),
+            DefinitionChange::Removed("old".to_string()),
+            DefinitionChange::Changed("plain".to_string()),
+        ]
+    );
+    assert!(d.formats().is_empty());
+    assert!(d.to_string().contains("renamed sheet Q1 to Q1 2021"));
+
+    let mut options = DiffOptions::new();
+    options.set_tolerance(0.001);
+    let d = diff_with_options(&old, &new, &options);
+    assert!(d.sheet("Summary").unwrap().cell(0, 1).is_none());
+}
+
+#[test]
+fn diff_cell_format() {
+    let old = report();
+    let mut new = report();
+    new.sheet_mut(0)
+        .cell_format_mut(0, 0)
+        .text_mut()
+        .set_font_bold();
+
+    let d = diff(&old, &new);
+    let sh = d.sheet("Summary").unwrap();
+    assert_eq!(sh.cells().len(), 1);
+    assert!(matches!(
+        sh.cell(0, 0).unwrap().changes(),
+        [CellChange::Format {
+            old: None,
+            new: Some(_)
+        }]
+    ));
+    assert!(d.to_string().contains("Summary.A1: cell format changed"));
+}
@@ -0,0 +1,84 @@
+use spreadsheet_ods::style::{AttrFontDecl, AttrText, FontFaceDecl, FontPitch};
+use spreadsheet_ods::{
+    read_ods, write_ods_with_options, MissingFonts, OdsError, OdsWriteOptions, Sheet, Style,
+    ValueFormat, ValueType, WorkBook,
+};
+
+#[test]
+fn font_for() {
+    let mut wb = WorkBook::new();
+
+    let mut font = FontFaceDecl::new_with_name("Liberation Sans");
+    font.set_font_family("'Liberation Sans'");
+    wb.add_font(font);
+    assert_eq!(wb.font_for("Liberation Sans"), "Liberation Sans");
+
+    let name = wb.font_for("Liberation Mono");
+    assert_eq!(name, "Liberation Mono");
+    let font = wb.font(&name).unwrap();
+    assert_eq!(font.font_family().unwrap(), "'Liberation Mono'");
+    assert_eq!(font.font_family_generic().unwrap(), "modern");
+    assert_eq!(font.font_pitch(), Ok(Some(FontPitch::Fixed)));
+    assert_eq!(wb.font_for("Liberation Mono"), "Liberation Mono");
+
+    let name = wb.font_for("Arial");
+    assert_eq!(wb.font(&name).unwrap().font_family().unwrap(), "Arial");
+    let name = wb.font_for("Bob's Font");
+    assert_eq!(
+        wb.font(&name).unwrap().font_family().unwrap(),
+        "'Bob\\'s Font'"
+    );
+    assert_eq!(wb.font_for("Bob's Font"), name);
+
+    // name is taken by a different family
+    let mut font = FontFaceDecl::new_with_name("Serif");
+    font.set_font_family("'DejaVu Serif'");
+    wb.add_font(font);
+    assert_eq!(wb.font_for("Serif"), "Serif1");
+}
+
+fn book() -> WorkBook {
+    let mut wb = WorkBook::new();
+    let mut style = Style::new_cell_style("c", "");
+    style.text_mut().set_font_name("Liberation Serif");
+    wb.add_style(style);
+    let mut sh = Sheet::new();
+    sh.set_styled_value(0, 0, "a", "c");
+    wb.push_sheet(sh);
+    wb
+}
+
+#[test]
+fn missing_fonts() -> Result<(), OdsError> {
+    let wb = book();
+    assert_eq!(wb.missing_fonts(), vec!["Liberation Serif".to_string()]);
+
+    let mut options = OdsWriteOptions::new();
+    options.set_missing_fonts(MissingFonts::Error);
+    assert!(write_ods_with_options(&wb, "test_out/test_fonts.ods", &options).is_err());
+
+    options.set_missing_fonts(MissingFonts::Create);
+    write_ods_with_options(&wb, "test_out/test_fonts.ods", &options)?;
+    assert!(wb.font("Liberation Serif").is_none());
+
+    let wb = read_ods("test_out/test_fonts.ods")?;
+    assert!(wb.missing_fonts().is_empty());
+    let font = wb.font("Liberation Serif").unwrap();
+    assert_eq!(font.font_family().unwrap(), "'Liberation Serif'");
+    assert_eq!(font.font_family_generic().unwrap(), "roman");
+
+    Ok(())
+}
+
+#[test]
+fn missing_fonts_format() {
+    let mut wb = WorkBook::new();
+    let mut format = ValueFormat::new_with_name("n", ValueType::Number);
+    format.text_mut().set_font_name("Liberation Mono");
+    wb.add_format(format);
+    assert_eq!(wb.missing_fonts(), vec!["Liberation Mono".to_string()]);
+
+    wb.create_missing_fonts();
+    assert!(wb.missing_fonts().is_empty());
+    assert!(wb.font("Liberation Mono").is_some());
+}
@@ -1,7 +1,14 @@
 use chrono::NaiveDateTime;
 
-use spreadsheet_ods::{OdsError, Sheet, Style, ValueFormat, ValueType, WorkBook, write_ods};
-use spreadsheet_ods::format::{FormatCalendarStyle, FormatNumberStyle};
+use color::Rgb;
+
+use spreadsheet_ods::format::{
+    FormatCalendarStyle, FormatNumberStyle, FormatPartType, ValueFormatError,
+};
+use spreadsheet_ods::style::{AttrMap, AttrText};
+use spreadsheet_ods::{
+    read_ods, write_ods, OdsError, Sheet, Style, ValueFormat, ValueType, WorkBook,
+};
 
 #[test]
 pub fn value_format() {
@@ -169,3 +176,218 @@ fn write_format() -> Result<(), OdsError> {
         write_ods(&wb, path)
     }
 }
+
+fn part_types(format: &ValueFormat) -> Vec<FormatPartType> {
+    format.parts().iter().map(|p| p.part_type()).collect()
+}
+
+#[test]
+fn format_code_sections() -> Result<(), ValueFormatError> {
+    let f = ValueFormat::from_format_code(
+        "eur",
+        "#,##0.00 €;[RED]-#,##0.00 €",
+        ValueType::Currency,
+        "de-AT",
+    )?;
+    assert_eq!(f.len(), 2);
+    assert_eq!(f[0].name(), "eurP0");
+    assert_eq!(
+        part_types(&f[0]),
+        vec![
+            FormatPartType::Number,
+            FormatPartType::Text,
+            FormatPartType::CurrencySymbol
+        ]
+    );
+    let number = &f[0].parts()[0];
+    assert_eq!(number.attr("number:decimal-places").unwrap(), "2");
+    assert_eq!(number.attr("loext:min-decimal-places").unwrap(), "2");
+    assert_eq!(number.attr("number:grouping").unwrap(), "true");
+    let currency = &f[0].parts()[2];
+    assert_eq!(currency.content().unwrap(), "€");
+    assert_eq!(currency.attr("number:country").unwrap(), "AT");
+    assert_eq!(f[0].attr("number:language").unwrap(), "de");
+    assert!(f[0].stylemaps().is_none());
+
+    assert_eq!(f[1].name(), "eur");
+    assert_eq!(f[1].parts()[0].content().unwrap(), "-");
+    assert_eq!(f[1].text().color(), Ok(Some(Rgb::new(255, 0, 0))));
+    let maps = f[1].stylemaps().unwrap();
+    assert_eq!(maps.len(), 1);
+    assert_eq!(maps[0].condition(), "value()>=0");
+    assert_eq!(maps[0].applied_style(), "eurP0");
+
+    let f =
+        ValueFormat::from_format_code("n", "0;(0);\"zero\";\"text: \"@", ValueType::Number, "")?;
+    assert_eq!(f.len(), 4);
+    let conditions: Vec<&str> = f[3]
+        .stylemaps()
+        .unwrap()
+        .iter()
+        .map(|v| v.condition().as_str())
+        .collect();
+    assert_eq!(conditions, vec!["value()>0", "value()<0", "value()=0"]);
+    assert_eq!(f[2].parts()[0].content().unwrap(), "zero");
+    assert_eq!(f[3].value_type(), ValueType::Text);
+    assert_eq!(
+        part_types(&f[3]),
+        vec![FormatPartType::Text, FormatPartType::TextContent]
+    );
+
+    let f = ValueFormat::from_format_code(
+        "c",
+        "[BLUE][>=1000]#,##0,\" k\";[<>0]0.0;0",
+        ValueType::Number,
+        "",
+    )?;
+    assert_eq!(f.len(), 3);
+    assert_eq!(f[0].text().color(), Ok(Some(Rgb::new(0, 0, 255))));
+    assert_eq!(
+        f[0].parts()[0].attr("number:display-factor").unwrap(),
+        "1000"
+    );
+    let maps = f[2].stylemaps().unwrap();
+    assert_eq!(maps[0].condition(), "value()>=1000");
+    assert_eq!(maps[1].condition(), "value()!=0");
+
+    Ok(())
+}
+
+#[test]
+fn format_code_numbers() -> Result<(), ValueFormatError> {
+    let f = ValueFormat::from_format_code("f", "# ??/??", ValueType::Number, "")?;
+    let part = &f[0].parts()[0];
+    assert_eq!(part.part_type(), FormatPartType::Fraction);
+    assert_eq!(part.attr("number:min-integer-digits").unwrap(), "0");
+    assert_eq!(part.attr("number:min-numerator-digits").unwrap(), "2");
+    assert_eq!(part.attr("number:min-denominator-digits").unwrap(), "2");
+
+    let f = ValueFormat::from_format_code("f", "?/16", ValueType::Number, "")?;
+    let part = &f[0].parts()[0];
+    assert_eq!(part.attr("number:denominator-value").unwrap(), "16");
+    assert!(part.attr("number:min-integer-digits").is_none());
+
+    let f = ValueFormat::from_format_code("s", "0.000E+00", ValueType::Number, "")?;
+    let part = &f[0].parts()[0];
+    assert_eq!(part.part_type(), FormatPartType::Scientific);
+    assert_eq!(part.attr("number:decimal-places").unwrap(), "3");
+    assert_eq!(part.attr("number:min-exponent-digits").unwrap(), "2");
+
+    let f = ValueFormat::from_format_code("p", "0.0%", ValueType::Percentage, "")?;
+    assert_eq!(
+        part_types(&f[0]),
+        vec![FormatPartType::Number, FormatPartType::Text]
+    );
+
+    let f = ValueFormat::from_format_code("b", "BOOLEAN", ValueType::Boolean, "")?;
+    assert_eq!(part_types(&f[0]), vec![FormatPartType::Boolean]);
+
+    Ok(())
+}
+
+#[test]
+fn format_code_date_time() -> Result<(), ValueFormatError> {
+    let f = ValueFormat::from_format_code("dt", "DD.MM.YYYY HH:MM:SS", ValueType::DateTime, "")?;
+    assert_eq!(
+        part_types(&f[0]),
+        vec![
+            FormatPartType::Day,
+            FormatPartType::Text,
+            FormatPartType::Month,
+            FormatPartType::Text,
+            FormatPartType::Year,
+            FormatPartType::Text,
+            FormatPartType::Hours,
+            FormatPartType::Text,
+            FormatPartType::Minutes,
+            FormatPartType::Text,
+            FormatPartType::Seconds,
+        ]
+    );
+    assert_eq!(f[0].parts()[4].attr("number:style").unwrap(), "long");
+
+    let f = ValueFormat::from_format_code("d", "NNN, D. MMMM YY", ValueType::DateTime, "")?;
+    let parts = f[0].parts();
+    assert_eq!(parts[0].part_type(), FormatPartType::DayOfWeek);
+    assert_eq!(parts[0].attr("number:style").unwrap(), "long");
+    assert_eq!(parts[4].part_type(), FormatPartType::Month);
+    assert_eq!(parts[4].attr("number:textual").unwrap(), "true");
+    assert_eq!(parts[6].attr("number:style").unwrap(), "short");
+
+    let f = ValueFormat::from_format_code("t", "H:MM AM/PM", ValueType::DateTime, "")?;
+    assert_eq!(
+        part_types(&f[0]),
+        vec![
+            FormatPartType::Hours,
+            FormatPartType::Text,
+            FormatPartType::Minutes,
+            FormatPartType::Text,
+            FormatPartType::AmPm,
+        ]
+    );
+
+    let f = ValueFormat::from_format_code("e", "[HH]:MM:SS.00", ValueType::TimeDuration, "")?;
+    assert_eq!(f[0].attr("number:truncate-on-overflow").unwrap(), "false");
+    assert_eq!(f[0].parts()[0].part_type(), FormatPartType::Hours);
+    assert_eq!(f[0].parts()[2].part_type(), FormatPartType::Minutes);
+    assert_eq!(f[0].parts()[4].attr("number:decimal-places").unwrap(), "2");
+
+    Ok(())
+}
+
+#[test]
+fn format_code_errors() {
+    let err = |code: &str, value_type: ValueType| match ValueFormat::from_format_code(
+        "e", code, value_type, "",
+    ) {
+        Err(ValueFormatError::Parse(pos, _)) => pos,
+        v => panic!("{} {:?}", code, v),
+    };
+    assert_eq!(err("", ValueType::Number), 0);
+    assert_eq!(err("0 \"abc", ValueType::Number), 2);
+    assert_eq!(err("0;0;0;@;0", ValueType::Number), 7);
+    assert_eq!(err("[RED 0", ValueType::Number), 0);
+    assert_eq!(err("0;[PURPLE]0", ValueType::Number), 2);
+    assert_eq!(err("[>x]0", ValueType::Number), 2);
+    assert_eq!(err("0.00 m", ValueType::Number), 5);
+    assert_eq!(err("DD.MM.YY 0", ValueType::DateTime), 9);
+    assert_eq!(err("[HH]:MM", ValueType::DateTime), 0);
+    assert_eq!(err("0 0", ValueType::Number), 2);
+    assert_eq!(err("# ?/", ValueType::Number), 4);
+    assert_eq!(err("0.0E+", ValueType::Number), 5);
+    assert_eq!(err("@;0", ValueType::Number), 0);
+    assert_eq!(err("0 @", ValueType::Number), 2);
+
+    let e = ValueFormat::from_format_code("e", "[>x]0", ValueType::Number, "").unwrap_err();
+    assert_eq!(e.to_string(), "Number expected in [>x] at position 2");
+}
+
+#[test]
+fn format_code_write_read() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    for format in ValueFormat::from_format_code(
+        "eur",
+        "#,##0.00 €;[RED]-#,##0.00 €",
+        ValueType::Currency,
+        "de-AT",
+    )
+    .unwrap()
+    {
+        wb.add_format(format);
+    }
+    wb.add_style(Style::new_cell_style("eur", "eur"));
+    let mut sh = Sheet::new();
+    sh.set_styled_value(0, 0, -1.5, "eur");
+    wb.push_sheet(sh);
+    write_ods(&wb, "test_out/test_format_code.ods")?;
+
+    let wb = read_ods("test_out/test_format_code.ods")?;
+    let format = wb.format("eur").unwrap();
+    assert_eq!(format.text().color(), Ok(Some(Rgb::new(255, 0, 0))));
+    assert_eq!(format.stylemaps().unwrap()[0].applied_style(), "eurP0");
+    let format = wb.format("eurP0").unwrap();
+    assert_eq!(format.value_type(), ValueType::Currency);
+    assert_eq!(format.attr("number:country").unwrap(), "AT");
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use color::Rgb;
+
+use spreadsheet_ods::format::create_number_format;
+use spreadsheet_ods::style::{
+    AttrFoBackgroundColor, AttrFoBorder, AttrFontDecl, AttrMap, AttrParagraph, AttrTableCell,
+    AttrText, Border, CellAlignVertical, FontFaceDecl, TextAlign,
+};
+use spreadsheet_ods::{
+    cm, html, mm, read_ods, Length, OdsError, Sheet, Style, Visibility, WorkBook,
+};
+
+#[test]
+fn html_sheet() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    wb.add_format(create_number_format("num2", 2, false));
+
+    let mut base = Style::new_cell_style("base", "");
+    base.cell_mut().set_background_color(Rgb::new(255, 255, 0));
+    base.text_mut().set_font_italic();
+    wb.add_style(base);
+
+    let mut head = Style::new_cell_style("head", "");
+    head.set_parent("base");
+    head.cell_mut()
+        .set_border(mm!(0.2), Border::Solid, Rgb::new(0, 0, 0));
+    head.cell_mut()
+        .set_vertical_align(CellAlignVertical::Middle);
+    head.paragraph_mut().set_text_align(TextAlign::Center);
+    head.text_mut().set_font_bold();
+    head.text_mut().set_color(Rgb::new(255, 0, 0));
+    wb.add_style(head);
+
+    wb.add_style(Style::new_cell_style("money", "num2"));
+
+    let mut sh = Sheet::new_with_name("Report <1>");
+    sh.set_styled_value(0, 0, "Head & more", "head");
+    sh.set_col_span(0, 0, 3);
+    sh.set_value(1, 0, "a\nb");
+    sh.set_styled_value(1, 1, 1.5, "money");
+    sh.set_value(1, 2, "hidden col");
+    sh.set_value(2, 0, "hidden row");
+    sh.set_value(3, 0, "tall");
+    sh.set_row_span(3, 0, 2);
+    sh.set_value(4, 1, "next to tall");
+    sh.set_column_visible(2, Visibility::Collapsed);
+    sh.set_row_visible(2, Visibility::Collapsed);
+    sh.set_col_width(0, cm!(3));
+    wb.push_sheet(sh);
+
+    let html = html::sheet_to_html(&wb, 0);
+
+    assert!(
+        html.contains(
+            ".ods-head { background-color: #ffff00; \
+        border-bottom: 0.2mm solid #000000; border-left: 0.2mm solid #000000; \
+        border-right: 0.2mm solid #000000; border-top: 0.2mm solid #000000; \
+        color: #ff0000; font-style: italic; font-weight: bold; \
+        text-align: center; vertical-align: middle; }"
+        ),
+        "{}",
+        html
+    );
+    assert!(html.contains("<caption>Report &lt;1&gt;</caption>"));
+    assert!(html.contains("<colgroup><col style=\"width: 3cm\"><col></colgroup>"));
+    assert!(html.contains("<td class=\"ods-head\" colspan=\"2\">Head &amp; more</td>"));
+    assert!(html.contains("<tr><td>a<br>b</td><td class=\"ods-money\">1.50</td></tr>"));
+    assert!(!html.contains("hidden"));
+    assert!(html.contains("<tr><td rowspan=\"2\">tall</td><td></td></tr>"));
+    assert!(html.contains("<tr><td>next to tall</td></tr>"));
+
+    Ok(())
+}
+
+#[test]
+fn html_document() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/orders.ods")?;
+    let mut hidden = Sheet::new_with_name("Hidden");
+    hidden.set_value(0, 0, 1);
+    hidden.set_display(false);
+    wb.push_sheet(hidden);
+
+    html::write_html(&wb, "test_out/orders.html")?;
+    let html = std::fs::read_to_string("test_out/orders.html")?;
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(!html.contains("<caption>Hidden</caption>"));
+
+    Ok(())
+}
+
+#[test]
+fn html_css_values() {
+    let mut wb = WorkBook::new();
+    let mut font = FontFaceDecl::new_with_name("Evil");
+    font.set_font_family("'Liberation Sans</style><script>', sans-serif");
+    wb.add_font(font);
+
+    let mut style = Style::new_cell_style("evil", "");
+    style.text_mut().set_font_name("Evil");
+    style
+        .cell_mut()
+        .set_attr("fo:background-color", "red;} body { color: red".to_string());
+    wb.add_style(style);
+
+    let mut sh = Sheet::new();
+    sh.set_styled_value(0, 0, "a", "evil");
+    wb.push_sheet(sh);
+
+    let html = html::sheet_to_html(&wb, 0);
+    assert!(!html.contains("<script>"), "{}", html);
+    assert!(
+        html.contains(
+            ".ods-evil { background-color: red body  color: red; \
+        font-family: 'Liberation Sans/stylescript', sans-serif; }"
+        ),
+        "{}",
+        html
+    );
+}
+
+#[test]
+fn html_class_names() {
+    let mut wb = WorkBook::new();
+    wb.add_style(Style::new_cell_style("a.b", ""));
+    wb.add_style(Style::new_cell_style("a_b", ""));
+
+    let mut sh = Sheet::new();
+    sh.set_styled_value(0, 0, "x", "a.b");
+    sh.set_styled_value(0, 1, "y", "a_b");
+    wb.push_sheet(sh);
+
+    let html = html::sheet_to_html(&wb, 0);
+    assert!(html.contains("<td class=\"ods-a_2e_b\">x</td>"), "{}", html);
+    assert!(html.contains("<td class=\"ods-a_5f_b\">y</td>"), "{}", html);
+}
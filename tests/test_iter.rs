@@ -0,0 +1,118 @@
+use spreadsheet_ods::{CellRange, CellRef, Sheet};
+
+fn sheet() -> Sheet {
+    let mut sh = Sheet::new();
+    sh.set_value(0, 0, "a");
+    sh.set_value(0, 3, "b");
+    sh.set_value(2, 1, 1);
+    sh.set_value(2, 2, 2);
+    sh.set_value(4, 0, 3);
+    sh.set_value(4, 5, 4);
+    sh
+}
+
+#[test]
+fn iter() {
+    let sh = sheet();
+
+    let cells: Vec<(u32, u32)> = sh.iter().map(|(pos, _)| pos).collect();
+    assert_eq!(cells, vec![(0, 0), (0, 3), (2, 1), (2, 2), (4, 0), (4, 5)]);
+
+    let rows: Vec<(u32, Vec<u32>)> = sh
+        .rows()
+        .map(|(row, cells)| (row, cells.map(|(col, _)| col).collect()))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![(0, vec![0, 3]), (2, vec![1, 2]), (4, vec![0, 5])]
+    );
+
+    let cols: Vec<u32> = sh.row_cells(2).map(|(col, _)| col).collect();
+    assert_eq!(cols, vec![1, 2]);
+    assert_eq!(sh.row_cells(1).count(), 0);
+
+    let rows: Vec<u32> = sh.col_cells(0).map(|(row, _)| row).collect();
+    assert_eq!(rows, vec![0, 4]);
+    let rows: Vec<u32> = sh.col_cells(2).map(|(row, _)| row).collect();
+    assert_eq!(rows, vec![2]);
+    assert_eq!(sh.col_cells(4).count(), 0);
+
+    let cells: Vec<CellRef> = sh
+        .range(&CellRange::local(0, 1, 4, 3))
+        .map(|(cellref, _)| cellref)
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            CellRef::local(0, 3),
+            CellRef::local(2, 1),
+            CellRef::local(2, 2)
+        ]
+    );
+    assert_eq!(sh.range(&CellRange::local(1, 0, 1, 10)).count(), 0);
+    assert_eq!(
+        sh.range(&CellRange::local(0, 0, u32::MAX, u32::MAX))
+            .count(),
+        6
+    );
+}
+
+#[test]
+fn iter_mut() {
+    let mut sh = sheet();
+
+    for (_, cell) in sh.range_mut(&CellRange::local(2, 0, 4, 2)) {
+        cell.set_value(cell.value().as_f64_or(0.0) * 10.0);
+    }
+    assert_eq!(sh.value(2, 1).as_f64_or(0.0), 10.0);
+    assert_eq!(sh.value(2, 2).as_f64_or(0.0), 20.0);
+    assert_eq!(sh.value(4, 0).as_f64_or(0.0), 30.0);
+    assert_eq!(sh.value(4, 5).as_f64_or(0.0), 4.0);
+
+    for (_, cell) in sh.row_cells_mut(0) {
+        cell.set_style("bold");
+    }
+    assert_eq!(sh.cell_style(0, 3).unwrap(), "bold");
+
+    for (_, cell) in sh.iter_mut() {
+        cell.set_formula("of:=1");
+    }
+    assert_eq!(sh.formula(4, 5).unwrap(), "of:=1");
+
+    let mut rows: Vec<_> = sh.rows_mut().collect();
+    assert_eq!(
+        rows.iter().map(|(row, _)| *row).collect::<Vec<_>>(),
+        vec![0, 2, 4]
+    );
+    // The row iterators can be used in any order.
+    for (_, cells) in rows.iter_mut().rev() {
+        for (col, cell) in cells {
+            cell.set_value(col);
+        }
+    }
+    assert_eq!(sh.value(0, 3).as_u32_or(0), 3);
+    assert_eq!(sh.value(4, 5).as_u32_or(0), 5);
+
+    let mut rows = Vec::new();
+    for (row, cell) in sh.col_cells_mut(0) {
+        cell.set_value("first");
+        rows.push(row);
+    }
+    assert_eq!(rows, vec![0, 4]);
+    assert_eq!(sh.value(4, 0).as_str_or(""), "first");
+    assert_eq!(sh.col_cells_mut(4).count(), 0);
+
+    let cells: Vec<CellRef> = sh
+        .range_mut(&CellRange::local(0, 1, 4, 3))
+        .map(|(cellref, _)| cellref)
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            CellRef::local(0, 3),
+            CellRef::local(2, 1),
+            CellRef::local(2, 2)
+        ]
+    );
+    assert_eq!(sh.range_mut(&CellRange::local(1, 0, 1, 10)).count(), 0);
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "json")]
+
+use chrono::NaiveDate;
+use time::Duration;
+
+use spreadsheet_ods::json::{apply_json, read_json, to_json, write_json};
+use spreadsheet_ods::style::{AttrText, CellFormat};
+use spreadsheet_ods::text::TextTag;
+use spreadsheet_ods::{read_ods, write_ods, OdsError, Sheet, Value, WorkBook};
+
+#[test]
+fn json_roundtrip() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_styled_value(0, 0, "Name", "head");
+    sh.set_col_span(0, 0, 2);
+    sh.set_value(1, 0, true);
+    sh.set_value(1, 1, 1.5);
+    sh.set_value(1, 2, Value::Percentage(0.25));
+    sh.set_value(1, 3, Value::Currency("EUR".to_string(), 12.5));
+    sh.set_value(2, 0, NaiveDate::from_ymd(2021, 3, 1).and_hms(10, 15, 30));
+    sh.set_value(2, 1, Duration::hours(36));
+    sh.set_formula(2, 2, "of:=[.B2]+1");
+    sh.set_value(2, 2, 2.5);
+    sh.set_cell_style(3, 0, "empty");
+    sh.set_value(3, 1, "bold");
+    sh.cell_format_mut(3, 1).text_mut().set_font_bold();
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new_with_name("Other"));
+
+    let json = to_json(&wb);
+    assert!(json.contains("\"version\":1"));
+    assert!(json.contains("{\"col\":1,\"type\":\"number\",\"value\":1.5}"));
+    assert!(json.contains("\"currency\":\"EUR\""));
+    assert!(json.contains("\"value\":\"2021-03-01T10:15:30\""));
+    assert!(json.contains("\"type\":\"duration\",\"value\":129600000"));
+    assert!(json.contains("\"format\":{\"text\":{\"fo:font-weight\":\"bold\"}}"));
+
+    let mut buf = Vec::new();
+    write_json(&wb, &mut buf)?;
+    assert_eq!(String::from_utf8(buf).unwrap(), json);
+
+    let wb = read_json(json.as_bytes())?;
+    assert_eq!(wb.num_sheets(), 2);
+    let sh = wb.sheet(0);
+    assert_eq!(sh.name(), "Data");
+    assert_eq!(sh.value(0, 0).as_str_or(""), "Name");
+    assert_eq!(sh.cell_style(0, 0).map(|v| v.as_str()), Some("head"));
+    assert_eq!(sh.col_span(0, 0), 2);
+    assert!(sh.value(1, 0).as_bool_or(false));
+    assert_eq!(sh.value(1, 1).as_f64_or(0.0), 1.5);
+    assert!(matches!(sh.value(1, 2), Value::Percentage(v) if *v == 0.25));
+    assert!(matches!(sh.value(1, 3), Value::Currency(c, v) if c == "EUR" && *v == 12.5));
+    assert_eq!(
+        sh.value(2, 0).as_datetime_opt(),
+        Some(NaiveDate::from_ymd(2021, 3, 1).and_hms(10, 15, 30))
+    );
+    assert_eq!(
+        sh.value(2, 1).as_timeduration_opt(),
+        Some(Duration::hours(36))
+    );
+    assert_eq!(sh.formula(2, 2).map(|v| v.as_str()), Some("of:=[.B2]+1"));
+    assert_eq!(sh.cell_style(3, 0).map(|v| v.as_str()), Some("empty"));
+    let mut bold = CellFormat::new();
+    bold.text_mut().set_font_bold();
+    assert_eq!(sh.cell_format(3, 1), Some(&bold));
+    assert_eq!(to_json(&wb), json);
+
+    Ok(())
+}
+
+#[test]
+fn json_apply() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_value(0, 0, "keep");
+    sh.set_value(0, 1, "remove");
+    sh.set_value(0, 2, TextTag::new("text:p").con_text("formatted"));
+    wb.push_sheet(sh);
+
+    let edit = r#"{ "version": 1, "sheets": [
+        { "name": "Data", "rows": [
+            { "row": 0, "cells": [
+                { "col": 1, "type": "empty" },
+                { "col": 2, "type": "text", "value": "formatted" },
+                { "col": 3, "type": "number", "value": 4, "style": "num" }
+            ]}
+        ]},
+        { "name": "New", "rows": [
+            { "row": 5, "cells": [{ "col": 0, "type": "text", "value": "new" }] }
+        ]}
+    ]}"#;
+    apply_json(&mut wb, edit.as_bytes())?;
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_str_or(""), "keep");
+    assert!(sh.cell(0, 1).is_none());
+    assert!(matches!(sh.value(0, 2), Value::TextXml(_)));
+    assert_eq!(sh.value(0, 3).as_f64_or(0.0), 4.0);
+    assert_eq!(sh.cell_style(0, 3).map(|v| v.as_str()), Some("num"));
+    assert_eq!(wb.sheet(1).name(), "New");
+    assert_eq!(wb.sheet(1).value(5, 0).as_str_or(""), "new");
+
+    assert!(apply_json(&mut wb, r#"{ "version": 2 }"#.as_bytes()).is_err());
+
+    // Nothing is changed if any cell is invalid.
+    assert!(apply_json(
+        &mut wb,
+        r#"{ "sheets": [{ "name": "Data", "rows": [{ "row": 0, "cells": [
+            { "col": 0, "type": "text", "value": "changed" },
+            { "col": 1, "type": "date", "value": "yesterday" }
+        ]}]}, { "name": "Other" }]}"#
+            .as_bytes()
+    )
+    .is_err());
+    assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "keep");
+    assert_eq!(wb.num_sheets(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn json_apply_keep() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_styled_value(0, 0, 1.0, "num");
+    sh.set_formula(0, 0, "of:=1");
+    sh.set_col_span(0, 0, 2);
+    sh.set_styled_value(1, 0, "x", "num");
+    sh.set_formula(1, 0, "of:=2");
+    sh.cell_format_mut(1, 0).text_mut().set_font_bold();
+    wb.push_sheet(sh);
+
+    let edit = r#"{ "sheets": [{ "name": "Data", "rows": [
+        { "row": 0, "cells": [{ "col": 0, "type": "number", "value": 2 }] },
+        { "row": 1, "cells": [
+            { "col": 0, "type": "empty", "formula": null, "style": null, "format": null }
+        ]}
+    ]}]}"#;
+    apply_json(&mut wb, edit.as_bytes())?;
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_f64_or(0.0), 2.0);
+    assert_eq!(sh.formula(0, 0).map(|v| v.as_str()), Some("of:=1"));
+    assert_eq!(sh.cell_style(0, 0).map(|v| v.as_str()), Some("num"));
+    assert_eq!(sh.col_span(0, 0), 2);
+    assert!(sh.cell(1, 0).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn json_orders() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/orders.ods")?;
+    let json = to_json(&wb);
+    apply_json(&mut wb, json.as_bytes())?;
+    assert_eq!(to_json(&wb), json);
+    write_ods(&wb, "test_out/json_orders.ods")?;
+    Ok(())
+}
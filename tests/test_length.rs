@@ -0,0 +1,58 @@
+use spreadsheet_ods::{cm, deg, inch, mm, pt, px, Angle, Length};
+
+#[test]
+fn length_parse() {
+    assert_eq!("10.198cm".parse::<Length>().unwrap(), Length::Cm(10.198));
+    assert_eq!(" 12pt ".parse::<Length>().unwrap(), Length::Pt(12.0));
+    assert_eq!("1inch".parse::<Length>().unwrap(), Length::In(1.0));
+    assert_eq!("96px".parse::<Length>().unwrap(), Length::Px(96.0));
+    assert!("12".parse::<Length>().is_err());
+    assert!("12%".parse::<Length>().is_err());
+    assert!("cm".parse::<Length>().is_err());
+
+    let l = Length::Mm(-1.5);
+    assert_eq!(l.to_string().parse::<Length>().unwrap(), l);
+}
+
+#[test]
+fn length_units() {
+    assert!((inch!(1).to_cm() - 2.54).abs() < 1e-9);
+    assert!((cm!(2.54).to_pt() - 72.0).abs() < 1e-9);
+    assert!((px!(96).to_in() - 1.0).abs() < 1e-9);
+    assert!((pt!(72).to_mm() - 25.4).abs() < 1e-9);
+    assert!((Length::Pc(1.0).to_pt() - 12.0).abs() < 1e-9);
+}
+
+#[test]
+fn length_arithmetic() {
+    assert_eq!(cm!(1) + cm!(2), cm!(3));
+    assert_eq!(cm!(1) + mm!(5), cm!(1.5));
+    assert_eq!(mm!(5) + cm!(1), mm!(15));
+    assert_eq!(cm!(3) - mm!(10), cm!(2));
+    assert_eq!(cm!(3) * 2.0, cm!(6));
+    assert_eq!(cm!(3) / 2.0, cm!(1.5));
+    assert_eq!(-cm!(3), cm!(-3));
+
+    let widths = vec![cm!(2), mm!(15), inch!(1)];
+    let total = widths.into_iter().fold(cm!(0), |a, b| a + b);
+    assert!((total.to_cm() - 6.04).abs() < 1e-9);
+
+    assert!(cm!(1) > mm!(9));
+    assert!(pt!(72) < cm!(2.6));
+    assert_eq!(cm!(1), mm!(10));
+    assert_ne!(cm!(1), mm!(11));
+}
+
+#[test]
+fn angle() {
+    assert_eq!("45deg".parse::<Angle>().unwrap(), deg!(45));
+    assert_eq!("45".parse::<Angle>().unwrap(), deg!(45));
+    assert_eq!("100grad".parse::<Angle>().unwrap(), deg!(90));
+    assert!("45turn".parse::<Angle>().is_err());
+
+    assert!((Angle::Rad(std::f64::consts::PI).to_deg() - 180.0).abs() < 1e-9);
+    assert!((deg!(90).to_grad() - 100.0).abs() < 1e-9);
+    assert_eq!(deg!(30) + Angle::Grad(100.0), deg!(120));
+    assert_eq!(deg!(30) * 3.0, deg!(90));
+    assert!(deg!(91) > Angle::Grad(100.0));
+}
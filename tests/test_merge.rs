@@ -0,0 +1,118 @@
+use color::Rgb;
+
+use spreadsheet_ods::format::{create_date_dmy_format, create_date_mdy_format};
+use spreadsheet_ods::style::{AttrFoMargin, AttrText, FontFaceDecl, PageLayout};
+use spreadsheet_ods::{cm, read_ods, write_ods, Length, OdsError, Sheet, Style, WorkBook};
+
+fn book(color: Rgb<u8>, margin: Length, mdy: bool) -> WorkBook {
+    let mut wb = WorkBook::new();
+
+    wb.add_font(FontFaceDecl::new_with_name("Liberation Sans"));
+    let mut layout = PageLayout::new_default();
+    layout.set_margin(margin);
+    wb.add_pagelayout(layout);
+    let mut style = Style::new_table_style("ta1", "");
+    style.set_master_page_name("Default");
+    wb.add_style(style);
+
+    if mdy {
+        wb.add_format(create_date_mdy_format("date"));
+    } else {
+        wb.add_format(create_date_dmy_format("date"));
+    }
+    wb.add_style(Style::new_cell_style("date", "date"));
+    wb.add_style(Style::new_cell_style("plain", ""));
+
+    let mut style = Style::new_cell_style("head", "");
+    style.set_parent("plain");
+    style.text_mut().set_color(color);
+    style.text_mut().set_font_name("Liberation Sans");
+    wb.add_style(style);
+    wb.add_style(Style::new_col_style("co1", ""));
+    wb.add_style(Style::new_row_style("ro1", ""));
+
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_style("ta1");
+    sh.set_styled_value(0, 0, "Name", "head");
+    sh.set_styled_value(1, 0, "plain", "plain");
+    sh.set_cell_style(2, 0, "date");
+    sh.set_column_style(0, "co1");
+    sh.set_column_cell_style(1, "head");
+    sh.set_row_style(0, "ro1");
+    sh.set_row_cell_style(1, "head");
+    wb.push_sheet(sh);
+
+    wb
+}
+
+#[test]
+fn import_sheet() -> Result<(), OdsError> {
+    let mut wb = book(Rgb::new(0, 0, 0), cm!(1), false);
+    let other = book(Rgb::new(255, 0, 0), cm!(2), true);
+
+    let idx = wb.import_sheet(&other, 0);
+    assert_eq!(idx, 1);
+
+    // equal definitions are shared
+    assert!(wb.style("plain_2").is_none());
+    assert!(wb.style("co1_2").is_none());
+    assert!(wb.font("Liberation Sans_2").is_none());
+
+    let sh = wb.sheet(idx);
+    assert_eq!(sh.name(), "Data");
+    assert_eq!(sh.cell_style(0, 0).map(|v| v.as_str()), Some("head_2"));
+    assert_eq!(sh.cell_style(1, 0).map(|v| v.as_str()), Some("plain"));
+    assert_eq!(sh.cell_style(2, 0).map(|v| v.as_str()), Some("date_2"));
+    assert_eq!(sh.column_style(0).map(|v| v.as_str()), Some("co1"));
+    assert_eq!(sh.column_cell_style(1).map(|v| v.as_str()), Some("head_2"));
+    assert_eq!(sh.row_cell_style(1).map(|v| v.as_str()), Some("head_2"));
+    assert_eq!(sh.style().map(|v| v.as_str()), Some("ta1_2"));
+
+    let head = wb.style("head_2").unwrap();
+    assert_eq!(head.parent().map(|v| v.as_str()), Some("plain"));
+    let date = wb.style("date_2").unwrap();
+    assert_eq!(date.value_format().map(|v| v.as_str()), Some("date_2"));
+    assert!(wb.format("date_2").is_some());
+    let table = wb.style("ta1_2").unwrap();
+    assert_eq!(
+        table.master_page_name().map(|v| v.as_str()),
+        Some("Default_2")
+    );
+    assert!(wb.pagelayout("Mpm1_2").is_some());
+
+    // the original sheet is unchanged
+    assert_eq!(
+        wb.sheet(0).cell_style(0, 0).map(|v| v.as_str()),
+        Some("head")
+    );
+
+    // importing the same sheet again uses the renamed definitions
+    wb.import_sheet(&other, 0);
+    assert_eq!(
+        wb.sheet(2).cell_style(0, 0).map(|v| v.as_str()),
+        Some("head_2")
+    );
+
+    write_ods(&wb, "test_out/test_merge.ods")?;
+    Ok(())
+}
+
+#[test]
+fn import_sheet_orders() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let other = read_ods("tests/orders.ods")?;
+    for idx in 0..other.num_sheets() {
+        wb.import_sheet(&other, idx);
+    }
+    assert_eq!(wb.num_sheets(), other.num_sheets());
+    for idx in 0..other.num_sheets() {
+        for ((row, col), cell) in other.sheet(idx).iter() {
+            if let Some(style) = cell.style() {
+                assert_eq!(wb.style(style), other.style(style));
+                assert_eq!(wb.sheet(idx).cell_style(row, col), Some(style));
+            }
+        }
+    }
+    write_ods(&wb, "test_out/test_merge_orders.ods")?;
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use color::Rgb;
 
 use spreadsheet_ods::{cm, Length, OdsError, read_ods, WorkBook, write_ods};
-use spreadsheet_ods::style::{AttrFoBackgroundColor, AttrFoMargin, AttrFoMinHeight, PageLayout};
+use spreadsheet_ods::style::{
+    AttrFoBackgroundColor, AttrFoMargin, AttrFoMinHeight, AttrMap, PageLayout,
+};
 
 #[test]
 fn pagelayout() -> Result<(), OdsError> {
@@ -52,3 +54,18 @@ fn crpagelayout() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn content_size() -> Result<(), OdsError> {
+    let mut pl = PageLayout::new_default();
+    assert_eq!(pl.content_width()?, None);
+
+    pl.set_page_size(Length::Mm(210.0), Length::Mm(297.0));
+    pl.set_margin(cm!(2));
+    pl.set_attr("fo:margin-left", "10%".to_string());
+    assert_eq!(pl.page_width()?, Some(Length::Mm(210.0)));
+    assert_eq!(pl.content_width()?, Some(Length::Mm(169.0)));
+    assert_eq!(pl.content_height()?, Some(Length::Mm(257.0)));
+
+    Ok(())
+}
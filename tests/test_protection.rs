@@ -0,0 +1,73 @@
+use spreadsheet_ods::protection::{ProtectionDigest, ProtectionOptions};
+use spreadsheet_ods::style::{AttrMap, AttrTableCell, CellProtect, Style};
+use spreadsheet_ods::{read_ods, write_ods, OdsError, Sheet, WorkBook};
+
+#[test]
+fn protect() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    let mut st = Style::new_cell_style("unlocked", "");
+    st.cell_mut().set_cell_protect(CellProtect::None);
+    st.cell_mut().set_print_content(true);
+    wb.add_style(st);
+
+    let mut st = Style::new_cell_style("locked", "");
+    st.cell_mut()
+        .set_cell_protect(CellProtect::ProtectedFormulaHidden);
+    wb.add_style(st);
+
+    let mut sh = Sheet::new_with_name("sha256");
+    sh.set_styled_value(0, 0, 1, "unlocked");
+    sh.set_styled_value(0, 1, 2, "locked");
+    let mut options = ProtectionOptions::new();
+    options.set_select_protected_cells(false);
+    options.set_insert_rows(true);
+    options.set_delete_rows(true);
+    sh.protect("secret", options);
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("sha1");
+    sh.set_value(0, 0, 1);
+    let mut options = ProtectionOptions::new();
+    options.set_digest(ProtectionDigest::Sha1);
+    sh.protect("secret", options);
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("open");
+    sh.set_value(0, 0, 1);
+    wb.push_sheet(sh);
+
+    write_ods(&wb, "test_out/protection.ods")?;
+
+    let wb = read_ods("test_out/protection.ods")?;
+
+    let p = wb.sheet(0).protection().unwrap();
+    assert_eq!(
+        p.key().unwrap(),
+        "K7gNU3sdo+OL0wNhqoVWhr3g6s1xYv72ol/pe/Unols="
+    );
+    assert!(p.check_password("secret"));
+    assert!(!p.check_password("Secret"));
+    assert_eq!(p.options().digest(), ProtectionDigest::Sha256);
+    assert!(!p.options().select_protected_cells());
+    assert!(p.options().select_unprotected_cells());
+    assert!(!p.options().insert_columns());
+    assert!(p.options().insert_rows());
+    assert!(!p.options().delete_columns());
+    assert!(p.options().delete_rows());
+
+    let p = wb.sheet(1).protection().unwrap();
+    assert_eq!(p.key().unwrap(), "5en6G6MezRroT3XKqkdPOmY/BfQ=");
+    assert_eq!(p.options().digest(), ProtectionDigest::Sha1);
+    assert!(p.check_password("secret"));
+
+    assert!(!wb.sheet(2).is_protected());
+
+    let st = wb.style("locked").unwrap();
+    assert_eq!(
+        st.cell().attr("style:cell-protect").unwrap(),
+        "protected formula-hidden"
+    );
+
+    Ok(())
+}
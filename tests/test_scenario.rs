@@ -0,0 +1,104 @@
+use color::Rgb;
+
+use spreadsheet_ods::scenario::Scenario;
+use spreadsheet_ods::style::AttrText;
+use spreadsheet_ods::{read_ods, write_ods, CellRange, OdsError, Sheet, WorkBook};
+
+#[test]
+fn scenario() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    let mut sh = Sheet::new_with_name("Plan");
+    sh.set_value(0, 0, "Growth");
+    sh.set_value(0, 1, 0.02);
+    sh.set_value(1, 0, "Cost");
+    sh.set_value(1, 1, 100);
+    sh.set_formula(2, 1, "of:=[.B1]*[.B2]");
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new_with_name("Other"));
+
+    let mut scenario = Scenario::new();
+    scenario.add_range(CellRange::local(0, 1, 1, 1));
+    scenario.set_active(true);
+    scenario.set_border_color(Rgb::new(192, 0, 0));
+    scenario.set_comment("Best case");
+    let best = wb.add_scenario(0, "Best", scenario)?;
+    assert_eq!(best, 1);
+
+    let mut scenario = Scenario::new();
+    scenario.add_range(CellRange::local(0, 1, 1, 1));
+    scenario.set_comment("Worst case");
+    let worst = wb.add_scenario(0, "Worst", scenario)?;
+    assert_eq!(worst, 2);
+    wb.sheet_mut(worst).set_value(0, 1, -0.05);
+    wb.sheet_mut(worst).set_value(1, 1, 150);
+
+    assert_eq!(wb.scenarios(0), vec![1, 2]);
+    assert_eq!(wb.scenario_target(worst), Some(0));
+    assert_eq!(wb.scenario_target(0), None);
+    assert!(wb.add_scenario(best, "Nested", Scenario::new()).is_err());
+
+    write_ods(&wb, "test_out/scenario.ods")?;
+    let mut wb = read_ods("test_out/scenario.ods")?;
+
+    assert_eq!(wb.num_sheets(), 4);
+    assert!(!wb.sheet(0).is_scenario());
+    assert!(!wb.sheet(3).is_scenario());
+    let s = wb.sheet(best).scenario().unwrap();
+    assert!(s.active());
+    assert!(s.copy_back());
+    assert_eq!(s.border_color(), Some(Rgb::new(192, 0, 0)));
+    assert_eq!(s.comment().unwrap(), "Best case");
+    assert_eq!(s.ranges().len(), 1);
+    assert_eq!(s.ranges()[0].row(), 0);
+    assert_eq!(s.ranges()[0].to_row(), 1);
+    assert_eq!(s.ranges()[0].col(), 1);
+    assert!(!wb.sheet(worst).scenario().unwrap().active());
+
+    // Changes in the target are copied back to the active scenario.
+    wb.sheet_mut(0).set_value(1, 1, 110);
+    wb.activate_scenario(worst)?;
+    assert_eq!(wb.sheet(0).value(0, 1).as_f64_or(0.0), -0.05);
+    assert_eq!(wb.sheet(0).value(1, 1).as_f64_or(0.0), 150.0);
+    assert_eq!(wb.sheet(0).formula(2, 1).unwrap(), "of:=[.B1]*[.B2]");
+    assert!(!wb.sheet(best).scenario().unwrap().active());
+    assert!(wb.sheet(worst).scenario().unwrap().active());
+
+    wb.activate_scenario(best)?;
+    assert_eq!(wb.sheet(0).value(0, 1).as_f64_or(0.0), 0.02);
+    assert_eq!(wb.sheet(0).value(1, 1).as_f64_or(0.0), 110.0);
+
+    assert!(wb.activate_scenario(0).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn scenario_whole_column() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut sh = Sheet::new_with_name("Plan");
+    sh.set_value(0, 0, "keep");
+    sh.set_value(3, 1, 1);
+    sh.set_value(7, 1, 2);
+    sh.cell_format_mut(4, 1).text_mut().set_font_bold();
+    wb.push_sheet(sh);
+
+    let mut scenario = Scenario::new();
+    scenario.add_range(CellRange::local(0, 1, u32::MAX, 1));
+    let idx = wb.add_scenario(0, "Column", scenario)?;
+    assert_eq!(wb.sheet(idx).value(3, 1).as_f64_or(0.0), 1.0);
+    assert!(wb.sheet(idx).cell(0, 0).is_none());
+    assert!(wb.sheet(idx).cell_format(4, 1).is_some());
+
+    wb.sheet_mut(idx).set_value(5, 1, 3);
+    wb.sheet_mut(idx).remove_cell(7, 1);
+    wb.activate_scenario(idx)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_str_or(""), "keep");
+    assert_eq!(sh.value(3, 1).as_f64_or(0.0), 1.0);
+    assert_eq!(sh.value(5, 1).as_f64_or(0.0), 3.0);
+    assert!(sh.cell(7, 1).is_none());
+    assert!(sh.cell_format(4, 1).is_some());
+
+    Ok(())
+}
@@ -0,0 +1,216 @@
+#![cfg(feature = "serde")]
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use time::Duration;
+
+use spreadsheet_ods::serde_rows;
+use spreadsheet_ods::{read_ods, write_ods, OdsError, Sheet, Value, WorkBook};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Kind {
+    Income,
+    Expense,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Booking {
+    name: String,
+    kind: Kind,
+    count: u32,
+    amount: f64,
+    paid: bool,
+    #[serde(with = "serde_rows::date")]
+    due: NaiveDate,
+    #[serde(with = "serde_rows::datetime")]
+    booked: NaiveDateTime,
+    #[serde(with = "serde_rows::duration")]
+    spent: Duration,
+    note: Option<String>,
+}
+
+fn bookings() -> Vec<Booking> {
+    vec![
+        Booking {
+            name: "rent".to_string(),
+            kind: Kind::Expense,
+            count: 1,
+            amount: 800.0,
+            paid: true,
+            due: NaiveDate::from_ymd(2021, 1, 1),
+            booked: NaiveDate::from_ymd(2020, 12, 28).and_hms(10, 15, 0),
+            spent: Duration::minutes(5),
+            note: None,
+        },
+        Booking {
+            name: "salary".to_string(),
+            kind: Kind::Income,
+            count: 2,
+            amount: 1234.5,
+            paid: false,
+            due: NaiveDate::from_ymd(2021, 1, 31),
+            booked: NaiveDate::from_ymd(2021, 1, 2).and_hms(8, 0, 30),
+            spent: Duration::seconds(90),
+            note: Some("bonus".to_string()),
+        },
+    ]
+}
+
+#[test]
+fn serde_rows() -> Result<(), OdsError> {
+    let mut sh = Sheet::new_with_name("Bookings");
+    sh.serialize_rows(&bookings(), true)?;
+
+    assert_eq!(sh.value(0, 0).as_str_or(""), "name");
+    assert_eq!(sh.value(0, 8).as_str_or(""), "note");
+    assert_eq!(sh.value(1, 1).as_str_or(""), "Expense");
+    assert!(matches!(sh.value(1, 4), Value::Boolean(true)));
+    assert!(matches!(sh.value(1, 5), Value::DateTime(_)));
+    assert!(matches!(sh.value(1, 7), Value::TimeDuration(_)));
+    assert!(matches!(sh.value(1, 8), Value::Empty));
+    assert_eq!(sh.value(2, 3).as_f64_or(0.0), 1234.5);
+
+    let mut wb = WorkBook::new();
+    wb.push_sheet(sh);
+    write_ods(&wb, "test_out/serde.ods")?;
+    let wb = read_ods("test_out/serde.ods")?;
+
+    let read: Vec<Booking> = wb.sheet(0).deserialize_rows(0)?;
+    assert_eq!(read, bookings());
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Short {
+    amount: f64,
+    name: String,
+    extra: Option<u32>,
+}
+
+#[test]
+fn serde_by_header() -> Result<(), OdsError> {
+    let mut sh = Sheet::new();
+    sh.set_value(2, 0, "name");
+    sh.set_value(2, 1, "ignored");
+    sh.set_value(2, 3, "amount");
+    sh.set_value(3, 0, "a");
+    sh.set_value(3, 3, 1);
+    sh.set_value(5, 0, "b");
+    sh.set_value(5, 3, 2.5);
+
+    let read: Vec<Short> = sh.deserialize_rows(2)?;
+    assert_eq!(
+        read,
+        vec![
+            Short {
+                amount: 1.0,
+                name: "a".to_string(),
+                extra: None
+            },
+            Short {
+                amount: 2.5,
+                name: "b".to_string(),
+                extra: None
+            }
+        ]
+    );
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Counted {
+    name: String,
+    count: u32,
+}
+
+#[test]
+fn serde_errors() {
+    let mut sh = Sheet::new();
+    sh.set_value(0, 0, "name");
+    sh.set_value(0, 1, "count");
+    sh.set_value(1, 0, "a");
+    sh.set_value(1, 1, 1);
+    sh.set_value(2, 0, "b");
+    sh.set_value(2, 1, 1.5);
+
+    let err = sh.deserialize_rows::<Counted>(0).unwrap_err();
+    assert!(err.to_string().contains(".B3"), "{}", err);
+
+    sh.set_value(2, 1, "many");
+    let err = sh.deserialize_rows::<Counted>(0).unwrap_err();
+    assert!(err.to_string().contains(".B3"), "{}", err);
+
+    let mut sh = Sheet::new();
+    sh.set_value(0, 0, "name");
+    sh.set_value(1, 0, "a");
+    let err = sh.deserialize_rows::<Counted>(0).unwrap_err();
+    assert!(err.to_string().contains("count"), "{}", err);
+
+    let mut sh = Sheet::new();
+    let err = sh.serialize_rows(&[vec![1, 2]], false).unwrap_err();
+    assert!(err.to_string().contains(".A1"), "{}", err);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Skipped {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    count: u32,
+}
+
+#[test]
+fn serde_skipped_field() -> Result<(), OdsError> {
+    let records = vec![
+        Skipped {
+            name: "a".to_string(),
+            note: None,
+            count: 1,
+        },
+        Skipped {
+            name: "b".to_string(),
+            note: Some("x".to_string()),
+            count: 2,
+        },
+    ];
+
+    let mut sh = Sheet::new();
+    sh.serialize_rows(&records, true)?;
+    assert_eq!(sh.value(0, 1).as_str_or(""), "note");
+    assert_eq!(sh.value(0, 2).as_str_or(""), "count");
+    assert!(matches!(sh.value(1, 1), Value::Empty));
+    assert_eq!(sh.value(1, 2).as_i32_or(0), 1);
+    assert_eq!(sh.value(2, 1).as_str_or(""), "x");
+    assert_eq!(sh.value(2, 2).as_i32_or(0), 2);
+
+    assert_eq!(sh.deserialize_rows::<Skipped>(0)?, records);
+
+    Ok(())
+}
+
+#[cfg(feature = "use_decimal")]
+#[test]
+fn serde_decimal() -> Result<(), OdsError> {
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Price {
+        #[serde(with = "serde_rows::decimal")]
+        price: Decimal,
+    }
+
+    let prices = vec![Price {
+        price: Decimal::from_str("12.25").unwrap(),
+    }];
+
+    let mut sh = Sheet::new();
+    sh.serialize_rows(&prices, true)?;
+    assert_eq!(sh.value(1, 0).as_f64_or(0.0), 12.25);
+    assert_eq!(sh.deserialize_rows::<Price>(0)?, prices);
+
+    Ok(())
+}
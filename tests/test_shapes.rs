@@ -0,0 +1,84 @@
+use color::Rgb;
+
+use spreadsheet_ods::draw::{Shape, ShapeType};
+use spreadsheet_ods::style::{AttrGraphic, AttrMap, StyleFor};
+use spreadsheet_ods::{cm, read_ods, write_ods, Length, OdsError, Sheet, Style, WorkBook};
+
+#[test]
+fn shapes() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    let mut style = Style::new_graphic_style("callout");
+    style.graphic_mut().set_fill_color(Rgb::new(255, 255, 204));
+    style
+        .graphic_mut()
+        .set_stroke(cm!(0.05), Rgb::new(255, 0, 0));
+    wb.add_style(style);
+
+    let mut sh = Sheet::new_with_name("Shapes");
+    sh.set_value(0, 0, "value");
+
+    let mut shape = Shape::new_rect(cm!(1), cm!(2), cm!(3), cm!(4));
+    shape.set_name("box");
+    shape.set_style("callout");
+    shape.set_z_index(2);
+    sh.add_shape(shape);
+
+    let mut shape = Shape::new_ellipse(cm!(5), cm!(1), cm!(2), cm!(2));
+    shape.set_text("inside");
+    sh.add_shape(shape);
+
+    sh.add_shape(Shape::new_line(cm!(0), cm!(0), cm!(4), cm!(2.5)));
+    sh.add_shape(Shape::new_connector(cm!(1), cm!(1), cm!(6), cm!(1)));
+
+    let mut shape = Shape::new_text_box(cm!(0.5), cm!(0.5), cm!(5), cm!(2), "Note\nSecond line");
+    shape.set_style("callout");
+    shape.set_anchor_cell(0, 0);
+    sh.add_shape(shape);
+
+    let mut shape = Shape::new_rect(cm!(0), cm!(0), cm!(1), cm!(1));
+    shape.set_anchor_cell(5, 3);
+    sh.add_shape(shape);
+
+    wb.push_sheet(sh);
+
+    write_ods(&wb, "test_out/shapes.ods")?;
+    let wb = read_ods("test_out/shapes.ods")?;
+
+    let style = wb.style("callout").unwrap();
+    assert_eq!(style.family(), StyleFor::Graphic);
+    assert_eq!(style.graphic().attr("draw:fill-color").unwrap(), "#ffffcc");
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_str_or(""), "value");
+
+    let shapes = sh.shapes();
+    assert_eq!(shapes.len(), 6);
+
+    // Shapes in table:shapes come first, then the ones in the cells.
+    assert_eq!(shapes[0].shape_type(), ShapeType::Rect);
+    assert_eq!(shapes[0].anchor_cell(), None);
+    assert_eq!(shapes[0].name(), "box");
+    assert_eq!(shapes[0].z_index(), Some(2));
+    assert_eq!(shapes[0].position(), (cm!(1), cm!(2)));
+    assert_eq!(shapes[0].size(), (cm!(3), cm!(4)));
+
+    assert_eq!(shapes[1].shape_type(), ShapeType::Ellipse);
+    assert_eq!(shapes[1].text().unwrap(), "inside");
+
+    assert_eq!(shapes[2].shape_type(), ShapeType::Line);
+    assert_eq!(shapes[2].end(), (cm!(4), cm!(2.5)));
+
+    assert_eq!(shapes[3].shape_type(), ShapeType::Connector);
+    assert_eq!(shapes[3].position(), (cm!(1), cm!(1)));
+
+    assert_eq!(shapes[4].shape_type(), ShapeType::TextBox);
+    assert_eq!(shapes[4].anchor_cell(), Some((0, 0)));
+    assert_eq!(shapes[4].text().unwrap(), "Note\nSecond line");
+    assert_eq!(shapes[4].style().unwrap(), "callout");
+
+    assert_eq!(shapes[5].shape_type(), ShapeType::Rect);
+    assert_eq!(shapes[5].anchor_cell(), Some((5, 3)));
+
+    Ok(())
+}
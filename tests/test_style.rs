@@ -1,7 +1,13 @@
+use std::io::Read;
+
 use color::Rgb;
 
-use spreadsheet_ods::style::{AttrText, StyleMap, StyleOrigin, StyleUse};
-use spreadsheet_ods::{write_ods, CellRef, OdsError, Sheet, Style, WorkBook};
+use spreadsheet_ods::format::create_date_dmy_format;
+use spreadsheet_ods::style::{
+    AttrFoBorder, AttrMap, AttrText, Border, FontFaceDecl, StyleFor, StyleMap, StyleOrigin,
+    StyleUse,
+};
+use spreadsheet_ods::{pt, read_ods, write_ods, CellRef, Length, OdsError, Sheet, Style, WorkBook};
 
 #[test]
 fn teststyles() -> Result<(), OdsError> {
@@ -45,3 +51,131 @@ fn teststyles() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn effective_cell_style() {
+    let mut wb = WorkBook::new();
+    wb.add_format(create_date_dmy_format("date_dmy"));
+
+    let mut st = Style::new_cell_style("", "");
+    st.text_mut().set_font_name("Liberation Sans");
+    st.cell_mut()
+        .set_border(pt!(0.5), Border::Solid, Rgb::new(0, 0, 0));
+    wb.set_default_style(st);
+
+    let mut st = Style::new_cell_style("base", "date_dmy");
+    st.text_mut().set_color(Rgb::new(255, 0, 0));
+    st.cell_mut()
+        .set_border_top(pt!(1), Border::Solid, Rgb::new(0, 0, 255));
+    wb.add_style(st);
+
+    let mut st = Style::new_cell_style("child", "");
+    st.set_parent("base");
+    st.text_mut().set_font_bold();
+    wb.add_style(st);
+
+    let mut st = Style::new_cell_style("row", "");
+    st.set_parent("base");
+    st.cell_mut()
+        .set_border(pt!(2), Border::Double, Rgb::new(0, 0, 0));
+    wb.add_style(st);
+
+    let mut sh = Sheet::new_with_name("One");
+    sh.set_styled_value(0, 0, 1.0, "child");
+    sh.set_value(1, 0, 2.0);
+    sh.set_row_cell_style(1, "row");
+    sh.set_column_cell_style(1, "child");
+    wb.push_sheet(sh);
+
+    let st = wb.effective_cell_style(0, 0, 0);
+    assert_eq!(st.name().unwrap(), "child");
+    assert_eq!(st.text().attr("fo:font-weight").unwrap(), "bold");
+    assert_eq!(st.text().attr("fo:color").unwrap(), "#ff0000");
+    assert_eq!(
+        st.text().attr("style:font-name").unwrap(),
+        "Liberation Sans"
+    );
+    assert!(st.cell().attr("fo:border").is_some());
+    assert!(st.cell().attr("fo:border-top").is_some());
+    assert_eq!(st.value_format().unwrap().name(), "date_dmy");
+
+    // row before column, the shorthand replaces the side of the parent
+    let st = wb.effective_cell_style(0, 1, 1);
+    assert_eq!(st.name().unwrap(), "row");
+    assert!(st.cell().attr("fo:border-top").is_none());
+    assert!(st.text().attr("fo:font-weight").is_none());
+
+    // column
+    let st = wb.effective_cell_style(0, 5, 1);
+    assert_eq!(st.name().unwrap(), "child");
+
+    // default-style only
+    let st = wb.effective_cell_style(0, 5, 5);
+    assert!(st.name().is_none());
+    assert!(st.value_format().is_none());
+    assert_eq!(
+        st.text().attr("style:font-name").unwrap(),
+        "Liberation Sans"
+    );
+}
+
+#[test]
+fn common_styles() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    let mut st = Style::new_cell_style("", "");
+    st.text_mut().set_font_name("Liberation Sans");
+    wb.set_default_style(st);
+    let mut st = Style::new_graphic_style("");
+    st.text_mut().set_font_name("Liberation Serif");
+    wb.set_default_style(st);
+
+    wb.add_font(FontFaceDecl::new_with_name("Liberation Sans"));
+    let mut st = Style::new_cell_style("Heading", "");
+    st.text_mut().set_font_bold();
+    st.text_mut().set_font_name("Liberation Sans");
+    wb.add_common_style(st);
+    wb.add_style(Style::new_cell_style("ce1", ""));
+
+    let mut sh = Sheet::new_with_name("One");
+    sh.set_styled_value(0, 0, "Title", "Heading");
+    sh.set_styled_value(1, 0, "Text", "ce1");
+    wb.push_sheet(sh);
+
+    write_ods(&wb, "test_out/test_common_styles.ods")?;
+
+    let mut zip = zip::ZipArchive::new(std::fs::File::open("test_out/test_common_styles.ods")?)?;
+    let mut styles = String::new();
+    zip.by_name("styles.xml")?.read_to_string(&mut styles)?;
+    let mut content = String::new();
+    zip.by_name("content.xml")?.read_to_string(&mut content)?;
+
+    assert!(styles.contains("style:display-name=\"Heading\""));
+    assert!(styles.contains("<style:font-face style:name=\"Liberation Sans\""));
+    assert_eq!(styles.matches("<style:default-style ").count(), 2);
+    assert!(!content.contains("style:name=\"Heading\""));
+    assert!(content.contains("style:name=\"ce1\""));
+
+    let wb = read_ods("test_out/test_common_styles.ods")?;
+    let st = wb.style("Heading").unwrap();
+    assert_eq!(st.styleuse(), StyleUse::Named);
+    assert_eq!(st.text().attr("fo:font-weight").unwrap(), "bold");
+    assert_eq!(
+        wb.default_style(StyleFor::TableCell)
+            .unwrap()
+            .text()
+            .attr("style:font-name")
+            .unwrap(),
+        "Liberation Sans"
+    );
+    assert_eq!(
+        wb.default_style(StyleFor::Graphic)
+            .unwrap()
+            .text()
+            .attr("style:font-name")
+            .unwrap(),
+        "Liberation Serif"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+
+use spreadsheet_ods::trackedchanges::{ChangeState, ChangeType};
+use spreadsheet_ods::{read_ods, write_ods, CellRange, OdsError};
+
+#[test]
+fn read_changes() -> Result<(), OdsError> {
+    let wb = read_ods("tests/tracked.ods")?;
+
+    let changes = wb.tracked_changes();
+    assert_eq!(changes.len(), 4);
+
+    let c = &changes[0];
+    assert_eq!(c.id(), "ct1");
+    assert_eq!(c.change_type(), ChangeType::CellContent);
+    assert_eq!(c.state(), ChangeState::Pending);
+    assert_eq!(c.author().unwrap(), "Anna");
+    assert_eq!(
+        *c.date().unwrap(),
+        NaiveDate::from_ymd(2020, 5, 2).and_hms(10, 15, 0)
+    );
+    assert_eq!(c.comment().unwrap(), "price update");
+    assert_eq!(*c.range(), CellRange::remote("Prices", 0, 0, 0, 0));
+    assert_eq!(c.previous().unwrap().as_f64_or(0.0), 10.0);
+
+    let c = &changes[1];
+    assert_eq!(c.change_type(), ChangeType::InsertRows);
+    assert_eq!(c.range().row(), 2);
+    assert_eq!(c.range().to_row(), 3);
+
+    let c = &changes[2];
+    assert_eq!(c.change_type(), ChangeType::DeleteColumns);
+    assert_eq!(c.range().col(), 1);
+    assert_eq!(c.deleted().len(), 1);
+    assert_eq!(c.deleted()[0].1.as_str_or(""), "gone");
+
+    let c = &changes[3];
+    assert_eq!(c.change_type(), ChangeType::Movement);
+    assert_eq!(
+        *c.source().unwrap(),
+        CellRange::remote("Prices", 0, 3, 1, 3)
+    );
+    assert_eq!(*c.range(), CellRange::remote("Prices", 0, 5, 1, 5));
+
+    Ok(())
+}
+
+#[test]
+fn accept_changes() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/tracked.ods")?;
+    wb.accept_all_changes();
+    assert!(wb.tracked_changes().is_empty());
+
+    write_ods(&wb, "test_out/tracked_accept.ods")?;
+    let wb = read_ods("test_out/tracked_accept.ods")?;
+
+    assert!(wb.tracked_changes().is_empty());
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_f64_or(0.0), 12.0);
+    assert_eq!(sh.value(0, 1).as_str_or(""), "next");
+    assert_eq!(sh.value(2, 0).as_str_or(""), "new1");
+
+    Ok(())
+}
+
+#[test]
+fn reject_changes() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/tracked.ods")?;
+    wb.reject_all_changes();
+    assert!(wb.tracked_changes().is_empty());
+
+    write_ods(&wb, "test_out/tracked_reject.ods")?;
+    let wb = read_ods("test_out/tracked_reject.ods")?;
+
+    assert!(wb.tracked_changes().is_empty());
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_f64_or(0.0), 10.0);
+    assert_eq!(sh.value(0, 1).as_str_or(""), "gone");
+    assert_eq!(sh.value(0, 2).as_str_or(""), "next");
+    assert_eq!(sh.value(0, 4).as_str_or(""), "m1");
+    assert_eq!(sh.value(1, 4).as_str_or(""), "m2");
+    assert_eq!(sh.value(2, 0).as_str_or(""), "old");
+
+    Ok(())
+}
+
+#[test]
+fn reject_delete_table() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/tracked_table.ods")?;
+
+    let c = &wb.tracked_changes()[0];
+    assert_eq!(c.change_type(), ChangeType::DeleteTable);
+    assert_eq!(c.deleted()[0].0.table(), None);
+
+    wb.reject_all_changes();
+    assert_eq!(wb.num_sheets(), 2);
+    assert_eq!(wb.sheet(0).name(), "Sheet2");
+    assert_eq!(wb.sheet(0).value(2, 1).as_str_or(""), "lost");
+    assert_eq!(wb.sheet(1).name(), "Sheet1");
+    assert_eq!(wb.sheet(1).value(0, 0).as_str_or(""), "kept");
+
+    Ok(())
+}
+
+#[test]
+fn read_invalid_position() {
+    use std::io::{Read, Write};
+
+    // Copy of tracked.ods with a row insertion beyond the last row.
+    let mut src = zip::ZipArchive::new(std::fs::File::open("tests/tracked.ods").unwrap()).unwrap();
+    let path = "test_out/tracked_overflow.ods";
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    for i in 0..src.len() {
+        let mut file = src.by_index(i).unwrap();
+        let mut data = String::new();
+        file.read_to_string(&mut data).unwrap();
+        let data = data.replace(
+            "table:position=\"2\" table:count=\"2\"",
+            "table:position=\"4294967295\" table:count=\"2\"",
+        );
+        zip.start_file(file.name(), zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(data.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    assert!(read_ods(path).is_err());
+}
@@ -0,0 +1,173 @@
+use color::Rgb;
+
+use spreadsheet_ods::format::create_date_dmy_format;
+use spreadsheet_ods::style::{AttrMap, AttrText, FontFaceDecl, PageLayout, StyleMap, StyleUse};
+use spreadsheet_ods::{
+    read_ods, write_ods_with_options, CellRef, OdsError, OdsWriteOptions, Sheet, Style, WorkBook,
+};
+
+fn book() -> WorkBook {
+    let mut wb = WorkBook::new();
+
+    for name in &["Liberation Sans", "Liberation Sans1"] {
+        let mut font = FontFaceDecl::new_with_name(*name);
+        font.set_attr("svg:font-family", "'Liberation Sans'".to_string());
+        wb.add_font(font);
+    }
+    wb.add_font(FontFaceDecl::new_with_name("Unused"));
+
+    let mut layout = PageLayout::new_default();
+    layout.set_name("Mpm1".to_string());
+    wb.add_pagelayout(layout);
+    let mut layout = PageLayout::new_report();
+    layout.set_name("Mpm2".to_string());
+    wb.add_pagelayout(layout);
+
+    wb.add_format(create_date_dmy_format("N1"));
+    wb.add_format(create_date_dmy_format("N2"));
+    wb.add_format(create_date_dmy_format("N3"));
+
+    wb.add_style(Style::new_cell_style("date1", "N1"));
+    wb.add_style(Style::new_cell_style("date2", "N2"));
+    wb.add_style(Style::new_cell_style("date3", "N3"));
+
+    let mut style = Style::new_cell_style("red1", "");
+    style.text_mut().set_color(Rgb::new(255, 0, 0));
+    style.text_mut().set_font_name("Liberation Sans");
+    wb.add_style(style);
+    let mut style = Style::new_cell_style("red2", "");
+    style.text_mut().set_color(Rgb::new(255, 0, 0));
+    style.text_mut().set_font_name("Liberation Sans1");
+    wb.add_style(style);
+
+    let mut style = Style::new_cell_style("named", "");
+    style.set_styleuse(StyleUse::Named);
+    wb.add_style(style);
+    wb.add_style(Style::new_cell_style("unused", ""));
+    wb.add_style(Style::new_cell_style("applied", ""));
+
+    let mut style = Style::new_cell_style("cond", "");
+    style.push_stylemap(StyleMap::new(
+        "cell-content()>0",
+        "applied",
+        CellRef::remote("Data", 0, 0),
+    ));
+    wb.add_style(style);
+
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_styled_value(0, 0, 1.0, "cond");
+    sh.set_styled_value(1, 0, "a", "red2");
+    sh.set_styled_value(1, 1, "b", "red1");
+    sh.set_cell_style(2, 0, "date2");
+    sh.set_column_cell_style(1, "date1");
+    wb.push_sheet(sh);
+
+    wb
+}
+
+#[test]
+fn remove_unused_styles() {
+    let mut wb = book();
+    wb.remove_unused_styles();
+
+    // duplicates merged into the first name
+    assert!(wb.style("red2").is_none());
+    assert!(wb.style("date2").is_none());
+    assert!(wb.style("date3").is_none());
+    assert_eq!(wb.sheet(0).cell_style(1, 0).unwrap(), "red1");
+    assert_eq!(wb.sheet(0).cell_style(2, 0).unwrap(), "date1");
+    assert_eq!(wb.sheet(0).column_cell_style(1).unwrap(), "date1");
+    assert!(wb.format("N1").is_some());
+    assert!(wb.format("N2").is_none());
+    assert!(wb.format("N3").is_none());
+    assert_eq!(
+        wb.style("red1")
+            .unwrap()
+            .text()
+            .attr("style:font-name")
+            .unwrap(),
+        "Liberation Sans"
+    );
+
+    // unused removed, references followed
+    assert!(wb.style("unused").is_none());
+    assert!(wb.style("named").is_some());
+    assert!(wb.style("cond").is_some());
+    assert!(wb.style("applied").is_some());
+    assert!(wb.font("Liberation Sans").is_some());
+    assert!(wb.font("Liberation Sans1").is_none());
+    assert!(wb.font("Unused").is_none());
+    assert!(wb.pagelayout("Mpm1").is_some());
+    assert!(wb.pagelayout("Mpm2").is_none());
+}
+
+#[test]
+fn write_without_unused_styles() -> Result<(), OdsError> {
+    let wb = book();
+
+    let mut options = OdsWriteOptions::new();
+    options.set_remove_unused_styles(true);
+    write_ods_with_options(&wb, "test_out/test_unused_styles.ods", &options)?;
+
+    // the workbook itself is unchanged
+    assert!(wb.style("unused").is_some());
+
+    let wb = read_ods("test_out/test_unused_styles.ods")?;
+    assert!(wb.style("unused").is_none());
+    assert!(wb.style("red2").is_none());
+    assert_eq!(wb.sheet(0).cell_style(1, 0).unwrap(), "red1");
+    assert_eq!(wb.sheet(0).value(1, 0).as_str_or(""), "a");
+
+    Ok(())
+}
+
+#[test]
+fn keep_styles_of_passed_through_xml() -> Result<(), OdsError> {
+    use spreadsheet_ods::style::StyleFor;
+    use std::io::{Read, Write};
+
+    let mut wb = WorkBook::new();
+    wb.add_style(Style::new_graphic_style("gr1"));
+    wb.add_style(Style::new_graphic_style("gr2"));
+    wb.add_style(Style::new_with_name(StyleFor::Paragraph, "P1", ""));
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_value(0, 0, 1.0);
+    wb.push_sheet(sh);
+    let path = "test_out/test_unused_styles_xml.ods";
+    write_ods_with_options(&wb, path, &OdsWriteOptions::new())?;
+
+    // An image frame that is kept as xml.
+    let mut buf = Vec::new();
+    std::fs::File::open(path)?.read_to_end(&mut buf)?;
+    let mut src = zip::ZipArchive::new(std::io::Cursor::new(buf)).unwrap();
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    for i in 0..src.len() {
+        let mut file = src.by_index(i).unwrap();
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        if file.name() == "content.xml" {
+            let content = String::from_utf8(data).unwrap().replacen(
+                "<table:table-column",
+                "<table:shapes><draw:frame draw:style-name=\"gr1\" \
+                draw:text-style-name=\"P1\" svg:width=\"1cm\" svg:height=\"1cm\">\
+                <draw:image xlink:href=\"Pictures/image.png\"><text:p/></draw:image>\
+                </draw:frame></table:shapes><table:table-column",
+                1,
+            );
+            data = content.into_bytes();
+        }
+        zip.start_file(file.name(), zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(&data)?;
+    }
+    zip.finish().unwrap();
+
+    let mut wb = read_ods(path)?;
+    assert!(wb.style("gr1").is_some());
+    wb.remove_unused_styles();
+    assert!(wb.style("gr1").is_some());
+    assert!(wb.style("P1").is_some());
+    assert!(wb.style("gr2").is_none());
+
+    Ok(())
+}
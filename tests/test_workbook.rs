@@ -1,4 +1,12 @@
-use spreadsheet_ods::{currency, percent, SCell, Sheet, Value, ValueType, WorkBook};
+use color::Rgb;
+
+use spreadsheet_ods::style::{
+    AttrFoBreak, AttrStyleWritingMode, AttrTable, AttrTableCol, PageBreak, WritingMode,
+};
+use spreadsheet_ods::{
+    currency, percent, read_ods, write_ods, Length, OdsError, SCell, Sheet, Style, Value,
+    ValueType, WorkBook,
+};
 
 #[test]
 fn test_workbook() {
@@ -52,3 +60,87 @@ fn test_macros() {
     sh.set_value(0, 0, currency!("€", 20));
     sh.set_value(0, 0, percent!(17.22));
 }
+
+#[test]
+fn test_tab_color() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    wb.add_style(Style::new_table_style("ta1", ""));
+
+    let mut sh = Sheet::new_with_name("a");
+    sh.set_style("ta1");
+    sh.set_tab_color(&mut wb, Rgb::new(0, 128, 0));
+    sh.set_right_to_left(&mut wb, true);
+    assert_eq!(sh.style().unwrap(), "ta_1");
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("b");
+    sh.set_style("ta1");
+    wb.push_sheet(sh);
+
+    let mut sh = wb.sheet(0).clone();
+    sh.set_name("c");
+    sh.set_tab_color(&mut wb, Rgb::new(0, 0, 255));
+    assert_eq!(sh.style().unwrap(), "ta_2");
+    wb.push_sheet(sh);
+
+    write_ods(&wb, "test_out/tabcolor.ods")?;
+    let wb = read_ods("test_out/tabcolor.ods")?;
+
+    let style = wb.style(wb.sheet(0).style().unwrap()).unwrap();
+    assert_eq!(style.table().tab_color(), Some(Rgb::new(0, 128, 0)));
+    assert_eq!(style.table().writing_mode(), Some(WritingMode::RlTb));
+
+    let style = wb.style(wb.sheet(1).style().unwrap()).unwrap();
+    assert_eq!(style.table().tab_color(), None);
+
+    let style = wb.style(wb.sheet(2).style().unwrap()).unwrap();
+    assert_eq!(style.table().tab_color(), Some(Rgb::new(0, 0, 255)));
+    assert_eq!(style.table().writing_mode(), Some(WritingMode::RlTb));
+
+    Ok(())
+}
+
+#[test]
+fn col_width_per_sheet() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut style = Style::new_col_style("co1", "");
+    style.col_mut().set_break_before(PageBreak::Page);
+    wb.add_style(style);
+
+    let mut sh = Sheet::new_with_name("One");
+    sh.set_value(0, 3, 1);
+    sh.set_col_width(1, Length::Cm(2.0));
+    sh.set_row_height(0, Length::Cm(1.0));
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("Two");
+    sh.set_value(0, 3, 1);
+    sh.set_col_width(1, Length::Cm(5.0));
+    sh.set_col_width(2, Length::Cm(2.0));
+    sh.set_column_style(3, "co1");
+    sh.set_col_width(3, Length::Cm(2.0));
+    sh.set_row_height(0, Length::Cm(1.0));
+    wb.push_sheet(sh);
+
+    assert_eq!(wb.sheet(0).col_width(&wb, 1), Some(Length::Cm(2.0)));
+    assert_eq!(wb.sheet(1).col_width(&wb, 1), Some(Length::Cm(5.0)));
+    assert_eq!(wb.sheet(1).col_width(&wb, 0), None);
+
+    write_ods(&wb, "test_out/test_col_width.ods")?;
+    let wb = read_ods("test_out/test_col_width.ods")?;
+    let (one, two) = (wb.sheet(0), wb.sheet(1));
+    assert_eq!(one.col_width(&wb, 1), Some(Length::Cm(2.0)));
+    assert_eq!(two.col_width(&wb, 1), Some(Length::Cm(5.0)));
+    assert_eq!(two.col_width(&wb, 2), Some(Length::Cm(2.0)));
+    assert_eq!(two.col_width(&wb, 3), Some(Length::Cm(2.0)));
+    // shared when equal
+    assert_eq!(one.column_style(1), two.column_style(2));
+    assert_eq!(one.row_style(0), two.row_style(0));
+    // the column style is kept and not changed
+    assert_ne!(two.column_style(3), two.column_style(2));
+    let style = wb.style(two.column_style(3).unwrap()).unwrap();
+    assert_eq!(style.col().break_before(), Ok(Some(PageBreak::Page)));
+    assert_eq!(wb.style("co1").unwrap().col().col_width(), Ok(None));
+
+    Ok(())
+}
@@ -189,4 +189,26 @@ fn display_print() -> Result<(), OdsError> {
     write_ods(&wb, "test_out/display_print.ods")?;
 
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_duration() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+    let mut sh = Sheet::new();
+    sh.set_value(0, 0, time::Duration::milliseconds(1050));
+    sh.set_value(0, 1, time::Duration::milliseconds(-3_723_500));
+    wb.push_sheet(sh);
+
+    write_ods(&wb, "test_out/test_duration.ods")?;
+    let wb = read_ods("test_out/test_duration.ods")?;
+    let sh = wb.sheet(0);
+    assert_eq!(
+        sh.value(0, 0).as_timeduration_opt(),
+        Some(time::Duration::milliseconds(1050))
+    );
+    assert_eq!(
+        sh.value(0, 1).as_timeduration_opt(),
+        Some(time::Duration::milliseconds(-3_723_500))
+    );
+
+    Ok(())
+}
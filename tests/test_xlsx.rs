@@ -0,0 +1,353 @@
+use std::io::Read;
+
+use chrono::NaiveDate;
+use color::Rgb;
+use time::Duration;
+
+use spreadsheet_ods::format::create_date_dmy_format;
+use spreadsheet_ods::formula::{from_excel_formula, to_excel_formula};
+use spreadsheet_ods::style::{AttrFoBackgroundColor, AttrFoBorder, AttrMap, AttrText, Border};
+use spreadsheet_ods::{
+    cm, pt, read_ods, read_xlsx, write_xlsx, Length, OdsError, Sheet, Style, ValueType, Visibility,
+    WorkBook,
+};
+
+fn part(path: &str, name: &str) -> String {
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut buf = String::new();
+    zip.by_name(name).unwrap().read_to_string(&mut buf).unwrap();
+    // without the indentation of feature "indent_xml"
+    buf.lines().map(|v| v.trim_start()).collect()
+}
+
+#[test]
+fn excel_formula() {
+    assert_eq!(to_excel_formula("of:=[.A1]+1"), "A1+1");
+    assert_eq!(
+        to_excel_formula("of:=SUM([.$A$1:.B3];[Data.C1])"),
+        "SUM($A$1:B3,Data!C1)"
+    );
+    assert_eq!(
+        to_excel_formula("of:=IF([$'It''s'.A1]=\"a;[b]\";1;2)"),
+        "IF('It''s'!A1=\"a;[b]\",1,2)"
+    );
+    assert_eq!(to_excel_formula("of:=SUM([.A:.A])"), "SUM(A:A)");
+}
+
+#[test]
+fn excel_formula_import() {
+    assert_eq!(from_excel_formula("=A1+1"), "of:=[.A1]+1");
+    assert_eq!(
+        from_excel_formula("SUM($A$1:b3,Data!C1)"),
+        "of:=SUM([.$A$1:.B3];[Data.C1])"
+    );
+    assert_eq!(
+        from_excel_formula("IF('It''s'!A1=\"a,b\",1,2)"),
+        "of:=IF(['It''s'.A1]=\"a,b\";1;2)"
+    );
+    assert_eq!(
+        from_excel_formula("SUM(A:A,2:3)"),
+        "of:=SUM([.A:.A];[.2:.3])"
+    );
+    assert_eq!(
+        from_excel_formula("_xlfn.STDEV.S(A1:A3)"),
+        "of:=STDEV.S([.A1:.A3])"
+    );
+    assert_eq!(from_excel_formula("SUM({1,2;3,4})"), "of:=SUM({1;2|3;4})");
+    assert_eq!(from_excel_formula("LOG10(A1)"), "of:=LOG10([.A1])");
+}
+
+#[test]
+fn xlsx_write() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    wb.add_format(create_date_dmy_format("date_dmy"));
+    let mut style = Style::new_cell_style("head", "");
+    style.text_mut().set_font_bold();
+    style
+        .cell_mut()
+        .set_background_color(Rgb::new(192, 192, 192));
+    style
+        .cell_mut()
+        .set_border(pt!(0.75), Border::Solid, Rgb::new(0, 0, 0));
+    wb.add_style(style);
+    let style = Style::new_cell_style("date", "date_dmy");
+    wb.add_style(style);
+
+    let mut sh = Sheet::new_with_name("Data & More");
+    sh.set_col_width(0, cm!(4));
+    sh.set_styled_value(0, 0, "Name", "head");
+    sh.set_col_span(0, 0, 2);
+    sh.set_value(1, 0, "a < b");
+    sh.set_value(1, 1, 1.5);
+    sh.set_value(2, 0, "a < b");
+    sh.set_value(2, 1, true);
+    sh.set_styled_value(3, 0, NaiveDate::from_ymd(2021, 3, 1), "date");
+    sh.set_value(3, 1, Duration::hours(36));
+    sh.set_formula(4, 1, "of:=SUM([.B2:.B3];1)");
+    sh.set_value(4, 1, 2.5);
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("Data & More");
+    sh.set_display(false);
+    wb.push_sheet(sh);
+
+    write_xlsx(&wb, "test_out/test_xlsx.xlsx")?;
+
+    let path = "test_out/test_xlsx.xlsx";
+    let content_types = part(path, "[Content_Types].xml");
+    assert!(content_types.contains("/xl/worksheets/sheet2.xml"));
+
+    let workbook = part(path, "xl/workbook.xml");
+    assert!(
+        workbook.contains("name=\"Data &amp; More\""),
+        "{}",
+        workbook
+    );
+    assert!(workbook.contains("name=\"Data &amp; More (2)\" sheetId=\"2\" state=\"hidden\""));
+
+    let strings = part(path, "xl/sharedStrings.xml");
+    assert!(
+        strings.contains("count=\"3\" uniqueCount=\"2\""),
+        "{}",
+        strings
+    );
+    assert!(strings.contains("<t>a &lt; b</t>"));
+
+    let sheet = part(path, "xl/worksheets/sheet1.xml");
+    assert!(
+        sheet.contains("<col min=\"1\" max=\"1\" width=\"21.59375\" customWidth=\"1\"/>"),
+        "{}",
+        sheet
+    );
+    assert!(sheet.contains("<c r=\"B2\"><v>1.5</v></c>"), "{}", sheet);
+    assert!(sheet.contains("<c r=\"B3\" t=\"b\"><v>1</v></c>"));
+    assert!(sheet.contains("<v>44256</v>"));
+    assert!(sheet.contains("<v>1.5</v>"));
+    assert!(sheet.contains("<f>SUM(B2:B3,1)</f><v>2.5</v>"));
+    assert!(sheet.contains("<mergeCell ref=\"A1:B1\"/>"));
+
+    let styles = part(path, "xl/styles.xml");
+    assert!(
+        styles.contains("formatCode=\"dd&quot;.&quot;mm&quot;.&quot;yyyy\""),
+        "{}",
+        styles
+    );
+    assert!(styles.contains("<b/>"));
+    assert!(styles.contains("<fgColor rgb=\"FFC0C0C0\"/>"));
+    assert!(styles.contains("<left style=\"thin\">"));
+    assert!(styles.contains("numFmtId=\"46\""));
+
+    Ok(())
+}
+
+#[test]
+fn xlsx_from_ods() -> Result<(), OdsError> {
+    let wb = read_ods("tests/orders.ods")?;
+    write_xlsx(&wb, "test_out/orders.xlsx")?;
+
+    let workbook = part("test_out/orders.xlsx", "xl/workbook.xml");
+    assert!(workbook.contains("<sheet "));
+
+    Ok(())
+}
+
+#[test]
+fn xlsx_read() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new();
+
+    wb.add_format(create_date_dmy_format("date_dmy"));
+    let mut style = Style::new_cell_style("head", "");
+    style.text_mut().set_font_bold();
+    style
+        .cell_mut()
+        .set_background_color(Rgb::new(192, 192, 192));
+    wb.add_style(style);
+    wb.add_style(Style::new_cell_style("date", "date_dmy"));
+
+    let mut sh = Sheet::new_with_name("Data");
+    sh.set_col_width(0, cm!(4));
+    sh.set_row_height(2, pt!(30));
+    sh.set_row_visible(3, Visibility::Collapsed);
+    sh.set_styled_value(0, 0, "Name", "head");
+    sh.set_col_span(0, 0, 2);
+    sh.set_value(1, 0, "a < b");
+    sh.set_value(1, 1, 1.5);
+    sh.set_value(2, 0, "a < b");
+    sh.set_value(2, 1, true);
+    sh.set_styled_value(3, 0, NaiveDate::from_ymd(2021, 3, 1), "date");
+    sh.set_value(3, 1, Duration::hours(36));
+    sh.set_formula(4, 1, "of:=SUM([.B2:.B3];1)");
+    sh.set_value(4, 1, 2.5);
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new_with_name("Hidden");
+    sh.set_display(false);
+    sh.set_value(0, 0, NaiveDate::from_ymd(1900, 2, 1));
+    wb.push_sheet(sh);
+
+    write_xlsx(&wb, "test_out/test_xlsx_read.xlsx")?;
+    let wb = read_xlsx("test_out/test_xlsx_read.xlsx")?;
+
+    assert_eq!(wb.num_sheets(), 2);
+    let sh = wb.sheet(0);
+    assert_eq!(sh.name(), "Data");
+    assert_eq!(sh.value(0, 0).as_str_or(""), "Name");
+    assert_eq!(sh.col_span(0, 0), 2);
+    assert_eq!(sh.value(2, 0).as_str_or(""), "a < b");
+    assert_eq!(sh.value(1, 1).as_f64_or(0f64), 1.5);
+    assert!(sh.value(2, 1).as_bool_or(false));
+    assert_eq!(
+        sh.value(3, 0).as_datetime_opt(),
+        Some(NaiveDate::from_ymd(2021, 3, 1).and_hms(0, 0, 0))
+    );
+    assert_eq!(
+        sh.value(3, 1).as_timeduration_opt(),
+        Some(Duration::hours(36))
+    );
+    assert_eq!(
+        sh.formula(4, 1).map(|v| v.as_str()),
+        Some("of:=SUM([.B2:.B3];1)")
+    );
+    assert_eq!(sh.value(4, 1).as_f64_or(0f64), 2.5);
+    assert_eq!(sh.row_visible(3), Visibility::Collapsed);
+
+    let head = wb.style(sh.cell_style(0, 0).unwrap()).unwrap();
+    assert_eq!(
+        head.text().attr("fo:font-weight").map(|v| v.as_str()),
+        Some("bold")
+    );
+    assert_eq!(
+        head.cell().attr("fo:background-color").map(|v| v.as_str()),
+        Some("#c0c0c0")
+    );
+    let date = wb.style(sh.cell_style(3, 0).unwrap()).unwrap();
+    let format = wb.format(date.value_format().unwrap()).unwrap();
+    assert_eq!(format.value_type(), ValueType::DateTime);
+
+    assert_eq!(sh.col_width(&wb, 0), Some(Length::Cm(3.999)));
+    assert_eq!(sh.row_height(&wb, 2), Some(Length::Pt(30.0)));
+
+    let sh = wb.sheet(1);
+    assert!(!sh.display());
+    assert_eq!(
+        sh.value(0, 0).as_datetime_opt(),
+        Some(NaiveDate::from_ymd(1900, 2, 1).and_hms(0, 0, 0))
+    );
+
+    write_xlsx(&wb, "test_out/test_xlsx_read2.xlsx")?;
+    spreadsheet_ods::write_ods(&wb, "test_out/test_xlsx_read.ods")?;
+
+    Ok(())
+}
+
+/// Minimal xlsx with the given styles and sheet data.
+fn write_minimal_xlsx(path: &str, styles: &str, sheet: &str) {
+    use std::io::Write;
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    let options = zip::write::FileOptions::default();
+    let parts = [
+        (
+            "xl/workbook.xml",
+            "<workbook><sheets><sheet name=\"S\" sheetId=\"1\"/></sheets></workbook>".to_string(),
+        ),
+        (
+            "xl/styles.xml",
+            format!("<styleSheet>{}</styleSheet>", styles),
+        ),
+        (
+            "xl/worksheets/sheet1.xml",
+            format!("<worksheet>{}</worksheet>", sheet),
+        ),
+    ];
+    for (name, data) in parts.iter() {
+        zip.start_file(*name, options).unwrap();
+        zip.write_all(data.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn xlsx_read_empty_style_elements() -> Result<(), OdsError> {
+    write_minimal_xlsx(
+        "test_out/test_xlsx_empty.xlsx",
+        "<fonts><font/><font><b/></font></fonts>\
+        <fills><fill/><fill><patternFill patternType=\"solid\">\
+        <fgColor rgb=\"FFFF0000\"/></patternFill></fill></fills>\
+        <borders><border/><border><left style=\"thin\"/></border></borders>\
+        <cellXfs><xf/><xf fontId=\"1\" fillId=\"1\" borderId=\"1\"/></cellXfs>",
+        "<sheetData><row r=\"1\"><c r=\"A1\" s=\"1\"><v>1</v></c></row></sheetData>",
+    );
+    let wb = read_xlsx("test_out/test_xlsx_empty.xlsx")?;
+    let sh = wb.sheet(0);
+    let style = wb.style(sh.cell_style(0, 0).unwrap()).unwrap();
+    assert_eq!(
+        style.text().attr("fo:font-weight").map(|v| v.as_str()),
+        Some("bold")
+    );
+    assert_eq!(
+        style.cell().attr("fo:background-color").map(|v| v.as_str()),
+        Some("#ff0000")
+    );
+    assert!(style.cell().attr("fo:border-left").is_some());
+
+    Ok(())
+}
+
+#[test]
+fn xlsx_read_invalid_col() {
+    write_minimal_xlsx(
+        "test_out/test_xlsx_col.xlsx",
+        "",
+        "<cols><col min=\"20000\" max=\"20001\" width=\"10\" customWidth=\"1\"/></cols>",
+    );
+    assert!(read_xlsx("test_out/test_xlsx_col.xlsx").is_err());
+}
+
+#[test]
+fn xlsx_read_number_formats() -> Result<(), OdsError> {
+    let builtin = [
+        1, 2, 3, 4, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 37, 38, 39, 40, 45, 46,
+        47, 48, 49,
+    ];
+    let mut xfs = String::from("<xf/><xf numFmtId=\"164\"/>");
+    let mut cells = String::from("<c r=\"A1\" s=\"1\"><v>-1</v></c>");
+    for (idx, id) in builtin.iter().enumerate() {
+        xfs.push_str(&format!("<xf numFmtId=\"{}\"/>", id));
+        cells.push_str(&format!(
+            "<c r=\"A{}\" s=\"{}\"><v>1</v></c>",
+            idx + 2,
+            idx + 2
+        ));
+    }
+    write_minimal_xlsx(
+        "test_out/test_xlsx_formats.xlsx",
+        &format!(
+            "<numFmts><numFmt numFmtId=\"164\" formatCode=\"#,##0.00 &quot;kg&quot;;\
+            [Red]-#,##0.00 &quot;kg&quot;\"/></numFmts><cellXfs>{}</cellXfs>",
+            xfs
+        ),
+        &format!("<sheetData><row r=\"1\">{}</row></sheetData>", cells),
+    );
+    let wb = read_xlsx("test_out/test_xlsx_formats.xlsx")?;
+    let sh = wb.sheet(0);
+
+    let style = wb.style(sh.cell_style(0, 0).unwrap()).unwrap();
+    let format = wb.format(style.value_format().unwrap()).unwrap();
+    assert_eq!(format.value_type(), ValueType::Number);
+    assert_eq!(format.text().color(), Ok(Some(Rgb::new(255, 0, 0))));
+    let positive = &format.stylemaps().unwrap()[0];
+    assert_eq!(positive.condition(), "value()>=0");
+    assert!(wb.format(positive.applied_style()).is_some());
+
+    for (idx, id) in builtin.iter().enumerate() {
+        let style = wb.style(sh.cell_style(idx as u32 + 1, 0).unwrap()).unwrap();
+        assert!(style.value_format().is_some(), "{}", id);
+    }
+    let style = wb.style(sh.cell_style(24, 0).unwrap()).unwrap();
+    let format = wb.format(style.value_format().unwrap()).unwrap();
+    assert_eq!(format.value_type(), ValueType::TimeDuration);
+
+    Ok(())
+}